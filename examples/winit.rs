@@ -1,12 +1,13 @@
 use std::rc::Rc;
 
 use anyhow::{Result, anyhow};
-use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+use tray_controls::{
+    CheckMenuKind, CommandRegistry, MenuControl, MenuManager, TrayAction, TrayClick,
+    TrayController, TrayEvent, icons,
+};
 use tray_icon::{
-    TrayIcon, TrayIconBuilder,
-    menu::{
-        CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu,
-    },
+    MouseButton, TrayIcon, TrayIconBuilder,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuId, MenuItem, PredefinedMenuItem, Submenu},
 };
 use winit::{
     application::ApplicationHandler,
@@ -19,11 +20,11 @@ fn main() -> Result<()> {
     let event_loop = EventLoop::<UserEvent>::with_user_event().build()?;
 
     let proxy = event_loop.create_proxy();
-    MenuEvent::set_event_handler(Some(move |event| {
+    TrayController::<MenuGroup>::set_event_handler(move |event| {
         proxy
-            .send_event(UserEvent::MenuEvent(event))
-            .expect("Failed to send MenuEvent");
-    }));
+            .send_event(UserEvent::TrayEvent(event))
+            .expect("Failed to send TrayEvent");
+    });
 
     let proxy = event_loop.create_proxy();
     let mut app = App::new(proxy)?;
@@ -40,28 +41,41 @@ enum MenuGroup {
     CheckBoxChange,
 }
 
+#[derive(Clone, Copy)]
+enum Command {
+    Quit,
+}
+
 #[derive(Debug)]
 enum UserEvent {
     Exit,
-    MenuEvent(MenuEvent),
+    TrayEvent(TrayEvent),
 }
 
 struct App {
     event_loop_proxy: EventLoopProxy<UserEvent>,
-    menu_manager: MenuManager<MenuGroup>,
-    tray: Option<TrayIcon>,
+    controller: TrayController<MenuGroup>,
+    commands: CommandRegistry<Command>,
 }
 
 impl App {
     fn new(event_loop_proxy: EventLoopProxy<UserEvent>) -> Result<Self> {
         let mut menu_manager: MenuManager<MenuGroup> = MenuManager::new();
-        let menu = create_menu(&mut menu_manager)?;
+        let mut commands = CommandRegistry::new();
+        let menu = create_menu(&mut menu_manager, &mut commands)?;
         let tray = create_tray(menu)?;
 
+        let mut controller = TrayController::new(tray, menu_manager);
+        controller.bind(TrayClick::Click(MouseButton::Left), TrayAction::ShowMenu);
+        controller.bind(
+            TrayClick::DoubleClick(MouseButton::Left),
+            TrayAction::ToggleWindow,
+        );
+
         Ok(App {
             event_loop_proxy,
-            menu_manager,
-            tray: Some(tray),
+            controller,
+            commands,
         })
     }
 }
@@ -70,11 +84,8 @@ impl ApplicationHandler<UserEvent> for App {
     fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
-        match event {
-            WindowEvent::CloseRequested => {
-                event_loop.exit();
-            }
-            _ => {}
+        if let WindowEvent::CloseRequested = event {
+            event_loop.exit();
         }
     }
 
@@ -83,9 +94,16 @@ impl ApplicationHandler<UserEvent> for App {
             UserEvent::Exit => {
                 event_loop.exit();
             }
-            UserEvent::MenuEvent(event) => {
-                let click_menu_id = event.id();
-                self.menu_manager.update(click_menu_id, |menu_control| {
+            UserEvent::TrayEvent(TrayEvent::Tray(event)) => {
+                if let Some(action) = self.controller.handle_tray_event(&event) {
+                    println!("Tray Action: {action:?}\n");
+                    // TODO: toggle your main window on `TrayAction::ToggleWindow`
+                }
+            }
+            UserEvent::TrayEvent(TrayEvent::Menu(event)) => {
+                let command = self.commands.resolve(&event);
+                let tray = self.controller.tray().clone();
+                self.controller.handle_menu_event(&event, |menu_control| {
                     if let Some(menu_control) = menu_control {
                         match menu_control {
                             MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
@@ -96,16 +114,13 @@ impl ApplicationHandler<UserEvent> for App {
                                     );
                                 }
                                 CheckMenuKind::CheckBox(check_menu, group) => {
-                                    match group {
-                                        MenuGroup::CheckBoxChange => {
-                                            println!(
-                                                "Click the Check Box Menu(Change): {:?}\n",
-                                                check_menu.text()
-                                            );
-                                            // TODO: do something
-                                        }
-                                        // your check box menu group id
-                                        _ => {}
+                                    // your check box menu group id
+                                    if let MenuGroup::CheckBoxChange = group {
+                                        println!(
+                                            "Click the Check Box Menu(Change): {:?}\n",
+                                            check_menu.text()
+                                        );
+                                        // TODO: do something
                                     }
                                 }
                                 CheckMenuKind::Radio(checked_menu, default_menu_id, group) => {
@@ -133,9 +148,7 @@ impl ApplicationHandler<UserEvent> for App {
 
                                             let icon = create_icon(color);
 
-                                            if let Some(tray) = &self.tray {
-                                                let _ = tray.set_icon(Some(icon));
-                                            }
+                                            let _ = tray.set_icon(Some(icon));
                                         }
                                         MenuGroup::RadioLanguage => {
                                             println!(
@@ -148,18 +161,25 @@ impl ApplicationHandler<UserEvent> for App {
                                     }
                                 }
                             },
+                            MenuControl::CheckIconMenu(check_icon_kind) => {
+                                println!(
+                                    "Click the Check Icon Menu: {:?}\n",
+                                    check_icon_kind.item().icon_menu().text()
+                                );
+                            }
                             MenuControl::IconMenu(icon_menu) => {
                                 println!("Click Icon Menu: {:?}\n", icon_menu.text());
                                 // TODO: do something
                             }
                             MenuControl::MenuItem(menu_item) => {
                                 println!("Click Menu Item: {:?}\n", menu_item.text());
-                                if click_menu_id.0 == "quit" {
-                                    let _ = self.event_loop_proxy.send_event(UserEvent::Exit);
+                                match command {
+                                    Some(Command::Quit) => {
+                                        let _ = self.event_loop_proxy.send_event(UserEvent::Exit);
+                                    }
+                                    // TODO: handle your commands
+                                    None => {}
                                 }
-                                // else if click_menu_id.0 == "your menu id" {
-                                //     // TODO: do something
-                                // }
                             }
                         }
                     }
@@ -169,12 +189,19 @@ impl ApplicationHandler<UserEvent> for App {
     }
 }
 
-fn create_menu(menu_manager: &mut MenuManager<MenuGroup>) -> Result<Menu> {
+fn create_menu(
+    menu_manager: &mut MenuManager<MenuGroup>,
+    commands: &mut CommandRegistry<Command>,
+) -> Result<Menu> {
     let separator_menu_item = PredefinedMenuItem::separator();
 
     let quit_menu_id = MenuId::new("quit");
     let quit_menu_item = MenuItem::with_id(quit_menu_id, "Quit", true, None);
-    menu_manager.insert(MenuControl::MenuItem(quit_menu_item.clone()));
+    commands.insert(
+        menu_manager,
+        MenuControl::MenuItem(quit_menu_item.clone()),
+        Command::Quit,
+    );
 
     // Color Radio Check Menu
     let color_sub_menu_item = {
@@ -294,14 +321,7 @@ fn create_tray(menu: Menu) -> Result<TrayIcon> {
 }
 
 fn create_icon(pixiel: [u8; 4]) -> tray_icon::Icon {
-    let (width, height) = (16_u32, 16_u32);
-    let pixel_count = (width * height) as usize;
-
-    let mut image_data = Vec::with_capacity(pixel_count * 4);
-
-    for _ in 0..pixel_count {
-        image_data.extend_from_slice(&pixiel);
-    }
-
-    tray_icon::Icon::from_rgba(image_data, width, height).expect("Failed to create icon")
+    icons::solid(pixiel, icons::TRAY_ICON_SIZE)
+        .to_tray_icon()
+        .expect("Failed to create icon")
 }
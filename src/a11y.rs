@@ -0,0 +1,158 @@
+/// Accessibility settings of the desktop that affect the tray icon
+///
+/// See [`TrayController::a11y_prefs`](crate::TrayController::a11y_prefs). With reduced
+/// motion, the attention icon is shown steadily instead of flashing. With high contrast,
+/// the icon set of
+/// [`TrayController::set_high_contrast_icon_set`](crate::TrayController::set_high_contrast_icon_set)
+/// is shown.
+///
+/// # Example
+/// ```
+/// use tray_controls::A11yPrefs;
+///
+/// let prefs = A11yPrefs::detect();
+/// if prefs.is_reduced_motion() {
+///     println!("no animations");
+/// }
+///
+/// // e.g. from an in-app setting
+/// let prefs = prefs.with_reduced_motion(true);
+/// assert!(prefs.is_reduced_motion());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct A11yPrefs {
+    high_contrast: bool,
+    reduced_motion: bool,
+}
+
+impl A11yPrefs {
+    /// Reads the settings of the desktop, a setting that can't be read is off.
+    ///
+    /// The settings are not watched, detect them again e.g. when the app regains focus.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows:** The high contrast flag and the `MinAnimate` window metric, read with `reg`.
+    /// - **macOS:** The "Increase contrast" and "Reduce motion" accessibility settings, read
+    ///   with `defaults`.
+    /// - **Linux:** The GNOME high contrast and animation settings, read with `gsettings`.
+    ///   Other desktops are reported as off.
+    pub fn detect() -> Self {
+        A11yPrefs {
+            high_contrast: platform::is_high_contrast().unwrap_or(false),
+            reduced_motion: platform::is_reduced_motion().unwrap_or(false),
+        }
+    }
+
+    pub fn with_high_contrast(mut self, high_contrast: bool) -> Self {
+        self.high_contrast = high_contrast;
+        self
+    }
+
+    pub fn with_reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    pub fn is_high_contrast(&self) -> bool {
+        self.high_contrast
+    }
+
+    pub fn is_reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const HIGH_CONTRAST_KEY: &str = r"HKCU\Control Panel\Accessibility\HighContrast";
+    const WINDOW_METRICS_KEY: &str = r"HKCU\Control Panel\Desktop\WindowMetrics";
+    /// `HCF_HIGHCONTRASTON`
+    const HIGH_CONTRAST_ON: u32 = 0x1;
+    /// Keeps `reg.exe` from flashing a console window in GUI apps
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// Reads a registry value, `None` if it doesn't exist.
+    fn reg_value(key: &str, name: &str) -> Option<String> {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", name])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        // `    Flags    REG_SZ    126`
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find(|line| line.trim_start().starts_with(name))?
+            .split_whitespace()
+            .nth(2)
+            .map(str::to_owned)
+    }
+
+    pub(super) fn is_high_contrast() -> Option<bool> {
+        let flags = reg_value(HIGH_CONTRAST_KEY, "Flags")?.parse::<u32>().ok()?;
+        Some(flags & HIGH_CONTRAST_ON != 0)
+    }
+
+    pub(super) fn is_reduced_motion() -> Option<bool> {
+        reg_value(WINDOW_METRICS_KEY, "MinAnimate").map(|value| value == "0")
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    const UNIVERSAL_ACCESS: &str = "com.apple.universalaccess";
+
+    fn read_flag(key: &str) -> Option<bool> {
+        let output = Command::new("defaults")
+            .args(["read", UNIVERSAL_ACCESS, key])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        Some(String::from_utf8_lossy(&output.stdout).trim() == "1")
+    }
+
+    pub(super) fn is_high_contrast() -> Option<bool> {
+        read_flag("increaseContrast")
+    }
+
+    pub(super) fn is_reduced_motion() -> Option<bool> {
+        read_flag("reduceMotion")
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    fn gsettings(schema: &str, key: &str) -> Option<String> {
+        let output = Command::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
+    pub(super) fn is_high_contrast() -> Option<bool> {
+        // GNOME 43 and later, older versions switch to a high contrast theme
+        match gsettings("org.gnome.desktop.a11y.interface", "high-contrast") {
+            Some(high_contrast) => Some(high_contrast == "true"),
+            None => gsettings("org.gnome.desktop.interface", "gtk-theme")
+                .map(|theme| theme.contains("HighContrast")),
+        }
+    }
+
+    pub(super) fn is_reduced_motion() -> Option<bool> {
+        gsettings("org.gnome.desktop.interface", "enable-animations")
+            .map(|enabled| enabled == "false")
+    }
+}
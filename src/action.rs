@@ -0,0 +1,287 @@
+#[cfg(feature = "open")]
+use std::path::PathBuf;
+use std::process::Command;
+#[cfg(feature = "clipboard")]
+use std::rc::Rc;
+
+use crate::{Error, FxIndexMap};
+
+/// Callback providing the text of a clipboard action at click time
+#[cfg(feature = "clipboard")]
+pub(crate) type ClipboardProvider = Rc<dyn Fn() -> String>;
+
+/// Declarative action of a menu control, see [`MenuManager::set_action`](crate::MenuManager::set_action)
+///
+/// Launcher-style menus attach built-in actions and need no handler code. Anything else
+/// is a [`Action::Custom`] action, handed back to the application as a [`MenuAction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    /// Sets an environment variable for the programs started by later [`Action::Exec`] actions
+    SetEnv { key: String, value: String },
+    /// Starts a program without waiting for it
+    Exec { program: String, args: Vec<String> },
+    /// Opens a URL in the default browser (**requires the `open` feature**)
+    ///
+    /// The platform opener is started: `rundll32` on **Windows**, `open` on **macOS**
+    /// and `xdg-open` on **Linux**.
+    #[cfg(feature = "open")]
+    OpenUrl(String),
+    /// Shows a file or folder in the file manager (**requires the `open` feature**)
+    ///
+    /// The file is selected on **Windows** and **macOS**, **Linux** opens its folder.
+    #[cfg(feature = "open")]
+    RevealPath(PathBuf),
+    /// Copies text to the clipboard (**requires the `clipboard` feature**)
+    ///
+    /// The clipboard tool of the platform is started: `clip` on **Windows**, `pbcopy`
+    /// on **macOS**, and `wl-copy`, `xclip` or `xsel` on **Linux**.
+    #[cfg(feature = "clipboard")]
+    CopyToClipboard(ClipboardText),
+    /// Asks for a line of text with a native input dialog, e.g. a server URL
+    /// (**requires the `dialogs` feature**)
+    ///
+    /// The entered text is handed back as [`MenuAction::Text`]. The dialog is modal, the
+    /// calling thread waits until it is closed.
+    ///
+    /// PowerShell is started on **Windows**, `osascript` on **macOS**, and `zenity` or
+    /// `kdialog` on **Linux**. An empty text can't be entered on **Windows**, it is
+    /// reported as [`MenuAction::Cancelled`].
+    #[cfg(feature = "dialogs")]
+    PromptForText {
+        title: String,
+        prompt: String,
+        default: String,
+    },
+    /// Named action handled by the application
+    Custom(String),
+}
+
+/// Text of [`Action::CopyToClipboard`] (**requires the `clipboard` feature**)
+#[cfg(feature = "clipboard")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardText {
+    Text(String),
+    /// Name of a provider registered with
+    /// [`MenuManager::set_clipboard_provider`](crate::MenuManager::set_clipboard_provider),
+    /// called at click time, e.g. to get the current local IP
+    Provider(String),
+}
+
+/// State shared by the actions of a menu manager
+#[derive(Clone, Default)]
+pub(crate) struct ActionContext {
+    /// Variables of the [`Action::SetEnv`] actions
+    pub(crate) env: FxIndexMap<String, String>,
+    #[cfg(feature = "clipboard")]
+    pub(crate) clipboard_providers: FxIndexMap<String, ClipboardProvider>,
+}
+
+/// Outcome of [`MenuManager::run_action`](crate::MenuManager::run_action)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    /// The built-in action ran
+    Done,
+    /// The application handles the named action
+    Custom(String),
+    /// Text entered for [`Action::PromptForText`] (**requires the `dialogs` feature**)
+    #[cfg(feature = "dialogs")]
+    Text(String),
+    /// The dialog was cancelled (**requires the `dialogs` feature**)
+    #[cfg(feature = "dialogs")]
+    Cancelled,
+}
+
+impl Action {
+    /// Parses an action from a configuration value (**requires the `serde` feature**).
+    ///
+    /// Accepted forms are `{ "set_env": "KEY", "value": "..." }`,
+    /// `{ "exec": "program", "args": ["..."] }` and `{ "custom": "name" }`, and with the
+    /// `open` feature `{ "open_url": "..." }` and `{ "reveal_path": "..." }`, and with the
+    /// `clipboard` feature `{ "copy": "text" }` and `{ "copy_provider": "name" }`, and with the
+    /// `dialogs` feature `{ "prompt": "...", "title": "...", "default": "..." }`.
+    /// Returns `None` for any other value.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json::json;
+    /// use tray_controls::{Action, MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("logs", "Show Logs", true, None)));
+    ///
+    /// // e.g. the `actions` table of a config file
+    /// let actions = json!({ "logs": { "exec": "xdg-open", "args": ["/var/log"] } });
+    /// for (id, value) in actions.as_object().unwrap() {
+    ///     manager.set_action(&MenuId::new(id), Action::from_value(value));
+    /// }
+    ///
+    /// assert!(matches!(manager.action(&MenuId::new("logs")), Some(Action::Exec { .. })));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_value(value: &serde_json::Value) -> Option<Action> {
+        let string = |key: &str| value.get(key).and_then(|value| value.as_str());
+
+        if let Some(key) = string("set_env") {
+            Some(Action::SetEnv {
+                key: key.to_owned(),
+                value: string("value")?.to_owned(),
+            })
+        } else if let Some(program) = string("exec") {
+            let args = match value.get("args") {
+                Some(args) => args
+                    .as_array()?
+                    .iter()
+                    .map(|arg| arg.as_str().map(ToOwned::to_owned))
+                    .collect::<Option<_>>()?,
+                None => Vec::new(),
+            };
+            Some(Action::Exec {
+                program: program.to_owned(),
+                args,
+            })
+        } else {
+            #[cfg(feature = "open")]
+            if let Some(url) = string("open_url") {
+                return Some(Action::OpenUrl(url.to_owned()));
+            } else if let Some(path) = string("reveal_path") {
+                return Some(Action::RevealPath(PathBuf::from(path)));
+            }
+
+            #[cfg(feature = "dialogs")]
+            if let Some(prompt) = string("prompt") {
+                return Some(Action::PromptForText {
+                    title: string("title").unwrap_or_default().to_owned(),
+                    prompt: prompt.to_owned(),
+                    default: string("default").unwrap_or_default().to_owned(),
+                });
+            }
+
+            #[cfg(feature = "clipboard")]
+            if let Some(text) = string("copy") {
+                return Some(Action::CopyToClipboard(ClipboardText::Text(
+                    text.to_owned(),
+                )));
+            } else if let Some(name) = string("copy_provider") {
+                return Some(Action::CopyToClipboard(ClipboardText::Provider(
+                    name.to_owned(),
+                )));
+            }
+
+            string("custom").map(|name| Action::Custom(name.to_owned()))
+        }
+    }
+
+    pub(crate) fn run(&self, context: &mut ActionContext) -> Result<MenuAction, Error> {
+        match self {
+            Action::SetEnv { key, value } => {
+                context.env.insert(key.clone(), value.clone());
+            }
+            Action::Exec { program, args } => {
+                let mut command = Command::new(program);
+                command.args(args).envs(context.env.iter());
+                spawn_detached(command)?;
+            }
+            #[cfg(feature = "open")]
+            Action::OpenUrl(url) => spawn_detached(open::url_command(url))?,
+            #[cfg(feature = "open")]
+            Action::RevealPath(path) => spawn_detached(open::reveal_command(path))?,
+            #[cfg(feature = "clipboard")]
+            Action::CopyToClipboard(text) => {
+                let text = match text {
+                    ClipboardText::Text(text) => text.clone(),
+                    ClipboardText::Provider(name) => {
+                        let provider = context.clipboard_providers.get(name).ok_or_else(|| {
+                            std::io::Error::new(
+                                std::io::ErrorKind::NotFound,
+                                format!("clipboard provider `{name}` is not registered"),
+                            )
+                        })?;
+                        provider()
+                    }
+                };
+                crate::clipboard::copy(&text)?;
+            }
+            #[cfg(feature = "dialogs")]
+            Action::PromptForText {
+                title,
+                prompt,
+                default,
+            } => {
+                return Ok(match crate::dialog::prompt_text(title, prompt, default)? {
+                    Some(text) => MenuAction::Text(text),
+                    None => MenuAction::Cancelled,
+                });
+            }
+            Action::Custom(name) => return Ok(MenuAction::Custom(name.clone())),
+        }
+
+        Ok(MenuAction::Done)
+    }
+}
+
+/// Starts a command without waiting for it.
+fn spawn_detached(mut command: Command) -> Result<(), Error> {
+    let mut child = command.spawn()?;
+    // Reap the child once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(feature = "open")]
+mod open {
+    use std::path::Path;
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    pub(super) fn url_command(url: &str) -> Command {
+        // `cmd /C start` would split the URL at `&`
+        let mut command = Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", url]);
+        command
+    }
+
+    #[cfg(target_os = "windows")]
+    pub(super) fn reveal_command(path: &Path) -> Command {
+        use std::os::windows::process::CommandExt;
+
+        // explorer parses `/select,` itself, the path must not be quoted separately
+        let mut command = Command::new("explorer");
+        command.raw_arg(format!("/select,\"{}\"", path.display()));
+        command
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn url_command(url: &str) -> Command {
+        let mut command = Command::new("open");
+        command.arg(url);
+        command
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn reveal_command(path: &Path) -> Command {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub(super) fn url_command(url: &str) -> Command {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        command
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub(super) fn reveal_command(path: &Path) -> Command {
+        // There is no portable way to select a file, open its folder instead
+        let folder = match path.is_dir() {
+            true => path,
+            false => path.parent().unwrap_or(path),
+        };
+        let mut command = Command::new("xdg-open");
+        command.arg(folder);
+        command
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+use tray_icon::Icon;
+
+/// Icon shown by the tray icon, see [`TrayController::attention`](crate::TrayController::attention)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ShownIcon {
+    Normal,
+    Attention,
+}
+
+/// Normal and attention icons of a tray icon
+pub(crate) struct IconState {
+    icon: Option<Icon>,
+    attention_icon: Option<Icon>,
+    flash_interval: Option<Duration>,
+    /// Shows the attention icon steadily instead of flashing it
+    reduced_motion: bool,
+    /// When attention was requested, `None` while it is off
+    attention_since: Option<Instant>,
+    /// `None` when the shown icon must be set again
+    shown: Option<ShownIcon>,
+}
+
+impl Default for IconState {
+    fn default() -> Self {
+        // The icon of the tray icon builder is kept until the icons change
+        IconState {
+            icon: None,
+            attention_icon: None,
+            flash_interval: None,
+            reduced_motion: false,
+            attention_since: None,
+            shown: Some(ShownIcon::Normal),
+        }
+    }
+}
+
+impl IconState {
+    pub(crate) fn set_icon(&mut self, icon: Option<Icon>) {
+        self.icon = icon;
+        self.shown = None;
+    }
+
+    pub(crate) fn set_attention_icon(
+        &mut self,
+        icon: Option<Icon>,
+        flash_interval: Option<Duration>,
+    ) {
+        self.attention_icon = icon;
+        self.flash_interval = flash_interval.filter(|interval| !interval.is_zero());
+        if self.shown == Some(ShownIcon::Attention) {
+            self.shown = None;
+        }
+    }
+
+    pub(crate) fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+    }
+
+    fn flash_interval(&self) -> Option<Duration> {
+        self.flash_interval.filter(|_| !self.reduced_motion)
+    }
+
+    pub(crate) fn set_attention(&mut self, enabled: bool, now: Instant) {
+        match (enabled, self.attention_since) {
+            (true, None) => self.attention_since = Some(now),
+            (false, Some(_)) => self.attention_since = None,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn is_attention(&self) -> bool {
+        self.attention_since.is_some()
+    }
+
+    /// Gets the icon to show at `now`, `None` if the shown icon is already up to date.
+    pub(crate) fn next_icon(&mut self, now: Instant) -> Option<Option<Icon>> {
+        let wanted = match (self.attention_since, &self.attention_icon) {
+            (Some(since), Some(_)) => match self.flash_interval() {
+                // Flashing starts with the attention icon
+                Some(interval) if (now - since).as_nanos() / interval.as_nanos() % 2 == 1 => {
+                    ShownIcon::Normal
+                }
+                _ => ShownIcon::Attention,
+            },
+            _ => ShownIcon::Normal,
+        };
+
+        if self.shown == Some(wanted) {
+            return None;
+        }
+
+        self.shown = Some(wanted);
+        Some(match wanted {
+            ShownIcon::Normal => self.icon.clone(),
+            ShownIcon::Attention => self.attention_icon.clone(),
+        })
+    }
+
+    /// Gets when the flashing icon changes next, `None` if it is not flashing.
+    pub(crate) fn next_flash(&self, now: Instant) -> Option<Instant> {
+        let since = self.attention_since?;
+        let interval = self.flash_interval()?;
+        self.attention_icon.as_ref()?;
+
+        let elapsed = (now - since).as_nanos() / interval.as_nanos() + 1;
+        Some(since + interval * u32::try_from(elapsed).unwrap_or(u32::MAX))
+    }
+}
@@ -0,0 +1,71 @@
+use muda::{MenuId, MenuItemKind};
+
+use crate::FxIndexSet;
+
+/// Result of [`MenuManager::audit`](crate::MenuManager::audit)
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuControl, MenuManager};
+/// use tray_icon::menu::{Menu, MenuId, MenuItem};
+///
+/// let menu = Menu::new();
+/// let quit = MenuItem::with_id("quit", "Quit", true, None);
+/// menu.append(&quit).unwrap();
+/// menu.append(&MenuItem::with_id("about", "About", true, None)).unwrap();
+///
+/// let mut manager = MenuManager::<()>::new();
+/// manager.insert(MenuControl::MenuItem(quit));
+/// manager.insert(MenuControl::MenuItem(MenuItem::with_id("help", "Help", true, None)));
+///
+/// let report = manager.audit(&menu);
+/// assert_eq!(report.unregistered(), [MenuId::new("about")]);
+/// assert_eq!(report.detached(), [MenuId::new("help")]);
+/// assert!(!report.is_clean());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    unregistered: Vec<MenuId>,
+    detached: Vec<MenuId>,
+}
+
+impl AuditReport {
+    pub(crate) fn new(unregistered: Vec<MenuId>, detached: Vec<MenuId>) -> Self {
+        AuditReport {
+            unregistered,
+            detached,
+        }
+    }
+
+    /// Gets the live menu items that the menu manager doesn't know, in menu order.
+    ///
+    /// Submenus and predefined menu items are never reported.
+    pub fn unregistered(&self) -> &[MenuId] {
+        &self.unregistered
+    }
+
+    /// Gets the registered menu controls that aren't in the live menu, in registration order.
+    ///
+    /// They are kept alive by the menu manager, but can no longer be clicked.
+    pub fn detached(&self) -> &[MenuId] {
+        &self.detached
+    }
+
+    /// Checks if the live menu and the registrations match.
+    pub fn is_clean(&self) -> bool {
+        self.unregistered.is_empty() && self.detached.is_empty()
+    }
+}
+
+/// Collects the IDs of the live menu items, descending into submenus.
+pub(crate) fn collect_live_ids(items: Vec<MenuItemKind>, live_ids: &mut FxIndexSet<MenuId>) {
+    for item in items {
+        match item {
+            MenuItemKind::Submenu(submenu) => collect_live_ids(submenu.items(), live_ids),
+            MenuItemKind::Predefined(_) => {}
+            item => {
+                live_ids.insert(item.id().clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, MenuId};
+
+use crate::{CheckMenuKind, Error, MenuControl, MenuManager};
+
+/// Prebuilt "Start at Login" checkbox backed by the autostart mechanism of the platform
+///
+/// The checkbox is checked from the platform when it is created. [`AutoStart::handle`]
+/// writes a click back, and unchecks the checkbox again if writing fails.
+///
+/// ## Platform-specific:
+///
+/// - **Windows:** A value named after the app in the `HKCU\...\CurrentVersion\Run` registry key.
+/// - **macOS:** A LaunchAgent `~/Library/LaunchAgents/{app_name}.plist`, so prefer a
+///   reverse-DNS app name like `com.example.myapp`.
+/// - **Linux:** A desktop entry `~/.config/autostart/{app_name}.desktop`.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{AutoStart, MenuManager, MenuParent};
+/// use tray_icon::menu::Menu;
+///
+/// let mut manager = MenuManager::<()>::new();
+/// let tray_menu = Menu::new();
+/// manager.insert_parent(MenuParent::Menu(tray_menu.clone()));
+///
+/// let autostart = AutoStart::new("autostart", "com.example.myapp")
+///     .unwrap()
+///     .with_args(["--minimized"]);
+/// autostart.insert_into(&mut manager, tray_menu.id()).unwrap();
+///
+/// // In the menu event handler, after `MenuManager::update`
+/// # let clicked_id = autostart.menu_id().clone();
+/// if let Some(result) = autostart.handle(&mut manager, &clicked_id) {
+///     result.unwrap();
+/// }
+/// ```
+pub struct AutoStart {
+    app_name: String,
+    program: PathBuf,
+    args: Vec<String>,
+    check_menu: Rc<CheckMenuItem>,
+}
+
+impl AutoStart {
+    /// Creates the checkbox for the current executable, labelled "Start at Login".
+    pub fn new<I: Into<MenuId>>(id: I, app_name: &str) -> Result<Self, Error> {
+        let autostart = AutoStart {
+            app_name: app_name.to_owned(),
+            program: std::env::current_exe()?,
+            args: Vec::new(),
+            check_menu: Rc::new(CheckMenuItem::with_id(
+                id,
+                "Start at Login",
+                true,
+                false,
+                None,
+            )),
+        };
+        autostart.check_menu.set_checked(autostart.is_enabled()?);
+        Ok(autostart)
+    }
+
+    pub fn with_text(self, text: &str) -> Self {
+        self.check_menu.set_text(text);
+        self
+    }
+
+    /// Sets the arguments the app is started with at login, e.g. `--minimized`.
+    ///
+    /// An already enabled autostart keeps its arguments until it is enabled again.
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn menu_id(&self) -> &MenuId {
+        self.check_menu.id()
+    }
+
+    pub fn menu_control<G>(&self) -> MenuControl<G> {
+        MenuControl::CheckMenu(CheckMenuKind::Separate(self.check_menu.clone()))
+    }
+
+    /// Inserts the checkbox into a registered parent menu.
+    pub fn insert_into<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+    ) -> Result<(), Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        manager.insert_into(parent_id, self.menu_control(), None)?;
+        Ok(())
+    }
+
+    /// Checks if the app is started at login.
+    pub fn is_enabled(&self) -> Result<bool, Error> {
+        Ok(platform::is_enabled(&self.app_name)?)
+    }
+
+    /// Enables or disables starting the app at login, without changing the checkbox.
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), Error> {
+        if enabled {
+            platform::enable(&self.app_name, &self.program, &self.args)?;
+        } else if self.is_enabled()? {
+            platform::disable(&self.app_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles a menu click, returns `None` if the menu ID is not the checkbox.
+    ///
+    /// Writes the checked state to the platform. If that fails, the checkbox goes back to
+    /// the state of the platform and the error is returned.
+    pub fn handle<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        menu_id: &MenuId,
+    ) -> Option<Result<(), Error>>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        if menu_id != self.menu_id() {
+            return None;
+        }
+
+        let result = self.set_enabled(self.check_menu.is_checked());
+        if result.is_err() {
+            let _ = self.refresh(manager);
+        }
+
+        Some(result)
+    }
+
+    /// Checks the checkbox from the platform, e.g. after the user changed it in the OS settings.
+    pub fn refresh<G>(&self, manager: &mut MenuManager<G>) -> Result<bool, Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let enabled = self.is_enabled()?;
+        manager.batch(|batch| {
+            batch.set_checked(self.menu_id(), enabled);
+        });
+        Ok(enabled)
+    }
+}
+
+/// Quotes the program and its arguments into one command line.
+#[cfg(not(target_os = "macos"))]
+fn command_line(program: &std::path::Path, args: &[String], quote: fn(&str) -> String) -> String {
+    std::iter::once(quote(&program.to_string_lossy()))
+        .chain(args.iter().map(|arg| quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> std::io::Result<PathBuf> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "HOME is not set"))
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::io;
+    use std::os::windows::process::CommandExt;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    /// Keeps `reg.exe` from flashing a console window in GUI apps
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    fn reg(args: &[&str]) -> io::Result<bool> {
+        Ok(Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?
+            .success())
+    }
+
+    fn quote(arg: &str) -> String {
+        format!("\"{arg}\"")
+    }
+
+    pub(super) fn is_enabled(app_name: &str) -> io::Result<bool> {
+        reg(&["query", RUN_KEY, "/v", app_name])
+    }
+
+    pub(super) fn enable(app_name: &str, program: &Path, args: &[String]) -> io::Result<()> {
+        let command_line = super::command_line(program, args, quote);
+        match reg(&[
+            "add",
+            RUN_KEY,
+            "/v",
+            app_name,
+            "/t",
+            "REG_SZ",
+            "/d",
+            &command_line,
+            "/f",
+        ])? {
+            true => Ok(()),
+            false => Err(io::Error::other("failed to write the Run registry key")),
+        }
+    }
+
+    pub(super) fn disable(app_name: &str) -> io::Result<()> {
+        match reg(&["delete", RUN_KEY, "/v", app_name, "/f"])? {
+            true => Ok(()),
+            false => Err(io::Error::other("failed to delete the Run registry value")),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn plist_path(app_name: &str) -> io::Result<PathBuf> {
+        Ok(super::home_dir()?
+            .join("Library/LaunchAgents")
+            .join(format!("{app_name}.plist")))
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub(super) fn is_enabled(app_name: &str) -> io::Result<bool> {
+        plist_path(app_name)?.try_exists()
+    }
+
+    pub(super) fn enable(app_name: &str, program: &Path, args: &[String]) -> io::Result<()> {
+        let path = plist_path(app_name)?;
+        let arguments: String = std::iter::once(program.to_string_lossy().into_owned())
+            .chain(args.iter().cloned())
+            .map(|arg| format!("        <string>{}</string>\n", escape(&arg)))
+            .collect();
+
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            escape(app_name)
+        );
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, plist)
+    }
+
+    pub(super) fn disable(app_name: &str) -> io::Result<()> {
+        std::fs::remove_file(plist_path(app_name)?)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn desktop_entry_path(app_name: &str) -> io::Result<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(config_dir) if !config_dir.is_empty() => PathBuf::from(config_dir),
+            _ => super::home_dir()?.join(".config"),
+        };
+        Ok(config_dir
+            .join("autostart")
+            .join(format!("{app_name}.desktop")))
+    }
+
+    /// Quotes an argument of the `Exec` key, see the Desktop Entry Specification.
+    fn quote(arg: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in arg.chars() {
+            match c {
+                '"' | '`' | '$' | '\\' => quoted.push('\\'),
+                // `%` starts a field code
+                '%' => quoted.push('%'),
+                _ => {}
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    pub(super) fn is_enabled(app_name: &str) -> io::Result<bool> {
+        desktop_entry_path(app_name)?.try_exists()
+    }
+
+    pub(super) fn enable(app_name: &str, program: &Path, args: &[String]) -> io::Result<()> {
+        let path = desktop_entry_path(app_name)?;
+        let desktop_entry = format!(
+            "[Desktop Entry]\nType=Application\nName={app_name}\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+            super::command_line(program, args, quote)
+        );
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, desktop_entry)
+    }
+
+    pub(super) fn disable(app_name: &str) -> io::Result<()> {
+        std::fs::remove_file(desktop_entry_path(app_name)?)
+    }
+}
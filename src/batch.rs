@@ -0,0 +1,163 @@
+use std::hash::Hash;
+
+use indexmap::IndexMap;
+
+use muda::MenuId;
+
+use crate::{CheckRef, MenuControl, MenuManager};
+
+struct ItemState {
+    checked: Option<bool>,
+    enabled: bool,
+    text: String,
+}
+
+/// Batch of menu changes, see [`MenuManager::batch`]
+///
+/// Changes are applied to the menu controls right away, but radio groups, "Select all"
+/// checkboxes and dependencies are only brought back to a consistent state on commit.
+pub struct Batch<'a, G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    manager: &'a MenuManager<G>,
+    before: IndexMap<MenuId, ItemState>,
+    touched: Vec<MenuId>,
+}
+
+impl<'a, G> Batch<'a, G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub(crate) fn new(manager: &'a MenuManager<G>) -> Self {
+        Batch {
+            manager,
+            before: IndexMap::new(),
+            touched: Vec::new(),
+        }
+    }
+
+    /// Sets the checked state of a check menu, returns `false` if there is none.
+    ///
+    /// If several radios of a group are checked, the last one wins on commit.
+    pub fn set_checked(&mut self, menu_id: &MenuId, checked: bool) -> bool {
+        let Some(menu) = self.touch(menu_id) else {
+            return false;
+        };
+
+        menu.set_checked(checked)
+    }
+
+    /// Toggles the checked state of a check menu, returns `false` if there is none.
+    pub fn toggle(&mut self, menu_id: &MenuId) -> bool {
+        let Some(menu) = self.touch(menu_id) else {
+            return false;
+        };
+
+        menu.as_check_menu()
+            .is_some_and(|check_menu| menu.set_checked(!check_menu.is_checked()))
+    }
+
+    /// Sets the text of a menu control, returns `false` if there is none.
+    pub fn set_text(&mut self, menu_id: &MenuId, text: &str) -> bool {
+        let Some(menu) = self.touch(menu_id) else {
+            return false;
+        };
+
+        self.manager.set_item_text(menu_id, menu, text);
+        true
+    }
+
+    /// Enables or disables a menu control, returns `false` if there is none.
+    pub fn set_enabled(&mut self, menu_id: &MenuId, enabled: bool) -> bool {
+        let Some(menu) = self.touch(menu_id) else {
+            return false;
+        };
+
+        self.manager.set_item_enabled(menu_id, menu, enabled);
+        true
+    }
+
+    /// Records the state of a menu control (and of its radio group) before its first change.
+    fn touch(&mut self, menu_id: &MenuId) -> Option<&'a MenuControl<G>> {
+        let manager = self.manager;
+        let menu = manager.id_to_menu.get(menu_id)?;
+
+        self.record(menu);
+        if let Some(CheckRef::Radio(_, _, group)) = menu.check_ref() {
+            manager
+                .grouped_check_items
+                .get(group)
+                .into_iter()
+                .flat_map(|check_menus| check_menus.keys())
+                .filter_map(|radio_id| manager.id_to_menu.get(radio_id.as_ref()))
+                .for_each(|radio| self.record(radio));
+        }
+
+        self.touched.retain(|touched_id| touched_id != menu_id);
+        self.touched.push(menu_id.clone());
+        Some(menu)
+    }
+
+    fn record(&mut self, menu: &MenuControl<G>) {
+        self.manager.observe_item(menu.id());
+        self.before
+            .entry(menu.id().clone())
+            .or_insert_with(|| ItemState {
+                checked: menu
+                    .as_check_menu()
+                    .map(|check_menu| check_menu.is_checked()),
+                enabled: menu.is_enabled(),
+                text: menu.text(),
+            });
+    }
+
+    /// Enforces the group rules and returns the menu IDs whose state changed.
+    pub(crate) fn commit(self) -> Vec<MenuId> {
+        let manager = self.manager;
+
+        // Radios are settled in reverse order of their last change, so the last one wins
+        let mut settled_groups = Vec::new();
+        for menu_id in self.touched.iter().rev() {
+            if let Some(CheckRef::Radio(check_menu, _, group)) = manager
+                .id_to_menu
+                .get(menu_id)
+                .and_then(|menu| menu.check_ref())
+                && !settled_groups.contains(&group)
+            {
+                manager.settle_radio_group(group, check_menu.is_checked().then_some(menu_id));
+                settled_groups.push(group);
+            }
+        }
+
+        self.touched
+            .iter()
+            .for_each(|menu_id| manager.sync_master_checkboxes(menu_id));
+        manager.apply_dependencies();
+        manager.refresh_check_icons();
+
+        let mut changed: Vec<MenuId> = self
+            .before
+            .into_iter()
+            .filter(|(menu_id, before)| {
+                manager.id_to_menu.get(menu_id).is_some_and(|menu| {
+                    before.checked
+                        != menu
+                            .as_check_menu()
+                            .map(|check_menu| check_menu.is_checked())
+                        || before.enabled != menu.is_enabled()
+                        || before.text != menu.text()
+                })
+            })
+            .map(|(menu_id, _)| menu_id)
+            .collect();
+
+        changed.sort_by_key(|menu_id| {
+            self.touched
+                .iter()
+                .position(|touched_id| touched_id == menu_id)
+                .unwrap_or(usize::MAX)
+        });
+        changed
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, MenuId, MenuItem, Submenu};
+
+use crate::{CheckMenuKind, Error, MenuControl, MenuManager, MenuParent, ScopedIds};
+
+/// Binds a collection of values to the entries of a submenu
+///
+/// Each value is mapped to a menu ID and a label. [`SubmenuBinding::sync`] diffs the
+/// collection against the current entries, and adds, removes, updates or reorders
+/// the menu controls in both the [`MenuManager`] and the live submenu.
+///
+/// The entries are kept at the start of the submenu, items appended after them
+/// (e.g. a separator and a "Clear" item) are left untouched.
+///
+/// With [`SubmenuBinding::with_page_size`], a collection larger than the page size is split
+/// into page submenus labelled `"1–25"`, `"26–50"`, and so on. The radio group of the entries
+/// still spans all pages.
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, SubmenuBinding};
+/// use tray_icon::menu::{MenuId, Submenu};
+///
+/// struct Device {
+///     serial: String,
+///     name: String,
+/// }
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let submenu = Submenu::new("Devices", true);
+///
+/// // Entries are radio buttons of the "device_group" group
+/// let mut binding = SubmenuBinding::new(submenu, |device: &Device| {
+///     (MenuId::new(&device.serial), device.name.clone())
+/// })
+/// .with_radio_group("device_group");
+///
+/// let devices = vec![Device { serial: "usb0".into(), name: "Keyboard".into() }];
+/// binding.sync(&mut manager, &devices).unwrap();
+/// ```
+pub struct SubmenuBinding<T, G> {
+    submenu: Submenu,
+    mapper: fn(&T) -> (MenuId, String),
+    radio_group: Option<G>,
+    page_size: Option<usize>,
+    pages: Vec<Submenu>,
+    entries: Vec<MenuId>,
+}
+
+impl<T, G> SubmenuBinding<T, G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new(submenu: Submenu, mapper: fn(&T) -> (MenuId, String)) -> Self {
+        SubmenuBinding {
+            submenu,
+            mapper,
+            radio_group: None,
+            page_size: None,
+            pages: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates the entries as radio buttons of the group, instead of standard menu items.
+    pub fn with_radio_group(mut self, group: G) -> Self {
+        self.radio_group = Some(group);
+        self
+    }
+
+    /// Splits the entries into page submenus once there are more than `page_size` of them.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size.max(1));
+        self
+    }
+
+    pub fn submenu(&self) -> &Submenu {
+        &self.submenu
+    }
+
+    /// Gets the page submenus, empty while the entries fit in the submenu.
+    pub fn pages(&self) -> &[Submenu] {
+        &self.pages
+    }
+
+    /// Gets the menu IDs of the entries in submenu order, across all pages.
+    pub fn entries(&self) -> &[MenuId] {
+        &self.entries
+    }
+
+    /// Synchronizes the submenu entries with the items.
+    pub fn sync(&mut self, manager: &mut MenuManager<G>, items: &[T]) -> Result<(), Error> {
+        let parent_id = self.submenu.id().clone();
+        manager.insert_parent(MenuParent::Submenu(self.submenu.clone()));
+
+        let desired: Vec<(MenuId, String)> = items.iter().map(self.mapper).collect();
+
+        let paged = self
+            .page_size
+            .is_some_and(|page_size| desired.len() > page_size);
+        if paged || !self.pages.is_empty() {
+            return self.rebuild(manager, desired, paged);
+        }
+
+        self.entries.retain(|menu_id| {
+            let keep = desired.iter().any(|(desired_id, _)| desired_id == menu_id);
+            if !keep {
+                // A stale entry can't stay the default radio, so the warning is expected
+                let _ = manager.remove(menu_id);
+            }
+            keep
+        });
+
+        for (position, (menu_id, text)) in desired.into_iter().enumerate() {
+            match self.entries.iter().position(|entry| *entry == menu_id) {
+                Some(current) => {
+                    let Some(menu_control) = manager.get_menu_item_from_id(&menu_id) else {
+                        continue;
+                    };
+
+                    if manager.item_text(&menu_id, menu_control) != text {
+                        manager.set_item_text(&menu_id, menu_control, &text);
+                    }
+
+                    if current != position {
+                        let item = menu_control.as_is_menu_item();
+                        self.submenu.remove(item)?;
+                        self.submenu.insert(item, position)?;
+
+                        let entry = self.entries.remove(current);
+                        self.entries.insert(position, entry);
+                    }
+                }
+                None => {
+                    let menu_control = self.new_control(menu_id.clone(), text);
+                    manager.insert_into(&parent_id, menu_control, Some(position))?;
+                    self.entries.insert(position, menu_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the entries, paged or not, keeping the menu controls of the current entries.
+    ///
+    /// Paged entries are rebuilt on every sync, as entries move between pages.
+    fn rebuild(
+        &mut self,
+        manager: &mut MenuManager<G>,
+        desired: Vec<(MenuId, String)>,
+        paged: bool,
+    ) -> Result<(), Error> {
+        let group_default = self
+            .radio_group
+            .as_ref()
+            .and_then(|group| manager.group_default(group).cloned());
+
+        // The kept menu controls take their checked state along
+        let mut kept: HashMap<MenuId, MenuControl<G>> = HashMap::new();
+        for menu_id in self.entries.drain(..) {
+            if let Some(menu_control) = manager.get_menu_item_from_id(&menu_id).cloned() {
+                let _ = manager.remove(&menu_id);
+                kept.insert(menu_id, menu_control);
+            }
+        }
+        for page in self.pages.drain(..) {
+            self.submenu.remove(&page)?;
+            manager.remove_parent(page.id());
+        }
+
+        let parent_id = self.submenu.id().clone();
+        let page_size = match self.page_size {
+            Some(page_size) if paged => page_size,
+            _ => desired.len().max(1),
+        };
+
+        for (page, chunk) in desired.chunks(page_size).enumerate() {
+            let target_id = if paged {
+                let first = page * page_size + 1;
+                let page_menu = Submenu::with_id(
+                    ScopedIds::new(parent_id.as_ref()).id(&format!("page{page}")),
+                    format!("{first}–{}", first + chunk.len() - 1),
+                    true,
+                );
+                self.submenu.insert(&page_menu, page)?;
+                manager.insert_parent(MenuParent::Submenu(page_menu.clone()));
+
+                let page_id = page_menu.id().clone();
+                self.pages.push(page_menu);
+                page_id
+            } else {
+                parent_id.clone()
+            };
+
+            for (position, (menu_id, text)) in chunk.iter().enumerate() {
+                let menu_control = match kept.remove(menu_id) {
+                    Some(menu_control) => {
+                        if menu_control.text() != *text {
+                            menu_control.set_text(text);
+                        }
+                        menu_control
+                    }
+                    None => self.new_control(menu_id.clone(), text.clone()),
+                };
+
+                manager.insert_into(&target_id, menu_control, Some(position))?;
+                self.entries.push(menu_id.clone());
+            }
+        }
+
+        if let (Some(group), Some(default_id)) = (&self.radio_group, group_default)
+            && self.entries.contains(&default_id)
+        {
+            manager.set_group_default(group.clone(), default_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn new_control(&self, menu_id: MenuId, text: String) -> MenuControl<G> {
+        match &self.radio_group {
+            Some(group) => MenuControl::CheckMenu(CheckMenuKind::Radio(
+                Rc::new(CheckMenuItem::with_id(menu_id, text, true, false, None)),
+                None,
+                group.clone(),
+            )),
+            None => MenuControl::MenuItem(MenuItem::with_id(menu_id, text, true, None)),
+        }
+    }
+}
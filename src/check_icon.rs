@@ -0,0 +1,171 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, Icon, IconMenuItem, MenuId};
+
+use crate::DefaultMenuId;
+
+/// Icon menu that can be checked, e.g. a color swatch in a theme picker
+///
+/// Menus have no native checkable icon items, so the checked state is shown by swapping
+/// between the `icon` and the `checked_icon`. The state is kept by a `CheckMenuItem` with
+/// the same ID, which is never shown, so the item takes part in checkbox and radio groups
+/// like any other check menu. Append [`CheckIconMenuItem::icon_menu`] to the menu.
+///
+/// # Example
+/// ```
+/// use std::rc::Rc;
+/// use tray_controls::{CheckIconKind, CheckIconMenuItem, MenuControl, MenuManager};
+/// use tray_icon::menu::{Icon, MenuId};
+///
+/// let swatch = Icon::from_rgba(vec![255, 0, 0, 255], 1, 1).unwrap();
+/// let checked_swatch = Icon::from_rgba(vec![128, 0, 0, 255], 1, 1).unwrap();
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let red = Rc::new(CheckIconMenuItem::with_id(
+///     "red", "Red", true, false, Some(swatch), Some(checked_swatch),
+/// ));
+/// manager.insert(MenuControl::CheckIconMenu(CheckIconKind::Radio(red.clone(), None, "theme")));
+///
+/// // Icon menus aren't toggled by the platform, the manager toggles them on update
+/// manager.update(&MenuId::new("red"), |_| {});
+/// assert!(red.is_checked());
+/// ```
+pub struct CheckIconMenuItem {
+    icon_menu: IconMenuItem,
+    state: Rc<CheckMenuItem>,
+    icon: RefCell<Option<Icon>>,
+    checked_icon: RefCell<Option<Icon>>,
+    /// Checked state of the shown icon, `None` until an icon is shown
+    shown_checked: Cell<Option<bool>>,
+}
+
+impl CheckIconMenuItem {
+    pub fn new(
+        text: &str,
+        enabled: bool,
+        checked: bool,
+        icon: Option<Icon>,
+        checked_icon: Option<Icon>,
+    ) -> Self {
+        let icon_menu = IconMenuItem::new(text, enabled, None, None);
+        Self::from_icon_menu(icon_menu, checked, icon, checked_icon)
+    }
+
+    pub fn with_id<I: Into<MenuId>>(
+        id: I,
+        text: &str,
+        enabled: bool,
+        checked: bool,
+        icon: Option<Icon>,
+        checked_icon: Option<Icon>,
+    ) -> Self {
+        let icon_menu = IconMenuItem::with_id(id, text, enabled, None, None);
+        Self::from_icon_menu(icon_menu, checked, icon, checked_icon)
+    }
+
+    fn from_icon_menu(
+        icon_menu: IconMenuItem,
+        checked: bool,
+        icon: Option<Icon>,
+        checked_icon: Option<Icon>,
+    ) -> Self {
+        let state = CheckMenuItem::with_id(icon_menu.id().clone(), "", true, checked, None);
+        let item = CheckIconMenuItem {
+            icon_menu,
+            state: Rc::new(state),
+            icon: RefCell::new(icon),
+            checked_icon: RefCell::new(checked_icon),
+            shown_checked: Cell::new(None),
+        };
+        item.refresh_icon();
+        item
+    }
+
+    pub fn id(&self) -> &MenuId {
+        self.icon_menu.id()
+    }
+
+    /// Gets the icon menu to append to a `Menu` or `Submenu`.
+    pub fn icon_menu(&self) -> &IconMenuItem {
+        &self.icon_menu
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.state.is_checked()
+    }
+
+    pub fn set_checked(&self, checked: bool) {
+        self.state.set_checked(checked);
+        self.refresh_icon();
+    }
+
+    /// Sets the icons shown when unchecked and when checked.
+    pub fn set_icons(&self, icon: Option<Icon>, checked_icon: Option<Icon>) {
+        *self.icon.borrow_mut() = icon;
+        *self.checked_icon.borrow_mut() = checked_icon;
+        self.refresh_icon();
+    }
+
+    /// Shows the icon matching the checked state.
+    pub fn refresh_icon(&self) {
+        let checked = self.is_checked();
+        let icon = if checked {
+            self.checked_icon.borrow().clone()
+        } else {
+            self.icon.borrow().clone()
+        };
+
+        self.icon_menu.set_icon(icon);
+        self.shown_checked.set(Some(checked));
+    }
+
+    /// Shows the icon matching the checked state, if it changed since it was shown.
+    pub(crate) fn sync_icon(&self) {
+        if self.shown_checked.get() != Some(self.is_checked()) {
+            self.refresh_icon();
+        }
+    }
+
+    pub(crate) fn state(&self) -> &Rc<CheckMenuItem> {
+        &self.state
+    }
+}
+
+/// Checkable icon menu kinds, the counterpart of [`CheckMenuKind`](crate::CheckMenuKind) for [`CheckIconMenuItem`]
+#[derive(Clone)]
+pub enum CheckIconKind<G> {
+    /// A checkbox belonging to the group `G`
+    CheckBox(Rc<CheckIconMenuItem>, G),
+    /// A radio button belonging to the group `G`, with an optional default radio menu ID
+    ///
+    /// The default radio menu ID is deprecated, pass `None` and use
+    /// [`MenuManager::set_group_default`](crate::MenuManager::set_group_default) instead,
+    /// or build it with [`CheckIconKind::radio`].
+    Radio(Rc<CheckIconMenuItem>, Option<Rc<DefaultMenuId>>, G),
+    /// A standalone checkbox with no group association
+    Separate(Rc<CheckIconMenuItem>),
+}
+
+impl<G> CheckIconKind<G> {
+    /// Creates a radio button of the group `G`, see
+    /// [`MenuManager::set_group_default`](crate::MenuManager::set_group_default) for its
+    /// default radio.
+    pub fn radio(item: Rc<CheckIconMenuItem>, group: G) -> Self {
+        CheckIconKind::Radio(item, None, group)
+    }
+
+    /// Creates a radio button with a per-radio default radio menu ID.
+    #[deprecated(note = "use `CheckIconKind::radio` and `MenuManager::set_group_default`")]
+    pub fn radio_with_default(item: Rc<CheckIconMenuItem>, default_id: MenuId, group: G) -> Self {
+        CheckIconKind::Radio(item, Some(Rc::new(default_id)), group)
+    }
+
+    pub fn item(&self) -> &Rc<CheckIconMenuItem> {
+        match self {
+            CheckIconKind::CheckBox(item, _)
+            | CheckIconKind::Radio(item, _, _)
+            | CheckIconKind::Separate(item) => item,
+        }
+    }
+}
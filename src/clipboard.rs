@@ -0,0 +1,75 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Copies text to the clipboard with the clipboard tool of the platform.
+///
+/// `clip` on **Windows**, `pbcopy` on **macOS**, and `wl-copy`, `xclip` or `xsel` on **Linux**.
+pub(crate) fn copy(text: &str) -> io::Result<()> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no clipboard tool found");
+
+    for (program, args) in tools() {
+        match pipe(program, args, &encode(text)) {
+            Ok(()) => return Ok(()),
+            // Try the next tool only if this one is not installed
+            Err(e) if e.kind() == io::ErrorKind::NotFound => last_error = e,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_error)
+}
+
+fn pipe(program: &str, args: &[&str], input: &[u8]) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input)?;
+    }
+
+    match child.wait()?.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(format!("`{program}` failed"))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn tools() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![("clip", &[])]
+}
+
+#[cfg(target_os = "macos")]
+fn tools() -> Vec<(&'static str, &'static [&'static str])> {
+    vec![("pbcopy", &[])]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn tools() -> Vec<(&'static str, &'static [&'static str])> {
+    let x11: [(&str, &[&str]); 2] = [
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+
+    match std::env::var_os("WAYLAND_DISPLAY") {
+        Some(_) => std::iter::once(("wl-copy", &[][..])).chain(x11).collect(),
+        None => x11.to_vec(),
+    }
+}
+
+/// `clip` reads the OEM code page, unless the text is UTF-16 with a byte order mark.
+#[cfg(target_os = "windows")]
+fn encode(text: &str) -> Vec<u8> {
+    [0xFF, 0xFE]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn encode(text: &str) -> Vec<u8> {
+    text.as_bytes().to_vec()
+}
@@ -0,0 +1,84 @@
+use std::hash::Hash;
+
+use muda::{MenuEvent, MenuId};
+
+use crate::{FxIndexMap, MenuControl, MenuHandle, MenuManager};
+
+/// Maps menu IDs to application commands
+///
+/// Resolving the clicked menu to a command replaces matching on the menu ID strings.
+///
+/// # Example
+/// ```
+/// use tray_controls::{CommandRegistry, MenuControl, MenuManager};
+/// use tray_icon::menu::{MenuEvent, MenuId, MenuItem};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Command {
+///     Open,
+///     Quit,
+/// }
+///
+/// let mut manager = MenuManager::<()>::new();
+/// let mut commands = CommandRegistry::new();
+///
+/// let open = MenuItem::with_id("open", "Open", true, None);
+/// let quit = MenuItem::with_id("quit", "Quit", true, None);
+/// commands.insert(&mut manager, MenuControl::MenuItem(open), Command::Open);
+/// commands.insert(&mut manager, MenuControl::MenuItem(quit), Command::Quit);
+///
+/// let event = MenuEvent { id: MenuId::new("quit") };
+/// assert_eq!(commands.resolve(&event), Some(Command::Quit));
+/// ```
+pub struct CommandRegistry<C> {
+    commands: FxIndexMap<MenuId, C>,
+}
+
+impl<C> Default for CommandRegistry<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> CommandRegistry<C> {
+    pub fn new() -> Self {
+        CommandRegistry {
+            commands: FxIndexMap::default(),
+        }
+    }
+
+    /// Inserts a menu control into the menu manager, and registers its command.
+    pub fn insert<G>(
+        &mut self,
+        manager: &mut MenuManager<G>,
+        menu_control: MenuControl<G>,
+        command: C,
+    ) -> MenuHandle
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        self.register(menu_control.id().clone(), command);
+        manager.insert(menu_control)
+    }
+
+    /// Registers the command of an already inserted menu control, replacing the previous one.
+    pub fn register(&mut self, menu_id: MenuId, command: C) -> Option<C> {
+        self.commands.insert(menu_id, command)
+    }
+
+    pub fn unregister(&mut self, menu_id: &MenuId) -> Option<C> {
+        self.commands.shift_remove(menu_id)
+    }
+
+    pub fn command_of(&self, menu_id: &MenuId) -> Option<&C> {
+        self.commands.get(menu_id)
+    }
+
+    /// Gets the command of the clicked menu.
+    pub fn resolve(&self, event: &MenuEvent) -> Option<C>
+    where
+        C: Clone,
+    {
+        self.commands.get(event.id()).cloned()
+    }
+}
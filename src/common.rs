@@ -0,0 +1,107 @@
+use std::hash::Hash;
+
+use muda::accelerator::{Accelerator, CMD_OR_CTRL, Code};
+use muda::{MenuId, MenuItem};
+
+use crate::{Error, MenuControl, MenuHandle, MenuManager, TypedId, menu_ids, mnemonic};
+
+menu_ids! {
+    /// Standard items of a tray menu, with their menu IDs
+    ///
+    /// Each item comes with an English label, a translation key for
+    /// [`MenuManager::add_locale`] and a conventional accelerator.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{CommonAction, MenuManager, MenuParent, TypedId};
+    /// use tray_icon::menu::{Menu, MenuEvent};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let tray_menu = Menu::new();
+    /// manager.insert_parent(MenuParent::Menu(tray_menu.clone()));
+    ///
+    /// for action in CommonAction::ALL {
+    ///     action.insert_into(&mut manager, tray_menu.id(), true).unwrap();
+    /// }
+    /// manager.add_locale("de", [(CommonAction::Quit.translation_key(), "&Beenden")]);
+    ///
+    /// // Handle menu clicks
+    /// let event = MenuEvent { id: CommonAction::Quit.menu_id() };
+    /// match CommonAction::from_event(&event) {
+    ///     Some(CommonAction::Quit) => println!("Quit"),
+    ///     Some(action) => println!("{action:?}"),
+    ///     None => {}
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub enum CommonAction {
+        About = "about",
+        Settings = "settings",
+        Quit = "quit",
+    }
+}
+
+impl CommonAction {
+    /// Gets the English label, with a mnemonic marker.
+    pub fn text(&self) -> &'static str {
+        match self {
+            CommonAction::About => "&About",
+            CommonAction::Settings => "&Settings…",
+            CommonAction::Quit => "&Quit",
+        }
+    }
+
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            CommonAction::About => "common.about",
+            CommonAction::Settings => "common.settings",
+            CommonAction::Quit => "common.quit",
+        }
+    }
+
+    /// Gets the conventional accelerator, `Ctrl` is `Cmd` on **macOS**.
+    pub fn accelerator(&self) -> Option<Accelerator> {
+        match self {
+            CommonAction::About => None,
+            CommonAction::Settings => Some(Accelerator::new(Some(CMD_OR_CTRL), Code::Comma)),
+            CommonAction::Quit => Some(Accelerator::new(Some(CMD_OR_CTRL), Code::KeyQ)),
+        }
+    }
+
+    /// Creates the menu item, with the accelerator if `accelerator` is `true`.
+    pub fn menu_item(&self, accelerator: bool) -> MenuItem {
+        MenuItem::with_id(
+            *self,
+            mnemonic::apply_mnemonic(self.text()),
+            true,
+            self.accelerator().filter(|_| accelerator),
+        )
+    }
+
+    /// Inserts the menu item into a registered parent menu, with its translation key.
+    ///
+    /// The accelerator is registered in the menu manager, so a conflict is reported.
+    pub fn insert_into<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+        accelerator: bool,
+    ) -> Result<MenuHandle, Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let menu_id = self.menu_id();
+        let handle = manager.insert_into(
+            parent_id,
+            MenuControl::MenuItem(self.menu_item(false)),
+            None,
+        )?;
+        manager.set_translation_key(&menu_id, self.translation_key());
+
+        if accelerator {
+            manager.set_accelerator(&menu_id, self.accelerator())?;
+        }
+
+        Ok(handle)
+    }
+}
@@ -0,0 +1,16 @@
+use std::any::Any;
+use std::rc::Rc;
+
+/// Condition of a menu control, `None` when evaluated against another context type
+pub(crate) type Condition = Rc<dyn Fn(&dyn Any) -> Option<bool>>;
+
+pub(crate) fn condition<C: 'static>(condition: impl Fn(&C) -> bool + 'static) -> Condition {
+    Rc::new(move |ctx: &dyn Any| ctx.downcast_ref::<C>().map(&condition))
+}
+
+/// Conditions of a menu control, see [`MenuManager::refresh_conditions`](crate::MenuManager::refresh_conditions)
+#[derive(Clone, Default)]
+pub(crate) struct ItemConditions {
+    pub(crate) enabled_if: Option<Condition>,
+    pub(crate) visible_if: Option<Condition>,
+}
@@ -0,0 +1,264 @@
+use std::hash::Hash;
+
+use muda::MenuId;
+use serde_json::{Map, Value};
+
+use crate::{Action, ChangeOrigin, MenuManager};
+
+enum KeyBinding {
+    CheckBox {
+        key: String,
+        menu_id: MenuId,
+    },
+    Radio {
+        key: String,
+        options: Vec<(MenuId, Value)>,
+    },
+}
+
+impl KeyBinding {
+    fn key(&self) -> &str {
+        match self {
+            KeyBinding::CheckBox { key, .. } | KeyBinding::Radio { key, .. } => key,
+        }
+    }
+}
+
+/// Maps the keys of a configuration to check menus (**requires the `serde` feature**)
+///
+/// Keys are dotted paths into the configuration, e.g. `"ui.theme"`. A boolean key maps to a
+/// checkbox, and a key with a few possible values maps to the radios of a radio group.
+///
+/// The configuration is a `serde_json::Value`, so it works with any config crate that
+/// (de)serializes with serde, e.g. `figment` with `figment.extract::<serde_json::Value>()`
+/// and `figment::providers::Serialized::defaults(delta)`, or `config` with
+/// `config.try_deserialize::<serde_json::Value>()`.
+///
+/// The configuration can also attach the actions of menu controls, see
+/// [`ConfigBinding::actions`].
+///
+/// # Example
+/// ```
+/// use std::rc::Rc;
+/// use serde_json::json;
+/// use tray_controls::{CheckMenuKind, ConfigBinding, MenuControl, MenuManager};
+/// use tray_icon::menu::{CheckMenuItem, MenuId};
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let autostart = CheckMenuItem::with_id("autostart", "Autostart", true, false, None);
+/// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(autostart))));
+/// for id in ["light", "dark"] {
+///     let radio = CheckMenuItem::with_id(id, id, true, false, None);
+///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+/// }
+///
+/// let binding = ConfigBinding::new()
+///     .checkbox("autostart", MenuId::new("autostart"))
+///     .radio("ui.theme", [(MenuId::new("light"), "light"), (MenuId::new("dark"), "dark")]);
+///
+/// binding.load(&mut manager, &json!({ "autostart": true, "ui": { "theme": "dark" } }));
+///
+/// // In the menu event handler, after `MenuManager::update`
+/// manager.get_menu_item_from_id(&MenuId::new("light")).unwrap().set_checked(true);
+/// manager.update(&MenuId::new("light"), |_| {});
+/// assert_eq!(
+///     binding.delta(&manager, &MenuId::new("light")),
+///     Some(json!({ "ui": { "theme": "light" } }))
+/// );
+/// ```
+pub struct ConfigBinding {
+    keys: Vec<KeyBinding>,
+    actions_key: Option<String>,
+}
+
+impl Default for ConfigBinding {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBinding {
+    pub fn new() -> Self {
+        ConfigBinding {
+            keys: Vec::new(),
+            actions_key: None,
+        }
+    }
+
+    /// Maps a boolean key to a checkbox.
+    pub fn checkbox(mut self, key: &str, menu_id: MenuId) -> Self {
+        self.keys.push(KeyBinding::CheckBox {
+            key: key.to_owned(),
+            menu_id,
+        });
+        self
+    }
+
+    /// Maps a key to radios, each radio standing for one value of the key.
+    pub fn radio<V: Into<Value>>(
+        mut self,
+        key: &str,
+        options: impl IntoIterator<Item = (MenuId, V)>,
+    ) -> Self {
+        self.keys.push(KeyBinding::Radio {
+            key: key.to_owned(),
+            options: options
+                .into_iter()
+                .map(|(menu_id, value)| (menu_id, value.into()))
+                .collect(),
+        });
+        self
+    }
+
+    /// Maps a key to the actions of menu controls, a table from menu IDs to actions.
+    ///
+    /// The actions are parsed with [`Action::from_value`] on [`ConfigBinding::load`], an
+    /// entry that isn't an action detaches the action of its menu control.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json::json;
+    /// use tray_controls::{Action, ConfigBinding, MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("logs", "Show Logs", true, None)));
+    ///
+    /// let config = json!({ "actions": { "logs": { "exec": "xdg-open", "args": ["/var/log"] } } });
+    /// ConfigBinding::new().actions("actions").load(&mut manager, &config);
+    ///
+    /// assert!(matches!(manager.action(&MenuId::new("logs")), Some(Action::Exec { .. })));
+    /// ```
+    pub fn actions(mut self, key: &str) -> Self {
+        self.actions_key = Some(key.to_owned());
+        self
+    }
+
+    /// Checks the mapped menu controls from a configuration, and returns the menu IDs whose
+    /// state changed.
+    ///
+    /// Missing keys, and values of the wrong type or without a radio, are skipped. The
+    /// actions of [`ConfigBinding::actions`] are attached to the registered menu controls.
+    pub fn load<G>(&self, manager: &mut MenuManager<G>, config: &Value) -> Vec<MenuId>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        if let Some(actions) = self
+            .actions_key
+            .as_deref()
+            .and_then(|key| lookup(config, key))
+            .and_then(Value::as_object)
+        {
+            for (menu_id, value) in actions {
+                manager.set_action(&MenuId::new(menu_id), Action::from_value(value));
+            }
+        }
+
+        manager.with_origin(ChangeOrigin::Restore, |manager| {
+            manager.batch(|batch| {
+                for binding in &self.keys {
+                    let Some(value) = lookup(config, binding.key()) else {
+                        continue;
+                    };
+
+                    match binding {
+                        KeyBinding::CheckBox { menu_id, .. } => {
+                            if let Some(checked) = value.as_bool() {
+                                batch.set_checked(menu_id, checked);
+                            }
+                        }
+                        KeyBinding::Radio { options, .. } => {
+                            if let Some((menu_id, _)) =
+                                options.iter().find(|(_, option)| option == value)
+                            {
+                                batch.set_checked(menu_id, true);
+                            }
+                        }
+                    }
+                }
+            })
+        })
+    }
+
+    /// Gets the configuration delta of a clicked menu control, `None` if it isn't mapped.
+    ///
+    /// Call it after [`MenuManager::update`], so radio groups are settled.
+    pub fn delta<G>(&self, manager: &MenuManager<G>, menu_id: &MenuId) -> Option<Value>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let binding = self.keys.iter().find(|binding| match binding {
+            KeyBinding::CheckBox {
+                menu_id: bound_id, ..
+            } => bound_id == menu_id,
+            KeyBinding::Radio { options, .. } => {
+                options.iter().any(|(option_id, _)| option_id == menu_id)
+            }
+        })?;
+
+        let mut delta = Value::Object(Map::new());
+        insert(&mut delta, binding.key(), self.value_of(manager, binding)?);
+        Some(delta)
+    }
+
+    /// Gets the configuration of all the mapped menu controls.
+    pub fn to_json<G>(&self, manager: &MenuManager<G>) -> Value
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let mut config = Value::Object(Map::new());
+        for binding in &self.keys {
+            if let Some(value) = self.value_of(manager, binding) {
+                insert(&mut config, binding.key(), value);
+            }
+        }
+
+        config
+    }
+
+    fn value_of<G>(&self, manager: &MenuManager<G>, binding: &KeyBinding) -> Option<Value>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let is_checked = |menu_id: &MenuId| {
+            manager
+                .get_menu_item_from_id(menu_id)
+                .and_then(|menu| menu.as_check_menu())
+                .map(|check_menu| check_menu.is_checked())
+        };
+
+        match binding {
+            KeyBinding::CheckBox { menu_id, .. } => is_checked(menu_id).map(Value::Bool),
+            KeyBinding::Radio { options, .. } => options
+                .iter()
+                .find(|(menu_id, _)| is_checked(menu_id) == Some(true))
+                .map(|(_, value)| value.clone()),
+        }
+    }
+}
+
+fn lookup<'a>(config: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(config, |value, segment| value.get(segment))
+}
+
+fn insert(config: &mut Value, key: &str, value: Value) {
+    let mut segments: Vec<&str> = key.split('.').collect();
+    let Some(last) = segments.pop() else {
+        return;
+    };
+
+    let mut object = config;
+    for segment in segments {
+        let Value::Object(map) = object else {
+            return;
+        };
+        object = map
+            .entry(segment)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    if let Value::Object(map) = object {
+        map.insert(last.to_owned(), value);
+    }
+}
@@ -0,0 +1,868 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+
+use tray_icon::{
+    Icon, MouseButton, MouseButtonState, Rect, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent},
+};
+
+use crate::a11y::A11yPrefs;
+use crate::attention::IconState;
+use crate::icons::{IconCache, IconSet};
+use crate::{
+    Error, MenuControl, MenuManager, PanicPolicy, call_with_policy, is_tray_supported, template,
+};
+
+/// A tray icon interaction that can be bound to a [`TrayAction`]
+///
+/// Single clicks are reported once the mouse button is released, so a
+/// press-and-release pair only produces one action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayClick {
+    /// A single click with the given mouse button
+    Click(MouseButton),
+    /// A double click with the given mouse button (**Windows only**)
+    DoubleClick(MouseButton),
+}
+
+/// Typed action produced by [`TrayController::handle_tray_event`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    /// The tray menu is shown by the platform
+    ShowMenu,
+    /// Show the main window
+    ShowWindow,
+    /// Toggle the visibility of the main window
+    ToggleWindow,
+    /// The click has no binding and is passed through unchanged
+    Click(TrayClick),
+}
+
+/// Any event that the tray icon can emit
+///
+/// Used by [`TrayController::set_event_handler`] to forward menu and tray icon
+/// events through a single channel.
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    Menu(MenuEvent),
+    Tray(TrayIconEvent),
+}
+
+thread_local! {
+    /// Menus registered by [`TrayController::set_click_menus`].
+    ///
+    /// The platform opens the tray menu right after it emits the mouse down event,
+    /// so the menus are swapped inside the event handler, on the thread that owns the tray.
+    static CLICK_MENUS: RefCell<Vec<ClickMenus>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Number of scale factors whose icon set image is kept converted
+const ICON_SET_CACHE_CAPACITY: usize = 4;
+
+/// Hook of [`TrayController::set_shutdown_hook`].
+type ShutdownHook<G> = Box<dyn FnOnce(&MenuManager<G>)>;
+
+struct ClickMenus {
+    tray: TrayIcon,
+    left_click_menu: Menu,
+    right_click_menu: Menu,
+}
+
+fn swap_click_menu(event: &TrayIconEvent) {
+    let TrayIconEvent::Click {
+        id,
+        button,
+        button_state: MouseButtonState::Down,
+        ..
+    } = event
+    else {
+        return;
+    };
+
+    CLICK_MENUS.with_borrow(|click_menus| {
+        if let Some(click_menus) = click_menus.iter().find(|menus| menus.tray.id() == id) {
+            let menu = match button {
+                MouseButton::Left => &click_menus.left_click_menu,
+                MouseButton::Right => &click_menus.right_click_menu,
+                MouseButton::Middle => return,
+            };
+            click_menus.tray.set_menu(Some(Box::new(menu.clone())));
+        }
+    });
+}
+
+/// Gets the scale factor from the tray icon rectangle of an event, on **Windows**.
+fn detect_scale_factor(event: &TrayIconEvent) -> Option<f64> {
+    match event {
+        TrayIconEvent::Click { rect, .. }
+        | TrayIconEvent::DoubleClick { rect, .. }
+        | TrayIconEvent::Enter { rect, .. }
+        | TrayIconEvent::Move { rect, .. }
+        | TrayIconEvent::Leave { rect, .. } => scale_factor_of(*rect),
+        _ => None,
+    }
+}
+
+/// Windows sizes the tray icon area from the 16 pixels small icon, scaled by the DPI.
+/// On the other platforms, the area is the status item or unknown.
+fn scale_factor_of(rect: Rect) -> Option<f64> {
+    match cfg!(target_os = "windows") && rect.size.height > 0 {
+        true => Some(f64::from(rect.size.height) / 16.0),
+        false => None,
+    }
+}
+
+/// Tray controller that owns the tray icon together with its [`MenuManager`]
+///
+/// It routes both menu events and tray icon events, so applications only need
+/// one event handler for everything the tray can emit.
+///
+/// ## Platform-specific:
+///
+/// - **Windows:** When `explorer.exe` restarts, the taskbar broadcasts `TaskbarCreated`.
+///   tray-icon handles it and adds the icon back with its tooltip and menu, so no Win32
+///   message handling is needed. The menu state lives in the menu items and is kept.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{TrayAction, TrayClick, TrayController, MenuManager};
+/// use tray_icon::{MouseButton, TrayIconBuilder};
+///
+/// let tray = TrayIconBuilder::new().build().unwrap();
+/// let mut controller = TrayController::new(tray, MenuManager::<&str>::new());
+///
+/// // Double-click toggles the main window, left click shows the menu
+/// controller.bind(TrayClick::DoubleClick(MouseButton::Left), TrayAction::ToggleWindow);
+/// controller.bind(TrayClick::Click(MouseButton::Left), TrayAction::ShowMenu);
+/// ```
+pub struct TrayController<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    tray: TrayIcon,
+    menu_manager: MenuManager<G>,
+    click_bindings: Vec<(TrayClick, TrayAction)>,
+    tooltip_template: Option<String>,
+    tooltip_vars: HashMap<String, String>,
+    shutdown_hook: Option<ShutdownHook<G>>,
+    icon_state: IconState,
+    /// Menu shown by [`TrayController::show_menu`]
+    menu: Option<Menu>,
+    icon_set: Option<IconSet>,
+    /// Converted images of the icon set by size, so switching displays converts them once
+    icon_set_cache: IconCache<u32>,
+    scale_factor: f64,
+    /// Pixel size of the icon set image shown, `None` when it must be picked again
+    icon_set_size: Option<u32>,
+    a11y_prefs: A11yPrefs,
+    /// Shown instead of the icon set while high contrast is on
+    high_contrast_icon_set: Option<IconSet>,
+}
+
+impl<G> TrayController<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Wraps a tray icon, the accessibility settings are detected with [`A11yPrefs::detect`].
+    pub fn new(tray: TrayIcon, menu_manager: MenuManager<G>) -> Self {
+        let a11y_prefs = A11yPrefs::detect();
+        let mut icon_state = IconState::default();
+        icon_state.set_reduced_motion(a11y_prefs.is_reduced_motion());
+
+        TrayController {
+            tray,
+            menu_manager,
+            click_bindings: Vec::new(),
+            tooltip_template: None,
+            tooltip_vars: HashMap::new(),
+            shutdown_hook: None,
+            icon_state,
+            menu: None,
+            icon_set: None,
+            icon_set_cache: IconCache::new(ICON_SET_CACHE_CAPACITY),
+            scale_factor: 1.0,
+            icon_set_size: None,
+            a11y_prefs,
+            high_contrast_icon_set: None,
+        }
+    }
+
+    /// Builds the tray icon, [`Error::TrayUnsupported`] if the session has no system tray.
+    ///
+    /// See [`is_tray_supported`], and [`TrayHost`](crate::TrayHost) to keep the menu
+    /// manager working without a tray.
+    pub fn try_new(builder: TrayIconBuilder, menu_manager: MenuManager<G>) -> Result<Self, Error> {
+        Self::build(builder, menu_manager).map_err(|failed| failed.0)
+    }
+
+    /// Builds the tray icon, the menu manager is given back on failure.
+    pub(crate) fn build(
+        builder: TrayIconBuilder,
+        menu_manager: MenuManager<G>,
+    ) -> Result<Self, Box<(Error, MenuManager<G>)>> {
+        if !is_tray_supported() {
+            return Err(Box::new((Error::TrayUnsupported, menu_manager)));
+        }
+
+        match builder.build() {
+            Ok(tray) => Ok(Self::new(tray, menu_manager)),
+            Err(e) => Err(Box::new((e.into(), menu_manager))),
+        }
+    }
+
+    /// Forwards both menu events and tray icon events to one handler.
+    ///
+    /// NOTE: This replaces the handlers of `MenuEvent::set_event_handler` and
+    /// `TrayIconEvent::set_event_handler`, which can only be set once.
+    /// It is also required for the menus of [`TrayController::set_click_menus`].
+    pub fn set_event_handler<F>(handler: F)
+    where
+        F: Fn(TrayEvent) + Send + Sync + 'static,
+    {
+        Self::set_event_handler_with_policy(PanicPolicy::Propagate, handler);
+    }
+
+    /// Same as [`TrayController::set_event_handler`], with a policy for a panicking handler.
+    ///
+    /// The handler is called from the platform event loop, where an unwinding panic can
+    /// abort the process and leave the tray icon behind. [`PanicPolicy::Continue`] drops
+    /// the event instead.
+    pub fn set_event_handler_with_policy<F>(policy: PanicPolicy, handler: F)
+    where
+        F: Fn(TrayEvent) + Send + Sync + 'static,
+    {
+        let handler = std::sync::Arc::new(handler);
+
+        let menu_handler = handler.clone();
+        MenuEvent::set_event_handler(Some(move |event| {
+            call_with_policy(policy, || menu_handler(TrayEvent::Menu(event)));
+        }));
+        TrayIconEvent::set_event_handler(Some(move |event| {
+            swap_click_menu(&event);
+            call_with_policy(policy, || handler(TrayEvent::Tray(event)));
+        }));
+    }
+
+    /// Binds a tray icon interaction to an action.
+    ///
+    /// Binding the left click to [`TrayAction::ShowMenu`] lets the platform open
+    /// the menu on left click, binding it to anything else stops it from doing so.
+    pub fn bind(&mut self, click: TrayClick, action: TrayAction) {
+        if click == TrayClick::Click(MouseButton::Left) {
+            self.tray
+                .set_show_menu_on_left_click(action == TrayAction::ShowMenu);
+        }
+
+        self.unbind(&click);
+        self.click_bindings.push((click, action));
+    }
+
+    /// Removes the binding of a tray icon interaction.
+    pub fn unbind(&mut self, click: &TrayClick) {
+        self.click_bindings
+            .retain(|(bound_click, _)| bound_click != click);
+    }
+
+    /// Sets the tray menu, and remembers it for [`TrayController::show_menu`].
+    pub fn set_menu(&mut self, menu: Option<Menu>) {
+        self.tray
+            .set_menu(menu.clone().map(|menu| Box::new(menu) as Box<_>));
+        self.menu = menu;
+    }
+
+    /// Shows the tray menu at the tray icon, e.g. from a global hotkey.
+    ///
+    /// Returns `false` if the platform can't show it, or if no menu was set with
+    /// [`TrayController::set_menu`] or [`TrayController::set_click_menus`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows:** The menu pops up at the icon, or at the cursor if the icon position
+    ///   is unknown. This blocks until the menu is closed, a clicked item emits its menu
+    ///   event as usual.
+    /// - **macOS:** The menu pops up below the status item, and `false` is returned off the
+    ///   main thread. This blocks until the menu is closed, a clicked item emits its menu
+    ///   event as usual.
+    /// - **Linux:** Unsupported, AppIndicator menus can only be opened by the panel.
+    pub fn show_menu(&self) -> bool {
+        let Some(menu) = &self.menu else {
+            return false;
+        };
+
+        #[cfg(target_os = "windows")]
+        {
+            use muda::ContextMenu;
+            use tray_icon::dpi::Position;
+
+            trace_event!(debug, "show tray menu");
+
+            // The left click menu may be swapped in, show the full menu
+            self.tray.set_menu(Some(Box::new(menu.clone())));
+            let position = self
+                .tray
+                .rect()
+                .map(|rect| Position::Physical(rect.position.cast()));
+
+            // SAFETY: the window handle is valid as long as the tray icon
+            unsafe {
+                menu.show_context_menu_for_hwnd(self.tray.window_handle() as isize, position);
+            }
+            true
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use muda::ContextMenu;
+            use objc2::MainThreadMarker;
+            use objc2::rc::Retained;
+            use tray_icon::dpi::{LogicalPosition, Position};
+
+            // AppKit views can only be used on the main thread
+            let Some(mtm) = MainThreadMarker::new() else {
+                return false;
+            };
+            let Some(button) = self
+                .tray
+                .ns_status_item()
+                .and_then(|status_item| status_item.button(mtm))
+            else {
+                return false;
+            };
+
+            trace_event!(debug, "show tray menu");
+
+            // Below the status item, like a click on it
+            let position = Position::Logical(LogicalPosition::new(0.0, button.frame().size.height));
+
+            // SAFETY: the button is installed in the status bar window as long as the tray icon
+            unsafe {
+                menu.show_context_menu_for_nsview(Retained::as_ptr(&button).cast(), Some(position))
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            let _ = menu;
+            false
+        }
+    }
+
+    /// Uses a compact menu for left click and a full menu for right click.
+    ///
+    /// Controls of both menus are registered in the same [`MenuManager`].
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / macOS:** The menus are swapped when the mouse button is pressed,
+    ///   which requires [`TrayController::set_event_handler`].
+    /// - **Linux:** Click events are not emitted, so the right click menu is always shown.
+    pub fn set_click_menus(&mut self, left_click_menu: Menu, right_click_menu: Menu) {
+        self.tray.set_menu(Some(Box::new(right_click_menu.clone())));
+        self.menu = Some(right_click_menu.clone());
+
+        if cfg!(any(target_os = "windows", target_os = "macos")) {
+            self.bind(TrayClick::Click(MouseButton::Left), TrayAction::ShowMenu);
+
+            CLICK_MENUS.with_borrow_mut(|click_menus| {
+                click_menus.retain(|menus| menus.tray.id() != self.tray.id());
+                click_menus.push(ClickMenus {
+                    tray: self.tray.clone(),
+                    left_click_menu,
+                    right_click_menu,
+                });
+            });
+        }
+    }
+
+    /// Converts a tray icon event into the bound action.
+    ///
+    /// Returns `None` for events of other tray icons and for mouse movements.
+    /// When the mouse enters the tray icon, lazy submenus are refreshed.
+    pub fn handle_tray_event(&mut self, event: &TrayIconEvent) -> Option<TrayAction> {
+        if event.id() != self.tray.id() {
+            return None;
+        }
+
+        if let Some(scale_factor) = detect_scale_factor(event) {
+            let result = self.set_scale_factor(scale_factor);
+            self.menu_manager.report(result);
+        }
+
+        let click = match event {
+            TrayIconEvent::Enter { .. } => {
+                let result = self.menu_manager.refresh_lazy_submenus();
+                self.menu_manager.report(result);
+                return None;
+            }
+            TrayIconEvent::Click {
+                button,
+                button_state: MouseButtonState::Up,
+                ..
+            } => TrayClick::Click(*button),
+            TrayIconEvent::DoubleClick { button, .. } => TrayClick::DoubleClick(*button),
+            _ => return None,
+        };
+
+        Some(
+            self.click_bindings
+                .iter()
+                .find(|(bound_click, _)| *bound_click == click)
+                .map(|(_, action)| *action)
+                .unwrap_or(TrayAction::Click(click)),
+        )
+    }
+
+    /// Updates the menu state for a menu event, and callback the menu control.
+    ///
+    /// See [`MenuManager::update`].
+    pub fn handle_menu_event(
+        &mut self,
+        event: &MenuEvent,
+        callback: impl Fn(Option<&MenuControl<G>>),
+    ) {
+        self.menu_manager.update(event.id(), callback);
+    }
+
+    /// Activates a menu profile, returns `false` if the profile is not defined.
+    ///
+    /// See [`MenuManager::activate_profile`].
+    pub fn activate_profile(&mut self, name: &str) -> bool {
+        self.menu_manager.activate_profile(name)
+    }
+
+    /// Sets a tooltip template like `"MyApp — {status} ({count} items)"`, and renders it.
+    ///
+    /// Placeholders are replaced by the variables of [`TrayController::set_tooltip_var`].
+    pub fn set_tooltip_template(&mut self, template: &str) -> Result<(), Error> {
+        self.tooltip_template = Some(template.to_owned());
+        self.render_tooltip()
+    }
+
+    /// Sets a tooltip variable, and re-renders the tooltip if it changed.
+    pub fn set_tooltip_var(&mut self, name: &str, value: impl Display) -> Result<(), Error> {
+        let value = value.to_string();
+        if self.tooltip_vars.get(name) == Some(&value) {
+            return Ok(());
+        }
+
+        self.tooltip_vars.insert(name.to_owned(), value);
+
+        match &self.tooltip_template {
+            Some(tooltip_template) if template::references_var(tooltip_template, name) => {
+                self.render_tooltip()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn render_tooltip(&self) -> Result<(), Error> {
+        if let Some(tooltip_template) = &self.tooltip_template {
+            self.tray.set_tooltip(Some(template::render_template(
+                tooltip_template,
+                &self.tooltip_vars,
+            )))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the normal icon of the tray icon, shown while attention is off.
+    ///
+    /// Use this instead of `TrayIcon::set_icon`, so [`TrayController::attention`] can
+    /// restore the icon.
+    pub fn set_icon(&mut self, icon: Option<Icon>) -> Result<(), Error> {
+        self.icon_set = None;
+        self.icon_set_size = None;
+        self.icon_state.set_icon(icon);
+        self.refresh_icon()
+    }
+
+    /// Sets the normal icon from images of several sizes, the image matching the scale
+    /// factor is shown.
+    ///
+    /// The image is picked again when the scale factor changes, see
+    /// [`TrayController::set_scale_factor`]. [`TrayController::set_icon`] replaces the set.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows:** The scale factor is detected from the size of the tray icon, when the
+    ///   icon set is set and on each tray icon event.
+    /// - **macOS / Linux:** The scale factor is not detected, set it from the windowing
+    ///   library, e.g. winit's `WindowEvent::ScaleFactorChanged`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tray_controls::icons::{self, IconSet};
+    /// use tray_controls::{MenuManager, TrayController};
+    /// use tray_icon::TrayIconBuilder;
+    ///
+    /// let tray = TrayIconBuilder::new().build().unwrap();
+    /// let mut controller = TrayController::new(tray, MenuManager::<&str>::new());
+    ///
+    /// let icon_set = [16, 24, 32, 64]
+    ///     .into_iter()
+    ///     .fold(IconSet::new(), |icon_set, size| {
+    ///         icon_set.with_image(icons::solid([255, 0, 0, 255], size))
+    ///     });
+    /// controller.set_icon_set(Some(icon_set)).unwrap();
+    ///
+    /// // e.g. on `WindowEvent::ScaleFactorChanged`
+    /// controller.set_scale_factor(1.5).unwrap();
+    /// ```
+    pub fn set_icon_set(&mut self, icon_set: Option<IconSet>) -> Result<(), Error> {
+        self.icon_set = icon_set;
+        self.icon_set_cache.clear();
+        self.icon_set_size = None;
+
+        if let Some(rect) = self.tray.rect()
+            && let Some(scale_factor) = scale_factor_of(rect)
+        {
+            self.scale_factor = scale_factor;
+        }
+        self.refresh_icon_set()
+    }
+
+    /// Sets the display scale factor, e.g. `1.5` at 150% scaling, and picks the image of
+    /// the icon set again.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) -> Result<(), Error> {
+        if scale_factor <= 0.0 || scale_factor == self.scale_factor {
+            return Ok(());
+        }
+
+        trace_event!(debug, scale_factor, "tray scale factor changed");
+
+        self.scale_factor = scale_factor;
+        self.refresh_icon_set()
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Sets the icon set shown instead of the icon set of [`TrayController::set_icon_set`]
+    /// while high contrast is on, see [`TrayController::a11y_prefs`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tray_controls::icons::{self, IconSet};
+    /// use tray_controls::{MenuManager, TrayController};
+    /// use tray_icon::TrayIconBuilder;
+    ///
+    /// let tray = TrayIconBuilder::new().build().unwrap();
+    /// let mut controller = TrayController::new(tray, MenuManager::<&str>::new());
+    ///
+    /// let colored = IconSet::new().with_image(icons::solid([0, 120, 215, 255], 32));
+    /// let contrast = IconSet::new().with_image(icons::solid([255, 255, 255, 255], 32));
+    /// controller.set_icon_set(Some(colored)).unwrap();
+    /// controller.set_high_contrast_icon_set(Some(contrast)).unwrap();
+    /// ```
+    pub fn set_high_contrast_icon_set(&mut self, icon_set: Option<IconSet>) -> Result<(), Error> {
+        self.high_contrast_icon_set = icon_set;
+        if self.a11y_prefs.is_high_contrast() {
+            self.icon_set_cache.clear();
+            self.icon_set_size = None;
+        }
+        self.refresh_icon_set()
+    }
+
+    /// Gets the accessibility settings the tray icon follows.
+    ///
+    /// With reduced motion, the attention icon doesn't flash. With high contrast, the icon
+    /// set of [`TrayController::set_high_contrast_icon_set`] is shown.
+    pub fn a11y_prefs(&self) -> A11yPrefs {
+        self.a11y_prefs
+    }
+
+    /// Sets the accessibility settings the tray icon follows, and updates the icon.
+    ///
+    /// They are detected once when the controller is created, pass [`A11yPrefs::detect`]
+    /// to detect them again, or override them from an in-app setting.
+    pub fn set_a11y_prefs(&mut self, a11y_prefs: A11yPrefs) -> Result<(), Error> {
+        if a11y_prefs == self.a11y_prefs {
+            return Ok(());
+        }
+
+        trace_event!(debug, ?a11y_prefs, "tray accessibility settings changed");
+
+        if a11y_prefs.is_high_contrast() != self.a11y_prefs.is_high_contrast()
+            && self.high_contrast_icon_set.is_some()
+        {
+            self.icon_set_cache.clear();
+            self.icon_set_size = None;
+        }
+        self.a11y_prefs = a11y_prefs;
+        self.icon_state
+            .set_reduced_motion(a11y_prefs.is_reduced_motion());
+        self.refresh_icon_set()?;
+        self.refresh_icon()
+    }
+
+    fn refresh_icon_set(&mut self) -> Result<(), Error> {
+        let icon_set = match (&self.icon_set, &self.high_contrast_icon_set) {
+            (Some(_), Some(high_contrast)) if self.a11y_prefs.is_high_contrast() => high_contrast,
+            (Some(icon_set), _) => icon_set,
+            (None, _) => return Ok(()),
+        };
+
+        let size = icon_set.size_for(self.scale_factor);
+        if self.icon_set_size == Some(size) {
+            return Ok(());
+        }
+
+        if !icon_set.is_empty() {
+            let icon = self.icon_set_cache.get_or_convert(size, || {
+                icon_set.image_for(size).expect("the icon set is not empty")
+            })?;
+            self.icon_state.set_icon(Some(icon));
+            self.icon_set_size = Some(size);
+        }
+        self.refresh_icon()
+    }
+
+    /// Sets the icon shown by [`TrayController::attention`], e.g. the icon with a badge.
+    ///
+    /// With a flash interval, the attention icon and the normal icon alternate, call
+    /// [`TrayController::tick_attention`] periodically to flash it. With reduced motion (see
+    /// [`TrayController::a11y_prefs`]), the attention icon is shown without flashing.
+    pub fn set_attention_icon(
+        &mut self,
+        icon: Option<Icon>,
+        flash_interval: Option<Duration>,
+    ) -> Result<(), Error> {
+        self.icon_state.set_attention_icon(icon, flash_interval);
+        self.refresh_icon()
+    }
+
+    /// Draws the eye of the user to the tray icon, e.g. when something requires action.
+    ///
+    /// The attention icon of [`TrayController::set_attention_icon`] is shown until attention
+    /// is turned off, then the normal icon of [`TrayController::set_icon`] is restored.
+    /// Set the normal icon first, the icon of the tray icon builder is not known here.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / Linux / macOS:** tray-icon exposes no native flashing or attention
+    ///   state for tray icons, so attention swaps the icon on every platform. Without an
+    ///   attention icon, only [`TrayController::is_attention`] changes.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tray_controls::{MenuManager, TrayController};
+    /// use tray_icon::{Icon, TrayIconBuilder};
+    ///
+    /// let tray = TrayIconBuilder::new().build().unwrap();
+    /// let mut controller = TrayController::new(tray, MenuManager::<&str>::new());
+    ///
+    /// let icon = Icon::from_rgba(vec![0, 0, 0, 255], 1, 1).unwrap();
+    /// let badge = Icon::from_rgba(vec![255, 0, 0, 255], 1, 1).unwrap();
+    /// controller.set_icon(Some(icon)).unwrap();
+    /// controller.set_attention_icon(Some(badge), Some(Duration::from_millis(500))).unwrap();
+    /// controller.attention(true).unwrap();
+    ///
+    /// // Call periodically, e.g. from your event loop
+    /// controller.tick_attention().unwrap();
+    /// ```
+    pub fn attention(&mut self, enabled: bool) -> Result<(), Error> {
+        trace_event!(debug, enabled, "tray attention");
+
+        self.icon_state.set_attention(enabled, Instant::now());
+        self.refresh_icon()
+    }
+
+    pub fn is_attention(&self) -> bool {
+        self.icon_state.is_attention()
+    }
+
+    /// Flashes the attention icon if its interval has elapsed.
+    ///
+    /// Returns when to call it next, `None` if the icon is not flashing.
+    pub fn tick_attention(&mut self) -> Result<Option<Instant>, Error> {
+        self.refresh_icon()?;
+        Ok(self.icon_state.next_flash(Instant::now()))
+    }
+
+    fn refresh_icon(&mut self) -> Result<(), Error> {
+        if let Some(icon) = self.icon_state.next_icon(Instant::now()) {
+            self.tray.set_icon(icon)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the hook called first on shutdown, e.g. to persist the menu state.
+    pub fn set_shutdown_hook(&mut self, hook: impl FnOnce(&MenuManager<G>) + 'static) {
+        self.shutdown_hook = Some(Box::new(hook));
+    }
+
+    /// Cleans up the tray: calls the shutdown hook, unregisters the event handlers, and
+    /// removes the tray icon before the menus are dropped.
+    ///
+    /// NOTE: The event handlers are global, so the handlers of other tray icons are
+    /// unregistered too.
+    pub fn shutdown(mut self) -> Result<(), Error> {
+        self.cleanup()
+    }
+
+    /// Wraps the controller in a [`TrayGuard`], which shuts it down when dropped.
+    pub fn into_guard(self) -> TrayGuard<G> {
+        TrayGuard {
+            controller: Some(self),
+        }
+    }
+
+    fn cleanup(&mut self) -> Result<(), Error> {
+        if let Some(hook) = self.shutdown_hook.take() {
+            hook(&self.menu_manager);
+        }
+
+        MenuEvent::set_event_handler(None::<fn(MenuEvent)>);
+        TrayIconEvent::set_event_handler(None::<fn(TrayIconEvent)>);
+
+        // Hide the icon first, a removed menu must not be shown by a lingering icon
+        let visible = self.tray.set_visible(false);
+        self.tray.set_menu(None);
+        visible?;
+
+        Ok(())
+    }
+
+    /// Rebuilds the tray menu from its current state, and sets it on the tray icon.
+    ///
+    /// See [`MenuManager::rebuild_menu`]. A rebuilt click menu of
+    /// [`TrayController::set_click_menus`] is swapped in as well.
+    pub fn rebuild_menu(&mut self, menu: &Menu) -> Result<Menu, Error> {
+        let rebuilt = self.menu_manager.rebuild_menu(menu)?;
+
+        // Only the left click menu waits for its click, the right click menu stays set
+        let mut is_left_click_menu = false;
+        CLICK_MENUS.with_borrow_mut(|click_menus| {
+            if let Some(menus) = click_menus
+                .iter_mut()
+                .find(|menus| menus.tray.id() == self.tray.id())
+            {
+                if menus.left_click_menu.id() == rebuilt.id() {
+                    menus.left_click_menu = rebuilt.clone();
+                    is_left_click_menu = true;
+                }
+                if menus.right_click_menu.id() == rebuilt.id() {
+                    menus.right_click_menu = rebuilt.clone();
+                    is_left_click_menu = false;
+                }
+            }
+        });
+
+        if !is_left_click_menu {
+            self.tray.set_menu(Some(Box::new(rebuilt.clone())));
+        }
+        if self
+            .menu
+            .as_ref()
+            .is_some_and(|menu| menu.id() == rebuilt.id())
+        {
+            self.menu = Some(rebuilt.clone());
+        }
+
+        Ok(rebuilt)
+    }
+
+    pub fn tray(&self) -> &TrayIcon {
+        &self.tray
+    }
+
+    pub fn menu_manager(&self) -> &MenuManager<G> {
+        &self.menu_manager
+    }
+
+    pub fn menu_manager_mut(&mut self) -> &mut MenuManager<G> {
+        &mut self.menu_manager
+    }
+}
+
+impl<G> Drop for TrayController<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    fn drop(&mut self) {
+        // Release the tray icon clone kept for the click menus
+        let _ = CLICK_MENUS.try_with(|click_menus| {
+            click_menus
+                .borrow_mut()
+                .retain(|menus| menus.tray.id() != self.tray.id());
+        });
+    }
+}
+
+/// Guard that shuts down a [`TrayController`] when dropped, see [`TrayController::shutdown`]
+///
+/// The tray icon is removed even when the application exits through a panic, instead of
+/// leaving a dangling icon until the mouse hovers it (**Windows**).
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{MenuManager, TrayController};
+/// use tray_icon::TrayIconBuilder;
+///
+/// let tray = TrayIconBuilder::new().build().unwrap();
+/// let controller = TrayController::new(tray, MenuManager::<&str>::new()).into_guard();
+///
+/// // The guard derefs to the controller
+/// controller.tray().set_tooltip(Some("tray-controls")).unwrap();
+/// ```
+pub struct TrayGuard<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    controller: Option<TrayController<G>>,
+}
+
+impl<G> TrayGuard<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Takes the controller back without shutting it down.
+    pub fn into_inner(mut self) -> TrayController<G> {
+        self.controller
+            .take()
+            .expect("the controller is only taken on drop")
+    }
+}
+
+impl<G> Deref for TrayGuard<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    type Target = TrayController<G>;
+
+    fn deref(&self) -> &Self::Target {
+        self.controller
+            .as_ref()
+            .expect("the controller is only taken on drop")
+    }
+}
+
+impl<G> DerefMut for TrayGuard<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.controller
+            .as_mut()
+            .expect("the controller is only taken on drop")
+    }
+}
+
+impl<G> Drop for TrayGuard<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    fn drop(&mut self) {
+        if let Some(controller) = self.controller.take() {
+            let _ = controller.shutdown();
+        }
+    }
+}
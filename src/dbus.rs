@@ -0,0 +1,244 @@
+use std::hash::Hash;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use futures_channel::oneshot;
+use muda::MenuId;
+use zbus::blocking::{Connection, connection};
+use zbus::fdo;
+
+use crate::{ChangeOrigin, CheckRef, Error, MenuControl, MenuManager};
+
+/// Object path of the exported menu manager
+const OBJECT_PATH: &str = "/io/github/iKineticate/TrayControls";
+
+/// Menu ID, parent menu ID, text, enabled, checkable and checked state of a menu control
+type ItemState = (String, String, String, bool, bool, bool);
+
+enum Command {
+    SetChecked(MenuId, bool),
+    SelectRadio(MenuId),
+    SetText(MenuId, String),
+    Trigger(MenuId),
+    GetState,
+}
+
+struct Request {
+    command: Command,
+    reply: oneshot::Sender<Result<Vec<ItemState>, String>>,
+}
+
+/// Server exporting the menu over the D-Bus session bus, e.g. for desktop scripts
+///
+/// The `io.github.iKineticate.TrayControls` interface is served at
+/// `/io/github/iKineticate/TrayControls`:
+///
+/// | Method | Arguments | Reply |
+/// |---|---|---|
+/// | `SetChecked` | `s` menu ID, `b` checked | |
+/// | `SelectRadio` | `s` menu ID | |
+/// | `SetText` | `s` menu ID, `s` text | |
+/// | `Trigger` | `s` menu ID | |
+/// | `GetState` | | `a(sssbbb)` menu ID, parent menu ID, text, enabled, checkable, checked |
+///
+/// `Trigger` clicks a menu control with [`MenuManager::activate`], the others work like the
+/// commands of an [`IpcServer`](crate::IpcServer). The parent menu ID is empty for menu
+/// controls that weren't inserted with [`MenuManager::insert_into`]. A failed call
+/// replies `org.freedesktop.DBus.Error.InvalidArgs`.
+///
+/// The menu manager stays on its thread, so method calls wait until
+/// [`DbusServer::handle_pending`] runs them. Their changes have the
+/// [`ChangeOrigin::Remote`] origin.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{DbusServer, MenuManager};
+///
+/// let mut manager = MenuManager::<()>::new();
+/// let server = DbusServer::bind("com.example.MyApp").unwrap();
+///
+/// // In the event loop of the tray app
+/// server.handle_pending(&mut manager, |_menu_control| {});
+/// ```
+///
+/// From a shell:
+///
+/// ```text
+/// busctl --user introspect com.example.MyApp /io/github/iKineticate/TrayControls
+/// busctl --user call com.example.MyApp /io/github/iKineticate/TrayControls \
+///     io.github.iKineticate.TrayControls SetChecked sb pause_sync true
+/// ```
+pub struct DbusServer {
+    requests: Receiver<Request>,
+    _connection: Connection,
+}
+
+impl DbusServer {
+    /// Connects to the session bus and owns `bus_name`, e.g. `"com.example.MyApp"`.
+    ///
+    /// Fails if the bus name is taken, e.g. by another instance of the app.
+    pub fn bind(bus_name: &str) -> Result<DbusServer, Error> {
+        let (sender, requests) = mpsc::channel();
+        let connection = connection::Builder::session()?
+            .name(bus_name)?
+            .serve_at(OBJECT_PATH, MenuInterface { sender })?
+            .build()?;
+
+        trace_event!(debug, bus_name, "d-bus server bound");
+
+        Ok(DbusServer {
+            requests,
+            _connection: connection,
+        })
+    }
+
+    /// Runs the method calls received since the last call, returns how many ran.
+    ///
+    /// `callback` gets the menu control of a `Trigger` call, see [`MenuManager::update`].
+    pub fn handle_pending<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        callback: impl Fn(Option<&MenuControl<G>>),
+    ) -> usize
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let mut count = 0;
+        while let Ok(request) = self.requests.try_recv() {
+            let reply = manager.with_origin(ChangeOrigin::Remote, |manager| {
+                execute(manager, request.command, &callback)
+            });
+            let _ = request.reply.send(reply);
+            count += 1;
+        }
+        count
+    }
+}
+
+struct MenuInterface {
+    sender: Sender<Request>,
+}
+
+impl MenuInterface {
+    /// Sends a call to the application, and waits for its reply without blocking the bus.
+    async fn call(&self, command: Command) -> fdo::Result<Vec<ItemState>> {
+        let (reply, reply_receiver) = oneshot::channel();
+        self.sender
+            .send(Request { command, reply })
+            .map_err(|_| fdo::Error::Failed("the server is shutting down".to_owned()))?;
+
+        reply_receiver
+            .await
+            .map_err(|_| fdo::Error::Failed("the application did not answer".to_owned()))?
+            .map_err(fdo::Error::InvalidArgs)
+    }
+}
+
+#[zbus::interface(name = "io.github.iKineticate.TrayControls")]
+impl MenuInterface {
+    async fn set_checked(&self, menu_id: String, checked: bool) -> fdo::Result<()> {
+        self.call(Command::SetChecked(MenuId::new(menu_id), checked))
+            .await
+            .map(drop)
+    }
+
+    async fn select_radio(&self, menu_id: String) -> fdo::Result<()> {
+        self.call(Command::SelectRadio(MenuId::new(menu_id)))
+            .await
+            .map(drop)
+    }
+
+    async fn set_text(&self, menu_id: String, text: String) -> fdo::Result<()> {
+        self.call(Command::SetText(MenuId::new(menu_id), text))
+            .await
+            .map(drop)
+    }
+
+    async fn trigger(&self, menu_id: String) -> fdo::Result<()> {
+        self.call(Command::Trigger(MenuId::new(menu_id)))
+            .await
+            .map(drop)
+    }
+
+    async fn get_state(&self) -> fdo::Result<Vec<ItemState>> {
+        self.call(Command::GetState).await
+    }
+}
+
+fn execute<G>(
+    manager: &mut MenuManager<G>,
+    command: Command,
+    callback: &impl Fn(Option<&MenuControl<G>>),
+) -> Result<Vec<ItemState>, String>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    match command {
+        Command::SetChecked(menu_id, checked) => {
+            if registered(manager, &menu_id)?.as_check_menu().is_none() {
+                return Err(format!("menu `{}` can't be checked", menu_id.as_ref()));
+            }
+            manager.batch(|batch| {
+                batch.set_checked(&menu_id, checked);
+            });
+        }
+        Command::SelectRadio(menu_id) => {
+            if !matches!(
+                registered(manager, &menu_id)?.check_ref(),
+                Some(CheckRef::Radio(..))
+            ) {
+                return Err(format!("menu `{}` is not a radio", menu_id.as_ref()));
+            }
+            manager.batch(|batch| {
+                batch.set_checked(&menu_id, true);
+            });
+        }
+        Command::SetText(menu_id, text) => {
+            registered(manager, &menu_id)?;
+            manager.batch(|batch| {
+                batch.set_text(&menu_id, &text);
+            });
+        }
+        Command::Trigger(menu_id) => {
+            let menu = registered(manager, &menu_id)?;
+            if !menu.is_enabled() {
+                return Err(format!("menu `{}` is disabled", menu_id.as_ref()));
+            }
+            manager.activate(&menu_id, callback);
+        }
+        Command::GetState => {
+            return Ok(manager
+                .state()
+                .iter()
+                .map(|(menu_id, item)| {
+                    let parent_id = manager
+                        .id_to_parent
+                        .get(menu_id)
+                        .map(|parent_id| parent_id.as_ref().to_owned())
+                        .unwrap_or_default();
+                    (
+                        menu_id.as_ref().to_owned(),
+                        parent_id,
+                        item.text().to_owned(),
+                        item.is_enabled(),
+                        item.is_checked().is_some(),
+                        item.is_checked().unwrap_or(false),
+                    )
+                })
+                .collect());
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+fn registered<'a, G>(
+    manager: &'a MenuManager<G>,
+    menu_id: &MenuId,
+) -> Result<&'a MenuControl<G>, String>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    manager
+        .get_menu_item_from_id(menu_id)
+        .ok_or_else(|| format!("menu `{}` is not registered", menu_id.as_ref()))
+}
@@ -0,0 +1,224 @@
+use std::io;
+use std::process::{Command, Output};
+
+use crate::Platform;
+
+/// Yes/no question asked before the action of a menu control runs (**requires the `dialogs` feature**)
+///
+/// See [`MenuManager::set_confirmation`](crate::MenuManager::set_confirmation). The dialog
+/// is modal, the calling thread waits until it is closed.
+///
+/// ## Platform-specific:
+///
+/// - **Windows:** A message box shown by PowerShell.
+/// - **macOS:** A dialog shown by `osascript`.
+/// - **Linux:** A dialog shown by `zenity` or `kdialog`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confirmation {
+    message: String,
+    title: String,
+    /// Platforms asking the question, `None` for all
+    platforms: Option<Vec<Platform>>,
+}
+
+impl Confirmation {
+    pub fn new(message: impl Into<String>) -> Self {
+        Confirmation {
+            message: message.into(),
+            title: String::new(),
+            platforms: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Asks only on some platforms, the action runs right away on the others.
+    ///
+    /// e.g. macOS apps conventionally quit without asking.
+    pub fn platforms(mut self, platforms: &[Platform]) -> Self {
+        self.platforms = Some(platforms.to_vec());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Asks the question if it applies to the current platform, returns `true` if confirmed.
+    pub(crate) fn ask(&self) -> io::Result<bool> {
+        if self
+            .platforms
+            .as_ref()
+            .is_some_and(|platforms| !platforms.contains(&Platform::current()))
+        {
+            return Ok(true);
+        }
+
+        let output = run_first(platform::confirm_commands(&self.title, &self.message))?;
+        Ok(output.status.success()
+            && platform::CONFIRM_ANSWER
+                .is_none_or(|answer| String::from_utf8_lossy(&output.stdout).trim() == answer))
+    }
+}
+
+/// Asks for a line of text with a native input dialog, `None` if it was cancelled.
+///
+/// PowerShell on **Windows**, `osascript` on **macOS**, and `zenity` or `kdialog` on **Linux**.
+pub(crate) fn prompt_text(title: &str, prompt: &str, default: &str) -> io::Result<Option<String>> {
+    let output = run_first(platform::prompt_commands(title, prompt, default))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    Ok(Some(text.to_owned()).filter(|text| platform::ACCEPTS_EMPTY || !text.is_empty()))
+}
+
+/// Runs the first installed dialog tool.
+fn run_first(commands: Vec<Command>) -> io::Result<Output> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no dialog tool found");
+
+    for mut command in commands {
+        match command.output() {
+            Ok(output) => return Ok(output),
+            // Try the next tool only if this one is not installed
+            Err(e) if e.kind() == io::ErrorKind::NotFound => last_error = e,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_error)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    /// `InputBox` returns an empty string when cancelled
+    pub(super) const ACCEPTS_EMPTY: bool = false;
+    /// Printed by `MessageBox` for the yes button
+    pub(super) const CONFIRM_ANSWER: Option<&str> = Some("Yes");
+    /// Keeps PowerShell from flashing a console window in GUI apps
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    pub(super) fn prompt_commands(title: &str, prompt: &str, default: &str) -> Vec<Command> {
+        // The texts are passed in variables, so they need no quoting
+        let script = "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+            Add-Type -AssemblyName Microsoft.VisualBasic; \
+            [Microsoft.VisualBasic.Interaction]::InputBox($env:TC_PROMPT, $env:TC_TITLE, $env:TC_DEFAULT)";
+
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("TC_TITLE", title)
+            .env("TC_PROMPT", prompt)
+            .env("TC_DEFAULT", default)
+            .creation_flags(CREATE_NO_WINDOW);
+        vec![command]
+    }
+
+    pub(super) fn confirm_commands(title: &str, message: &str) -> Vec<Command> {
+        let script = "Add-Type -AssemblyName PresentationFramework; \
+            [System.Windows.MessageBox]::Show($env:TC_MESSAGE, $env:TC_TITLE, 'YesNo', 'Question')";
+
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("TC_TITLE", title)
+            .env("TC_MESSAGE", message)
+            .creation_flags(CREATE_NO_WINDOW);
+        vec![command]
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    pub(super) const ACCEPTS_EMPTY: bool = true;
+    pub(super) const CONFIRM_ANSWER: Option<&str> = Some("Yes");
+
+    pub(super) fn prompt_commands(title: &str, prompt: &str, default: &str) -> Vec<Command> {
+        // The texts are passed as arguments, so they need no quoting
+        let mut command = Command::new("osascript");
+        command
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "display dialog (item 2 of argv) default answer (item 3 of argv) with title (item 1 of argv)",
+                "-e",
+                "return text returned of result",
+                "-e",
+                "end run",
+            ])
+            .args([title, prompt, default]);
+        vec![command]
+    }
+
+    pub(super) fn confirm_commands(title: &str, message: &str) -> Vec<Command> {
+        let mut command = Command::new("osascript");
+        command
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "display dialog (item 2 of argv) buttons {\"No\", \"Yes\"} default button \"Yes\" with title (item 1 of argv) with icon caution",
+                "-e",
+                "return button returned of result",
+                "-e",
+                "end run",
+            ])
+            .args([title, message]);
+        vec![command]
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    pub(super) const ACCEPTS_EMPTY: bool = true;
+    /// The answer is the exit status
+    pub(super) const CONFIRM_ANSWER: Option<&str> = None;
+
+    pub(super) fn prompt_commands(title: &str, prompt: &str, default: &str) -> Vec<Command> {
+        let mut zenity = Command::new("zenity");
+        zenity.args([
+            "--entry",
+            "--title",
+            title,
+            "--text",
+            &markup(prompt),
+            "--entry-text",
+            default,
+        ]);
+
+        let mut kdialog = Command::new("kdialog");
+        kdialog.args(["--title", title, "--inputbox", prompt, default]);
+
+        vec![zenity, kdialog]
+    }
+    pub(super) fn confirm_commands(title: &str, message: &str) -> Vec<Command> {
+        let mut zenity = Command::new("zenity");
+        zenity.args(["--question", "--title", title, "--text", &markup(message)]);
+
+        let mut kdialog = Command::new("kdialog");
+        kdialog.args(["--title", title, "--yesno", message]);
+
+        vec![zenity, kdialog]
+    }
+
+    /// Escapes a text for zenity, which parses it as Pango markup.
+    fn markup(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use muda::{MenuId, accelerator::Accelerator};
+
+/// Errors returned by tray-controls
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The parent `Menu` or `Submenu` is not registered in the menu manager
+    ParentNotFound(MenuId),
+    /// The menu control is not registered in the menu manager
+    MenuNotFound(MenuId),
+    /// The accelerator is already used by another menu control
+    AcceleratorConflict {
+        accelerator: Accelerator,
+        menu_id: MenuId,
+    },
+    /// The removed menu control was the default radio of its group, the group has no default radio anymore
+    DefaultRadioRemoved(MenuId),
+    /// The default radio menu ID is not a radio of the group, e.g. not registered yet
+    UnresolvedDefault(MenuId),
+    /// The menu control has another ID than the one it replaces
+    IdMismatch { expected: MenuId, found: MenuId },
+    /// The underlying menu operation failed
+    Menu(muda::Error),
+    /// The underlying tray icon operation failed
+    #[cfg(feature = "tray")]
+    Tray(tray_icon::Error),
+    /// Reading or writing a platform setting failed, e.g. the autostart entry
+    Io(std::io::Error),
+    /// The pixels of a drawn icon are invalid
+    #[cfg(feature = "tray")]
+    Icon(tray_icon::BadIcon),
+    /// The pixels of a drawn menu item icon are invalid
+    MenuIcon(muda::BadIcon),
+    /// The session has no system tray, e.g. a server or a Wayland session without one
+    #[cfg(feature = "tray")]
+    TrayUnsupported,
+    /// The StatusNotifierItem service failed, e.g. the session has no StatusNotifierWatcher
+    #[cfg(all(feature = "ksni", target_os = "linux"))]
+    Sni(ksni::Error),
+    /// The D-Bus connection failed, e.g. the bus name of a `DbusServer` is taken
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    Dbus(zbus::Error),
+    /// Writing the taskbar jump list failed
+    #[cfg(all(feature = "jump-list", windows))]
+    JumpList(windows::core::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ParentNotFound(menu_id) => {
+                write!(f, "parent menu `{}` is not registered", menu_id.0)
+            }
+            Error::MenuNotFound(menu_id) => {
+                write!(f, "menu `{}` is not registered", menu_id.0)
+            }
+            Error::AcceleratorConflict {
+                accelerator,
+                menu_id,
+            } => write!(
+                f,
+                "accelerator `{accelerator:?}` is already used by menu `{}`",
+                menu_id.0
+            ),
+            Error::DefaultRadioRemoved(menu_id) => {
+                write!(
+                    f,
+                    "removed menu `{}` was the default radio of its group",
+                    menu_id.0
+                )
+            }
+            Error::UnresolvedDefault(menu_id) => write!(
+                f,
+                "default radio `{}` is not a radio of the group",
+                menu_id.0
+            ),
+            Error::IdMismatch { expected, found } => write!(
+                f,
+                "menu `{}` can't replace menu `{}`, the IDs differ",
+                found.0, expected.0
+            ),
+            Error::Menu(e) => write!(f, "menu operation failed: {e}"),
+            #[cfg(feature = "tray")]
+            Error::Tray(e) => write!(f, "tray icon operation failed: {e}"),
+            Error::Io(e) => write!(f, "platform operation failed: {e}"),
+            #[cfg(feature = "tray")]
+            Error::Icon(e) => write!(f, "invalid icon: {e}"),
+            Error::MenuIcon(e) => write!(f, "invalid menu icon: {e}"),
+            #[cfg(feature = "tray")]
+            Error::TrayUnsupported => write!(f, "no system tray is available"),
+            #[cfg(all(feature = "ksni", target_os = "linux"))]
+            Error::Sni(e) => write!(f, "status notifier item failed: {e}"),
+            #[cfg(all(feature = "dbus", target_os = "linux"))]
+            Error::Dbus(e) => write!(f, "d-bus operation failed: {e}"),
+            #[cfg(all(feature = "jump-list", windows))]
+            Error::JumpList(e) => write!(f, "jump list operation failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Menu(e) => Some(e),
+            #[cfg(feature = "tray")]
+            Error::Tray(e) => Some(e),
+            Error::Io(e) => Some(e),
+            #[cfg(feature = "tray")]
+            Error::Icon(e) => Some(e),
+            Error::MenuIcon(e) => Some(e),
+            #[cfg(all(feature = "ksni", target_os = "linux"))]
+            Error::Sni(e) => Some(e),
+            #[cfg(all(feature = "dbus", target_os = "linux"))]
+            Error::Dbus(e) => Some(e),
+            #[cfg(all(feature = "jump-list", windows))]
+            Error::JumpList(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<muda::Error> for Error {
+    fn from(e: muda::Error) -> Self {
+        Error::Menu(e)
+    }
+}
+
+#[cfg(feature = "tray")]
+impl From<tray_icon::Error> for Error {
+    fn from(e: tray_icon::Error) -> Self {
+        Error::Tray(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "tray")]
+impl From<tray_icon::BadIcon> for Error {
+    fn from(e: tray_icon::BadIcon) -> Self {
+        Error::Icon(e)
+    }
+}
+
+impl From<muda::BadIcon> for Error {
+    fn from(e: muda::BadIcon) -> Self {
+        Error::MenuIcon(e)
+    }
+}
+
+#[cfg(all(feature = "ksni", target_os = "linux"))]
+impl From<ksni::Error> for Error {
+    fn from(e: ksni::Error) -> Self {
+        Error::Sni(e)
+    }
+}
+
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+impl From<zbus::Error> for Error {
+    fn from(e: zbus::Error) -> Self {
+        Error::Dbus(e)
+    }
+}
+
+#[cfg(all(feature = "jump-list", windows))]
+impl From<windows::core::Error> for Error {
+    fn from(e: windows::core::Error) -> Self {
+        Error::JumpList(e)
+    }
+}
@@ -0,0 +1,60 @@
+/// Describes a group for generic code, e.g. a settings exporter or a debug UI
+///
+/// # Example
+/// ```
+/// use tray_controls::{GroupMeta, MenuManager};
+///
+/// let mut manager = MenuManager::<&str>::new();
+///
+/// manager.set_group_meta(
+///     "theme_group",
+///     GroupMeta::new("Theme").with_description("Color theme of the main window"),
+/// );
+///
+/// let meta = manager.group_meta(&"theme_group").unwrap();
+/// assert_eq!(meta.display_name(), "Theme");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupMeta {
+    display_name: String,
+    description: Option<String>,
+    #[cfg(feature = "serde")]
+    data: serde_json::Value,
+}
+
+impl GroupMeta {
+    pub fn new(display_name: &str) -> Self {
+        GroupMeta {
+            display_name: display_name.to_owned(),
+            description: None,
+            #[cfg(feature = "serde")]
+            data: serde_json::Value::Null,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Attaches arbitrary data to the group (**requires the `serde` feature**).
+    #[cfg(feature = "serde")]
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Gets the arbitrary data of the group, `Value::Null` if none was attached.
+    #[cfg(feature = "serde")]
+    pub fn data(&self) -> &serde_json::Value {
+        &self.data
+    }
+}
@@ -0,0 +1,104 @@
+use std::rc::Rc;
+
+use muda::MenuId;
+
+use crate::menu_key::MenuKey;
+use crate::{FxIndexMap, MenuControl};
+
+/// Cheap copyable key of a registered menu control, returned by [`MenuManager::insert`]
+///
+/// Looking up a handle indexes a slot instead of hashing the `MenuId`, which suits hot
+/// paths like periodic text or icon updates. A handle becomes stale once its menu control
+/// is removed, even if the slot is reused later.
+///
+/// [`MenuManager::insert`]: crate::MenuManager::insert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MenuHandle {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Clone)]
+struct Slot<G> {
+    generation: u32,
+    entry: Option<(Rc<MenuId>, MenuControl<G>)>,
+}
+
+/// Slots of the menu controls, addressed by [`MenuHandle`]
+#[derive(Clone)]
+pub(crate) struct HandleSlots<G> {
+    slots: Vec<Slot<G>>,
+    free_indexes: Vec<u32>,
+    id_to_handle: FxIndexMap<Rc<MenuId>, MenuHandle>,
+}
+
+impl<G: Clone> HandleSlots<G> {
+    pub(crate) fn new() -> Self {
+        HandleSlots {
+            slots: Vec::new(),
+            free_indexes: Vec::new(),
+            id_to_handle: FxIndexMap::default(),
+        }
+    }
+
+    /// Stores a menu control, keeping the handle of a menu control replaced under the same ID.
+    pub(crate) fn insert(
+        &mut self,
+        menu_id: &Rc<MenuId>,
+        menu_control: &MenuControl<G>,
+    ) -> MenuHandle {
+        let entry = Some((menu_id.clone(), menu_control.clone()));
+
+        if let Some(&handle) = self.id_to_handle.get(menu_id) {
+            self.slots[handle.index as usize].entry = entry;
+            return handle;
+        }
+
+        let index = self.free_indexes.pop().unwrap_or_else(|| {
+            self.slots.push(Slot {
+                generation: 0,
+                entry: None,
+            });
+            (self.slots.len() - 1) as u32
+        });
+
+        let slot = &mut self.slots[index as usize];
+        slot.entry = entry;
+
+        let handle = MenuHandle {
+            index,
+            generation: slot.generation,
+        };
+        self.id_to_handle.insert(menu_id.clone(), handle);
+        handle
+    }
+
+    pub(crate) fn remove(&mut self, menu_id: &MenuId) {
+        let Some(handle) = self.id_to_handle.swap_remove(menu_id) else {
+            return;
+        };
+
+        let slot = &mut self.slots[handle.index as usize];
+        slot.entry = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_indexes.push(handle.index);
+    }
+
+    pub(crate) fn get(&self, handle: MenuHandle) -> Option<&(Rc<MenuId>, MenuControl<G>)> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.entry.as_ref())
+    }
+
+    /// Releases unused capacity, the slots are kept so stale handles stay invalid.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+        self.free_indexes.shrink_to_fit();
+        self.id_to_handle.shrink_to_fit();
+    }
+
+    pub(crate) fn handle_of(&self, menu_id: MenuKey) -> Option<MenuHandle> {
+        self.id_to_handle.get(&menu_id).copied()
+    }
+}
@@ -0,0 +1,135 @@
+use std::hash::Hash;
+
+use tray_icon::TrayIconBuilder;
+
+use crate::{MenuManager, TrayController};
+
+/// Tray controller, or the bare menu manager when the session has no system tray
+///
+/// The menu model keeps working without an icon, e.g. for [`IpcServer`](crate::IpcServer)
+/// clients or a D-Bus interface. See [`is_tray_supported`] for the detection.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{MenuManager, TrayHost};
+/// use tray_icon::TrayIconBuilder;
+///
+/// let host = TrayHost::new(TrayIconBuilder::new(), MenuManager::<&str>::new());
+/// if host.is_headless() {
+///     eprintln!("no system tray, the menu is only reachable over IPC");
+/// }
+///
+/// // Works either way
+/// let manager = host.menu_manager();
+/// ```
+pub enum TrayHost<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    Tray(Box<TrayController<G>>),
+    Headless(Box<MenuManager<G>>),
+}
+
+impl<G> TrayHost<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Builds the tray icon, or falls back to headless if there is no system tray or the
+    /// build fails.
+    pub fn new(builder: TrayIconBuilder, menu_manager: MenuManager<G>) -> Self {
+        match TrayController::build(builder, menu_manager) {
+            Ok(controller) => TrayHost::Tray(Box::new(controller)),
+            Err(failed) => {
+                let (_error, menu_manager) = *failed;
+                trace_event!(warn, error = %_error, "no tray icon, running headless");
+                TrayHost::Headless(Box::new(menu_manager))
+            }
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        matches!(self, TrayHost::Headless(_))
+    }
+
+    pub fn controller(&self) -> Option<&TrayController<G>> {
+        match self {
+            TrayHost::Tray(controller) => Some(controller),
+            TrayHost::Headless(_) => None,
+        }
+    }
+
+    pub fn controller_mut(&mut self) -> Option<&mut TrayController<G>> {
+        match self {
+            TrayHost::Tray(controller) => Some(controller),
+            TrayHost::Headless(_) => None,
+        }
+    }
+
+    pub fn menu_manager(&self) -> &MenuManager<G> {
+        match self {
+            TrayHost::Tray(controller) => controller.menu_manager(),
+            TrayHost::Headless(menu_manager) => menu_manager,
+        }
+    }
+
+    pub fn menu_manager_mut(&mut self) -> &mut MenuManager<G> {
+        match self {
+            TrayHost::Tray(controller) => controller.menu_manager_mut(),
+            TrayHost::Headless(menu_manager) => menu_manager,
+        }
+    }
+}
+
+/// Detects whether the session has a system tray to show an icon in.
+///
+/// ## Platform-specific:
+///
+/// - **Windows / macOS:** Always `true`.
+/// - **Linux:** `false` without a display server, or in a Wayland session where no
+///   `StatusNotifierWatcher` owns its D-Bus name, checked with `dbus-send`. X11 sessions
+///   may embed the icon in a legacy tray instead, so they are assumed to have one.
+pub fn is_tray_supported() -> bool {
+    platform::is_tray_supported()
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+mod platform {
+    pub(super) fn is_tray_supported() -> bool {
+        true
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::env;
+    use std::process::Command;
+
+    pub(super) fn is_tray_supported() -> bool {
+        let has_x11 = env::var_os("DISPLAY").is_some_and(|display| !display.is_empty());
+        let has_wayland = env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty());
+
+        match (has_x11, has_wayland) {
+            (false, false) => false,
+            (true, _) => true,
+            (false, true) => has_status_notifier_watcher().unwrap_or(true),
+        }
+    }
+
+    /// Asks the session bus for a watcher, `None` if it can't be asked.
+    fn has_status_notifier_watcher() -> Option<bool> {
+        let output = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.DBus",
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus.NameHasOwner",
+                "string:org.kde.StatusNotifierWatcher",
+            ])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        Some(String::from_utf8_lossy(&output.stdout).contains("boolean true"))
+    }
+}
@@ -0,0 +1,530 @@
+//! Generated icons, e.g. color swatches for the radios of a color picker
+//!
+//! Tray icons and menu item icons are different types, so the icons are generated as an
+//! [`IconImage`], converted with [`IconImage::to_tray_icon`] or [`IconImage::to_menu_icon`].
+//!
+//! # Example
+//! ```
+//! use std::rc::Rc;
+//! use tray_controls::icons::{self, MENU_ICON_SIZE};
+//! use tray_controls::{CheckIconKind, CheckIconMenuItem, MenuControl, MenuManager};
+//!
+//! let mut manager = MenuManager::new();
+//! for (id, color) in [("red", [255, 0, 0, 255]), ("green", [0, 255, 0, 255])] {
+//!     let swatch = icons::solid(color, MENU_ICON_SIZE).to_menu_icon().unwrap();
+//!     let checked_swatch = icons::swatch_with_border(color, [0, 0, 0, 255], MENU_ICON_SIZE)
+//!         .to_menu_icon()
+//!         .unwrap();
+//!     let radio = Rc::new(CheckIconMenuItem::with_id(
+//!         id, id, true, false, Some(swatch), Some(checked_swatch),
+//!     ));
+//!     manager.insert(MenuControl::CheckIconMenu(CheckIconKind::Radio(radio, None, "color")));
+//! }
+//!
+//! let tray_icon = icons::solid([255, 0, 0, 255], icons::TRAY_ICON_SIZE).to_tray_icon().unwrap();
+//! ```
+
+#[cfg(feature = "text-icons")]
+use ab_glyph::{Font, ScaleFont, point};
+#[cfg(feature = "tray")]
+use std::hash::Hash;
+
+use muda::Icon as MenuIcon;
+
+use crate::Error;
+#[cfg(feature = "tray")]
+use crate::FxIndexMap;
+
+/// Font of [`from_text`], loaded with `FontArc::try_from_vec` (**requires the `text-icons` feature**)
+#[cfg(feature = "text-icons")]
+pub use ab_glyph::FontArc;
+
+/// Size of generated tray icons, downscaled by the platform as needed
+pub const TRAY_ICON_SIZE: u32 = 32;
+/// Size of generated menu item icons
+pub const MENU_ICON_SIZE: u32 = 16;
+
+/// RGBA pixels of a generated icon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconImage {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl IconImage {
+    /// Creates a transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        IconImage {
+            rgba: vec![0; (width * height * 4) as usize],
+            width,
+            height,
+        }
+    }
+
+    /// Wraps RGBA pixels, e.g. of a decoded PNG.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, Error> {
+        if rgba.len() != (width as usize) * (height as usize) * 4 {
+            let (width_x_height, pixel_count) =
+                ((width as usize) * (height as usize), rgba.len() / 4);
+            #[cfg(feature = "tray")]
+            return Err(Error::Icon(tray_icon::BadIcon::DimensionsVsPixelCount {
+                width,
+                height,
+                width_x_height,
+                pixel_count,
+            }));
+            #[cfg(not(feature = "tray"))]
+            return Err(Error::MenuIcon(muda::BadIcon::DimensionsVsPixelCount {
+                width,
+                height,
+                width_x_height,
+                pixel_count,
+            }));
+        }
+
+        Ok(IconImage {
+            rgba,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn into_rgba(self) -> Vec<u8> {
+        self.rgba
+    }
+
+    /// Sets the color of a pixel, pixels outside of the image are ignored.
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        if x < self.width && y < self.height {
+            let offset = ((y * self.width + x) * 4) as usize;
+            self.rgba[offset..offset + 4].copy_from_slice(&rgba);
+        }
+    }
+
+    /// Draws a color over a pixel, blended by its alpha. Pixels outside of the image are ignored.
+    pub fn blend_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let offset = ((y * self.width + x) * 4) as usize;
+        let pixel = &mut self.rgba[offset..offset + 4];
+
+        let source_alpha = u32::from(rgba[3]);
+        let dest_alpha = u32::from(pixel[3]) * (255 - source_alpha) / 255;
+        let alpha = source_alpha + dest_alpha;
+        if alpha == 0 {
+            return;
+        }
+
+        for channel in 0..3 {
+            pixel[channel] = ((u32::from(rgba[channel]) * source_alpha
+                + u32::from(pixel[channel]) * dest_alpha)
+                / alpha) as u8;
+        }
+        pixel[3] = alpha as u8;
+    }
+
+    /// Fills a rectangle, clipped to the image.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, rgba: [u8; 4]) {
+        for y in y..y.saturating_add(height).min(self.height) {
+            for x in x..x.saturating_add(width).min(self.width) {
+                self.set_pixel(x, y, rgba);
+            }
+        }
+    }
+
+    /// Scales the image bilinearly to another size.
+    pub fn resize(&self, width: u32, height: u32) -> IconImage {
+        let mut image = IconImage::new(width, height);
+        if self.width == 0 || self.height == 0 {
+            return image;
+        }
+
+        // Colors are interpolated premultiplied, so transparent pixels don't bleed into edges
+        let pixel = |x: u32, y: u32| {
+            let offset = ((y * self.width + x) * 4) as usize;
+            let alpha = f32::from(self.rgba[offset + 3]) / 255.0;
+            [
+                f32::from(self.rgba[offset]) * alpha,
+                f32::from(self.rgba[offset + 1]) * alpha,
+                f32::from(self.rgba[offset + 2]) * alpha,
+                alpha,
+            ]
+        };
+        let sample = |position: u32, from: u32, to: u32| {
+            let source = ((position as f32 + 0.5) * from as f32 / to as f32 - 0.5).max(0.0);
+            let low = (source.floor() as u32).min(from - 1);
+            (low, (low + 1).min(from - 1), source - low as f32)
+        };
+
+        for y in 0..height {
+            let (top, bottom, fy) = sample(y, self.height, height);
+            for x in 0..width {
+                let (left, right, fx) = sample(x, self.width, width);
+
+                let mut value = [0.0; 4];
+                for (px, py, weight) in [
+                    (left, top, (1.0 - fx) * (1.0 - fy)),
+                    (right, top, fx * (1.0 - fy)),
+                    (left, bottom, (1.0 - fx) * fy),
+                    (right, bottom, fx * fy),
+                ] {
+                    for (value, channel) in value.iter_mut().zip(pixel(px, py)) {
+                        *value += channel * weight;
+                    }
+                }
+
+                let alpha = value[3];
+                let unpremultiply = |channel: f32| match alpha > 0.0 {
+                    true => (channel / alpha).round().clamp(0.0, 255.0) as u8,
+                    false => 0,
+                };
+                image.set_pixel(
+                    x,
+                    y,
+                    [
+                        unpremultiply(value[0]),
+                        unpremultiply(value[1]),
+                        unpremultiply(value[2]),
+                        (alpha * 255.0).round() as u8,
+                    ],
+                );
+            }
+        }
+
+        image
+    }
+
+    #[cfg(feature = "tray")]
+    pub fn to_tray_icon(&self) -> Result<tray_icon::Icon, Error> {
+        Ok(tray_icon::Icon::from_rgba(
+            self.rgba.clone(),
+            self.width,
+            self.height,
+        )?)
+    }
+
+    /// Converts to the icon of an `IconMenuItem` or a [`CheckIconMenuItem`](crate::CheckIconMenuItem).
+    pub fn to_menu_icon(&self) -> Result<MenuIcon, Error> {
+        Ok(MenuIcon::from_rgba(
+            self.rgba.clone(),
+            self.width,
+            self.height,
+        )?)
+    }
+}
+
+/// Tray icon drawn at several sizes, e.g. 16, 24, 32 and 64 pixels
+///
+/// [`IconSet::image_for`] picks the image of a pixel size, or rescales the closest one, so
+/// the tray icon stays sharp at any display scaling. See
+/// [`TrayController::set_icon_set`](crate::TrayController::set_icon_set).
+///
+/// # Example
+/// ```
+/// use tray_controls::icons::{self, IconSet};
+///
+/// let icon_set = IconSet::new()
+///     .with_image(icons::solid([255, 0, 0, 255], 16))
+///     .with_image(icons::solid([255, 0, 0, 255], 32))
+///     .with_logical_size(16);
+///
+/// // 150% scaling
+/// assert_eq!(icon_set.size_for(1.5), 24);
+/// assert_eq!(icon_set.image_for(24).unwrap().width(), 24);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconSet {
+    /// Square images sorted by size
+    images: Vec<IconImage>,
+    logical_size: u32,
+}
+
+impl Default for IconSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IconSet {
+    /// Creates an empty set, with the conventional logical tray icon size of the platform.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows:** 16 pixels.
+    /// - **macOS:** 18 pixels.
+    /// - **Linux:** 22 pixels.
+    pub fn new() -> Self {
+        let logical_size = if cfg!(target_os = "windows") {
+            16
+        } else if cfg!(target_os = "macos") {
+            18
+        } else {
+            22
+        };
+
+        IconSet {
+            images: Vec::new(),
+            logical_size,
+        }
+    }
+
+    /// Adds an image, replacing the image of the same size.
+    pub fn with_image(mut self, image: IconImage) -> Self {
+        self.images.retain(|other| other.width != image.width);
+        let index = self
+            .images
+            .partition_point(|other| other.width < image.width);
+        self.images.insert(index, image);
+        self
+    }
+
+    /// Sets the size of the tray icon at 100% scaling.
+    pub fn with_logical_size(mut self, logical_size: u32) -> Self {
+        self.logical_size = logical_size;
+        self
+    }
+
+    pub fn logical_size(&self) -> u32 {
+        self.logical_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// Gets the pixel size of the tray icon at a scale factor.
+    pub fn size_for(&self, scale_factor: f64) -> u32 {
+        (f64::from(self.logical_size) * scale_factor)
+            .round()
+            .max(1.0) as u32
+    }
+
+    /// Gets the image of a pixel size, `None` if the set is empty.
+    ///
+    /// Without an image of that size, the smallest larger image is scaled down, or the
+    /// largest image is scaled up.
+    pub fn image_for(&self, size: u32) -> Option<IconImage> {
+        let image = self
+            .images
+            .iter()
+            .find(|image| image.width >= size)
+            .or_else(|| self.images.last())?;
+
+        Some(match image.width == size && image.height == size {
+            true => image.clone(),
+            false => image.resize(size, size),
+        })
+    }
+}
+
+/// Converted tray icons by key, e.g. by animation frame or by state
+///
+/// Converting pixels to a tray icon is costly on some platforms, so icons that are swapped
+/// frequently are converted once. The least recently used icon is dropped when the cache
+/// is full.
+///
+/// # Example
+/// ```
+/// use tray_controls::icons::{self, IconCache};
+///
+/// let mut cache = IconCache::new(8);
+/// let frames = (0..8u8).map(|frame| (frame, icons::solid([frame * 32, 0, 0, 255], 32)));
+/// cache.precache(frames).unwrap();
+///
+/// // e.g. on each animation tick, nothing is converted
+/// let icon = cache.get_or_convert(3, || unreachable!()).unwrap();
+/// assert_eq!(cache.len(), 8);
+/// ```
+#[cfg(feature = "tray")]
+#[derive(Clone)]
+pub struct IconCache<K> {
+    /// Icons from the least to the most recently used
+    icons: FxIndexMap<K, tray_icon::Icon>,
+    capacity: usize,
+}
+
+#[cfg(feature = "tray")]
+impl<K: Eq + Hash> IconCache<K> {
+    pub fn new(capacity: usize) -> Self {
+        IconCache {
+            icons: FxIndexMap::default(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the capacity, dropping the least recently used icons that don't fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn len(&self) -> usize {
+        self.icons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.icons.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.icons.contains_key(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.icons.clear();
+    }
+
+    /// Gets the icon of a key, converting the image of `image` if it is not cached.
+    pub fn get_or_convert(
+        &mut self,
+        key: K,
+        image: impl FnOnce() -> IconImage,
+    ) -> Result<tray_icon::Icon, Error> {
+        let icon = match self.icons.shift_remove(&key) {
+            Some(icon) => icon,
+            None => image().to_tray_icon()?,
+        };
+
+        self.icons.insert(key, icon.clone());
+        self.evict();
+        Ok(icon)
+    }
+
+    /// Converts images up-front, e.g. all frames of an animation before it starts.
+    ///
+    /// Images of cached keys are not converted again.
+    pub fn precache(
+        &mut self,
+        images: impl IntoIterator<Item = (K, IconImage)>,
+    ) -> Result<(), Error> {
+        for (key, image) in images {
+            if !self.icons.contains_key(&key) {
+                self.icons.insert(key, image.to_tray_icon()?);
+            }
+        }
+        self.evict();
+        Ok(())
+    }
+
+    fn evict(&mut self) {
+        while self.icons.len() > self.capacity {
+            self.icons.shift_remove_index(0);
+        }
+    }
+}
+
+/// Generates a square of one color.
+pub fn solid(rgba: [u8; 4], size: u32) -> IconImage {
+    let mut image = IconImage::new(size, size);
+    image.fill_rect(0, 0, size, size, rgba);
+    image
+}
+
+/// Generates a square of one color with a border, e.g. for the checked radio of a color picker.
+///
+/// The border is 1 pixel wide for every 16 pixels of the size.
+pub fn swatch_with_border(fill: [u8; 4], border: [u8; 4], size: u32) -> IconImage {
+    let mut image = solid(border, size);
+    let border_width = (size / 16).max(1);
+    image.fill_rect(
+        border_width,
+        border_width,
+        size.saturating_sub(border_width * 2),
+        size.saturating_sub(border_width * 2),
+        fill,
+    );
+    image
+}
+
+/// Renders a short text, e.g. an unread count or a temperature, centered on a transparent
+/// square (**requires the `text-icons` feature**).
+///
+/// The text is scaled down to fit the width. Glyphs are drawn from their outlines in one
+/// color, so color emoji fonts render nothing.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::icons::{self, FontArc};
+///
+/// let font = FontArc::try_from_vec(std::fs::read("DejaVuSans-Bold.ttf").unwrap()).unwrap();
+/// let icon = icons::from_text("42", &font, [255, 255, 255, 255], icons::TRAY_ICON_SIZE)
+///     .to_tray_icon()
+///     .unwrap();
+/// ```
+#[cfg(feature = "text-icons")]
+pub fn from_text(text: &str, font: &FontArc, rgba: [u8; 4], size: u32) -> IconImage {
+    let mut image = IconImage::new(size, size);
+
+    // Lay out at the full size first, then shrink to fit the width
+    let advance = |scaled: &ab_glyph::PxScaleFont<&FontArc>| {
+        let mut previous = None;
+        text.chars()
+            .map(|c| {
+                let id = scaled.glyph_id(c);
+                let kern = previous.map_or(0.0, |previous| scaled.kern(previous, id));
+                previous = Some(id);
+                kern + scaled.h_advance(id)
+            })
+            .sum::<f32>()
+    };
+    let full = font.as_scaled(size as f32);
+    let width = advance(&full);
+    let scale = match width > size as f32 {
+        true => size as f32 * size as f32 / width,
+        false => size as f32,
+    };
+    let scaled = font.as_scaled(scale);
+
+    let mut x = (size as f32 - advance(&scaled)) / 2.0;
+    let baseline = (size as f32 - scaled.ascent() + scaled.descent()) / 2.0 + scaled.ascent();
+    let mut previous = None;
+
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            x += scaled.kern(previous, id);
+        }
+        previous = Some(id);
+
+        let glyph = id.with_scale_and_position(scale, point(x, baseline));
+        x += scaled.h_advance(id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|glyph_x, glyph_y, coverage| {
+            let (x, y) = (
+                bounds.min.x as i64 + i64::from(glyph_x),
+                bounds.min.y as i64 + i64::from(glyph_y),
+            );
+            if x < 0 || y < 0 {
+                return;
+            }
+
+            let alpha = (f32::from(rgba[3]) * coverage.clamp(0.0, 1.0)).round() as u8;
+            image.blend_pixel(x as u32, y as u32, [rgba[0], rgba[1], rgba[2], alpha]);
+        });
+    }
+
+    image
+}
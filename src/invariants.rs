@@ -0,0 +1,50 @@
+use std::fmt;
+
+use muda::MenuId;
+
+/// Broken state of a menu manager, see [`MenuManager::check_invariants`]
+///
+/// [`MenuManager::check_invariants`]: crate::MenuManager::check_invariants
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum InvariantViolation<G> {
+    /// More than one radio of a radio group is checked
+    MultipleCheckedRadios { group: G, checked: Vec<MenuId> },
+    /// A grouped check menu is not registered as a menu control
+    UnregisteredGroupedItem { group: G, menu_id: MenuId },
+    /// The default radio menu ID of a radio is not a radio of the same group
+    UnresolvedDefault { menu_id: MenuId, default_id: MenuId },
+    /// The default radio menu ID of a radio group is not a radio of the group
+    UnresolvedGroupDefault { group: G, default_id: MenuId },
+}
+
+impl<G> fmt::Display for InvariantViolation<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::MultipleCheckedRadios { checked, .. } => {
+                let checked: Vec<&str> = checked.iter().map(|menu_id| menu_id.0.as_str()).collect();
+                write!(
+                    f,
+                    "radios `{}` of one group are all checked",
+                    checked.join("`, `")
+                )
+            }
+            InvariantViolation::UnregisteredGroupedItem { menu_id, .. } => {
+                write!(f, "grouped menu `{}` is not registered", menu_id.0)
+            }
+            InvariantViolation::UnresolvedDefault {
+                menu_id,
+                default_id,
+            } => write!(
+                f,
+                "default radio `{}` of radio `{}` is not a radio of the same group",
+                default_id.0, menu_id.0
+            ),
+            InvariantViolation::UnresolvedGroupDefault { default_id, .. } => write!(
+                f,
+                "group default radio `{}` is not a radio of the group",
+                default_id.0
+            ),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use muda::MenuId;
+
+use crate::{ChangeOrigin, CheckRef, Error, MenuControl, MenuManager};
+
+/// How long a client waits for the application to run its command
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the server waits for the command line of a connection
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+/// How many connections are served at once, the others are dropped
+const MAX_CONNECTIONS: usize = 16;
+
+struct Request {
+    command: String,
+    reply: Sender<String>,
+}
+
+/// Server letting other processes of the user drive the menu, e.g. a CLI companion
+///
+/// Each connection sends one command line and receives the reply:
+///
+/// | Command | Reply |
+/// |---|---|
+/// | `set_checked <menu_id> <true\|false>` | `ok` |
+/// | `select_radio <menu_id>` | `ok` |
+/// | `set_text <menu_id> <text>` | `ok` |
+/// | `get_state [menu_id]` | One `<menu_id>\t<checked>\t<enabled>\t<text>\t<description>` line per menu control, `checked` is `-` for other menu controls, `description` is empty without an accessible description |
+///
+/// A failed command replies `error: <message>`. Menu IDs can't contain whitespace. Each
+/// connection is served on its own thread, up to 16 at once, and is dropped if its command
+/// line doesn't arrive within 2 seconds.
+///
+/// The menu manager stays on its thread, so received commands wait until
+/// [`IpcServer::handle_pending`] runs them. Their changes have the
+/// [`ChangeOrigin::Remote`] origin.
+///
+/// ## Platform-specific:
+///
+/// - **Linux / macOS:** A unix socket `{app_name}.sock` in `$XDG_RUNTIME_DIR`, or in a
+///   `{app_name}-{user}` directory of the temporary directory only accessible by the user.
+/// - **Windows:** A localhost TCP port, written to `{app_name}-{user}.ipc` in the temporary
+///   directory, since named pipes need the `windows` crate. Other local users can reach it.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{IpcClient, IpcServer, MenuManager};
+///
+/// let mut manager = MenuManager::<()>::new();
+/// let server = IpcServer::bind("com.example.myapp").unwrap();
+///
+/// // In the event loop of the tray app
+/// server.handle_pending(&mut manager);
+///
+/// // In the CLI companion
+/// let reply = IpcClient::new("com.example.myapp").send("set_checked pause_sync true").unwrap();
+/// assert_eq!(reply, "ok");
+/// ```
+pub struct IpcServer {
+    requests: Receiver<Request>,
+    endpoint: transport::Endpoint,
+    stop: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl IpcServer {
+    /// Starts listening for the commands of other processes.
+    ///
+    /// Fails if another server of the app is running.
+    pub fn bind(app_name: &str) -> Result<IpcServer, Error> {
+        let (listener, endpoint) = transport::bind(app_name)?;
+        let (sender, requests) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        trace_event!(debug, app_name, "ipc server bound");
+
+        let listener = std::thread::spawn({
+            let stop = stop.clone();
+            let connections = Arc::new(AtomicUsize::new(0));
+            move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+
+                    if connections.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::AcqRel);
+                        trace_event!(warn, "too many ipc connections, connection dropped");
+                        continue;
+                    }
+
+                    // A silent or slow client only holds its own thread
+                    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+                    let sender = sender.clone();
+                    let connections = connections.clone();
+                    std::thread::spawn(move || {
+                        let _ = serve(stream, &sender);
+                        connections.fetch_sub(1, Ordering::AcqRel);
+                    });
+                }
+            }
+        });
+
+        Ok(IpcServer {
+            requests,
+            endpoint,
+            stop,
+            listener: Some(listener),
+        })
+    }
+
+    /// Runs the commands received since the last call, returns how many ran.
+    pub fn handle_pending<G>(&self, manager: &mut MenuManager<G>) -> usize
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let mut count = 0;
+        while let Ok(request) = self.requests.try_recv() {
+            trace_event!(debug, command = %request.command, "run ipc command");
+
+            let reply = manager
+                .with_origin(ChangeOrigin::Remote, |manager| {
+                    execute(manager, &request.command)
+                })
+                .unwrap_or_else(|message| format!("error: {message}"));
+            let _ = request.reply.send(reply);
+            count += 1;
+        }
+        count
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake up the blocking accept
+        let _ = transport::connect(&self.endpoint);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+        transport::unbind(&self.endpoint);
+    }
+}
+
+/// Client of the [`IpcServer`] of an app
+pub struct IpcClient {
+    app_name: String,
+}
+
+impl IpcClient {
+    pub fn new(app_name: &str) -> Self {
+        IpcClient {
+            app_name: app_name.to_owned(),
+        }
+    }
+
+    /// Sends a command line, and waits for the reply.
+    ///
+    /// A command the server rejects is not an `Err`, its reply starts with `error:`.
+    pub fn send(&self, command: &str) -> Result<String, Error> {
+        let endpoint = transport::endpoint(&self.app_name)?;
+        let mut stream = transport::connect(&endpoint)?;
+        stream.write_all(command.trim_end().as_bytes())?;
+        stream.write_all(b"\n")?;
+        stream.flush()?;
+
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        Ok(reply.trim_end().to_owned())
+    }
+}
+
+/// Reads the command of a connection, and writes back the reply of the application.
+fn serve<S: Read + Write>(stream: S, sender: &Sender<Request>) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut command = String::new();
+    // Probes of `IpcServer::bind` and wake-ups of the listener send nothing
+    if stream.read_line(&mut command)? == 0 {
+        return Ok(());
+    }
+
+    let (reply, reply_receiver) = mpsc::channel();
+    let request = Request {
+        command: command.trim_end().to_owned(),
+        reply,
+    };
+    let reply = match sender.send(request) {
+        Ok(()) => reply_receiver
+            .recv_timeout(REPLY_TIMEOUT)
+            .unwrap_or_else(|_| "error: the application did not answer".to_owned()),
+        Err(_) => "error: the server is shutting down".to_owned(),
+    };
+
+    let stream = stream.get_mut();
+    stream.write_all(reply.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()
+}
+
+fn execute<G>(manager: &mut MenuManager<G>, command: &str) -> Result<String, String>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+    let (menu_id, rest) = args.split_once(' ').unwrap_or((args, ""));
+    let menu_id = MenuId::new(menu_id);
+
+    match name {
+        "set_checked" => {
+            let checked = rest
+                .parse::<bool>()
+                .map_err(|_| "expected `true` or `false`".to_owned())?;
+            if registered(manager, &menu_id)?.as_check_menu().is_none() {
+                return Err(format!("menu `{}` can't be checked", menu_id.as_ref()));
+            }
+            manager.batch(|batch| {
+                batch.set_checked(&menu_id, checked);
+            });
+        }
+        "select_radio" => {
+            if !matches!(
+                registered(manager, &menu_id)?.check_ref(),
+                Some(CheckRef::Radio(..))
+            ) {
+                return Err(format!("menu `{}` is not a radio", menu_id.as_ref()));
+            }
+            manager.batch(|batch| {
+                batch.set_checked(&menu_id, true);
+            });
+        }
+        "set_text" => {
+            registered(manager, &menu_id)?;
+            manager.batch(|batch| {
+                batch.set_text(&menu_id, rest);
+            });
+        }
+        "get_state" => {
+            if !args.is_empty() {
+                registered(manager, &menu_id)?;
+            }
+
+            let state = manager.state();
+            let lines: Vec<String> = state
+                .iter()
+                .filter(|(id, _)| args.is_empty() || **id == menu_id)
+                .map(|(id, item)| {
+                    let checked = item
+                        .is_checked()
+                        .map_or("-".to_owned(), |checked| checked.to_string());
+                    format!(
+                        "{}\t{checked}\t{}\t{}\t{}",
+                        id.as_ref(),
+                        item.is_enabled(),
+                        item.text(),
+                        item.description().unwrap_or_default()
+                    )
+                })
+                .collect();
+            return Ok(lines.join("\n"));
+        }
+        _ => return Err(format!("unknown command `{name}`")),
+    }
+
+    Ok("ok".to_owned())
+}
+
+fn registered<'a, G>(
+    manager: &'a MenuManager<G>,
+    menu_id: &MenuId,
+) -> Result<&'a MenuControl<G>, String>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    manager
+        .get_menu_item_from_id(menu_id)
+        .ok_or_else(|| format!("menu `{}` is not registered", menu_id.as_ref()))
+}
+
+fn runtime_dir() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+#[cfg(unix)]
+mod transport {
+    use std::fs::DirBuilder;
+    use std::io;
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+
+    pub(super) type Endpoint = PathBuf;
+
+    pub(super) fn endpoint(app_name: &str) -> io::Result<Endpoint> {
+        Ok(socket_dir(app_name).join(format!("{app_name}.sock")))
+    }
+
+    fn socket_dir(app_name: &str) -> PathBuf {
+        match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(runtime_dir) => PathBuf::from(runtime_dir),
+            // The temporary directory is shared by the users, so the socket gets its own
+            None => {
+                let user = std::env::var("USER").unwrap_or_default();
+                super::runtime_dir().join(format!("{app_name}-{user}"))
+            }
+        }
+    }
+
+    /// Creates a directory only accessible by the user, or checks the existing one.
+    fn create_private_dir(dir: &Path) -> io::Result<()> {
+        match DirBuilder::new().mode(0o700).create(dir) {
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+            result => return result,
+        }
+
+        // Another user may have created it first
+        let metadata = std::fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the socket directory is accessible by other users",
+            ));
+        }
+        Ok(())
+    }
+
+    pub(super) fn bind(app_name: &str) -> io::Result<(UnixListener, Endpoint)> {
+        if std::env::var_os("XDG_RUNTIME_DIR").is_none() {
+            create_private_dir(&socket_dir(app_name))?;
+        }
+        let path = endpoint(app_name)?;
+
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another server of the app is running",
+                ));
+            }
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "the socket path is taken by another file",
+                ));
+            }
+            // Left behind by a crashed server
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        Ok((listener, path))
+    }
+
+    pub(super) fn connect(path: &Endpoint) -> io::Result<UnixStream> {
+        UnixStream::connect(path)
+    }
+
+    pub(super) fn unbind(path: &Endpoint) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(windows)]
+mod transport {
+    use std::io;
+    use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    pub(super) type Endpoint = (PathBuf, u16);
+
+    fn port_path(app_name: &str) -> PathBuf {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        super::runtime_dir().join(format!("{app_name}-{user}.ipc"))
+    }
+
+    pub(super) fn endpoint(app_name: &str) -> io::Result<Endpoint> {
+        let path = port_path(app_name);
+        let port = std::fs::read_to_string(&path)?
+            .trim()
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid port file"))?;
+        Ok((path, port))
+    }
+
+    pub(super) fn bind(app_name: &str) -> io::Result<(TcpListener, Endpoint)> {
+        if let Ok(endpoint) = endpoint(app_name)
+            && connect(&endpoint).is_ok()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "another server of the app is running",
+            ));
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let port = listener.local_addr()?.port();
+        let path = port_path(app_name);
+        std::fs::write(&path, port.to_string())?;
+        Ok((listener, (path, port)))
+    }
+
+    pub(super) fn connect((_, port): &Endpoint) -> io::Result<TcpStream> {
+        TcpStream::connect_timeout(
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, *port)),
+            Duration::from_millis(500),
+        )
+    }
+
+    pub(super) fn unbind((path, port): &Endpoint) {
+        // A later server may own the port file by now
+        if std::fs::read_to_string(path).is_ok_and(|saved| saved.trim() == port.to_string()) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
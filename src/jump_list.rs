@@ -0,0 +1,132 @@
+use std::hash::Hash;
+
+use muda::MenuId;
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+};
+use windows::core::{HSTRING, Interface};
+
+use crate::{Error, MenuManager, QuickAction};
+
+/// Taskbar jump list mirroring the quick actions of a menu manager (**requires the `jump-list` feature**)
+///
+/// Each quick action is a task of the jump list, which starts the app again with the
+/// `{argument} {menu_id}` arguments. Forward them to the running instance, e.g. with
+/// `SingleInstance`, then click the menu control with [`JumpList::menu_id_from_args`] and
+/// [`MenuManager::activate`].
+///
+/// Call [`JumpList::sync`] after changing the menu, the jump list is only written again
+/// once the label or enabled state of a quick action changed. Tasks can't be disabled, so
+/// disabled quick actions (e.g. busy ones) are left out until they are enabled again.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{JumpList, MenuControl, MenuManager};
+/// use tray_icon::menu::{MenuId, MenuItem};
+///
+/// let mut manager = MenuManager::<()>::new();
+/// manager.insert(MenuControl::MenuItem(MenuItem::with_id("new", "&New Note", true, None)));
+/// manager.set_quick_action(&MenuId::new("new"), true);
+///
+/// let mut jump_list = JumpList::new("--quick-action");
+/// jump_list.sync(&manager).unwrap();
+///
+/// // With the arguments of an instance started from the jump list
+/// let args: Vec<String> = std::env::args().skip(1).collect();
+/// if let Some(menu_id) = jump_list.menu_id_from_args(&args) {
+///     manager.activate(&menu_id, |_| {});
+/// }
+/// ```
+pub struct JumpList {
+    argument: String,
+    exported: Option<Vec<QuickAction>>,
+}
+
+impl JumpList {
+    /// `argument` marks the command lines started from the jump list, e.g. `"--quick-action"`.
+    pub fn new(argument: &str) -> Self {
+        JumpList {
+            argument: argument.to_owned(),
+            exported: None,
+        }
+    }
+
+    /// Writes the quick actions to the jump list if they changed since the last sync.
+    ///
+    /// Returns `true` if the jump list was written.
+    pub fn sync<G>(&mut self, manager: &MenuManager<G>) -> Result<bool, Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let quick_actions: Vec<QuickAction> = manager
+            .quick_actions()
+            .into_iter()
+            .filter(|quick_action| quick_action.enabled)
+            .collect();
+        if self.exported.as_ref() == Some(&quick_actions) {
+            return Ok(false);
+        }
+
+        self.write(&quick_actions)?;
+        trace_event!(debug, count = quick_actions.len(), "jump list written");
+
+        self.exported = Some(quick_actions);
+        Ok(true)
+    }
+
+    /// Gets the menu ID of a quick action from the arguments of an instance started from
+    /// the jump list, without the program.
+    pub fn menu_id_from_args(&self, args: &[String]) -> Option<MenuId> {
+        args.windows(2)
+            .find(|pair| pair[0] == self.argument)
+            .map(|pair| MenuId::new(&pair[1]))
+    }
+
+    fn write(&self, quick_actions: &[QuickAction]) -> Result<(), Error> {
+        let exe = HSTRING::from(std::env::current_exe()?.as_path());
+
+        unsafe {
+            // Fails if the thread already joined another apartment, which works as well
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let list: ICustomDestinationList =
+                CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+            let mut min_slots = 0;
+            let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+
+            let tasks: IObjectCollection =
+                CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+            for quick_action in quick_actions {
+                let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+                link.SetPath(&exe)?;
+                link.SetIconLocation(&exe, 0)?;
+                link.SetArguments(&HSTRING::from(format!(
+                    "{} \"{}\"",
+                    self.argument,
+                    quick_action.menu_id.as_ref()
+                )))?;
+
+                // The label of a task is the title of its shell link
+                let properties: IPropertyStore = link.cast()?;
+                properties.SetValue(&PKEY_Title, &PROPVARIANT::from(quick_action.text.as_str()))?;
+                properties.Commit()?;
+
+                tasks.AddObject(&link)?;
+            }
+
+            if !quick_actions.is_empty() {
+                list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+            }
+            list.CommitList()?;
+        }
+
+        Ok(())
+    }
+}
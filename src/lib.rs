@@ -1,11 +1,277 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::hash::Hash;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use tray_icon::menu::{CheckMenuItem, IconMenuItem, MenuId, MenuItem, accelerator::Accelerator};
+use indexmap::{IndexMap, IndexSet};
+use rustc_hash::FxBuildHasher;
+
+use muda::{
+    CheckMenuItem, Icon, IconMenuItem, IsMenuItem, Menu, MenuId, MenuItem, MenuItemKind, Submenu,
+    accelerator::Accelerator,
+};
+
+/// Emits a `tracing` event, compiled out without the `tracing` feature.
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
+mod a11y;
+mod action;
+#[cfg(feature = "tray")]
+mod attention;
+mod audit;
+#[cfg(feature = "autostart")]
+mod autostart;
+mod batch;
+mod binding;
+mod check_icon;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod command;
+mod common;
+mod condition;
+#[cfg(feature = "serde")]
+mod config;
+#[cfg(feature = "tray")]
+mod controller;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+mod dbus;
+#[cfg(feature = "dialogs")]
+mod dialog;
+mod error;
+mod group_meta;
+mod handle;
+#[cfg(feature = "tray")]
+mod headless;
+pub mod icons;
+mod invariants;
+#[cfg(feature = "ipc")]
+mod ipc;
+#[cfg(all(feature = "jump-list", windows))]
+mod jump_list;
+mod menu_key;
+mod menu_template;
+mod mnemonic;
+mod observer;
+mod pending;
+mod platform;
+#[cfg(feature = "tray")]
+mod progress;
+mod quick_action;
+mod radio_group;
+mod radio_range;
+mod recent;
+#[cfg(feature = "tray")]
+mod registry;
+#[cfg(feature = "runtime")]
+mod runtime;
+mod scheduler;
+mod scoped_ids;
+mod settings;
+#[cfg(feature = "single-instance")]
+mod single_instance;
+#[cfg(all(feature = "ksni", target_os = "linux"))]
+mod sni;
+mod state;
+mod stats;
+mod status;
+mod stepper;
+#[cfg(feature = "system-lists")]
+mod system_list;
+mod template;
+pub mod testing;
+mod timer;
+mod typed_id;
+
+use action::ActionContext;
+use condition::ItemConditions;
+use handle::HandleSlots;
+use menu_key::MenuKey;
+use observer::Observers;
+use scheduler::Scheduler;
+
+pub use a11y::A11yPrefs;
+#[cfg(feature = "clipboard")]
+pub use action::ClipboardText;
+pub use action::{Action, MenuAction};
+pub use audit::AuditReport;
+#[cfg(feature = "autostart")]
+pub use autostart::AutoStart;
+pub use batch::Batch;
+pub use binding::SubmenuBinding;
+pub use check_icon::{CheckIconKind, CheckIconMenuItem};
+pub use command::CommandRegistry;
+pub use common::CommonAction;
+#[cfg(feature = "serde")]
+pub use config::ConfigBinding;
+#[cfg(feature = "tray")]
+pub use controller::{TrayAction, TrayClick, TrayController, TrayEvent, TrayGuard};
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+pub use dbus::DbusServer;
+#[cfg(feature = "dialogs")]
+pub use dialog::Confirmation;
+pub use error::Error;
+pub use group_meta::GroupMeta;
+pub use handle::MenuHandle;
+#[cfg(feature = "tray")]
+pub use headless::{TrayHost, is_tray_supported};
+pub use invariants::InvariantViolation;
+#[cfg(feature = "ipc")]
+pub use ipc::{IpcClient, IpcServer};
+#[cfg(all(feature = "jump-list", windows))]
+pub use jump_list::JumpList;
+pub use menu_template::MenuTemplate;
+pub use observer::{ChangeEvent, ChangeOrigin};
+pub use pending::PendingChange;
+pub use platform::Platform;
+#[cfg(feature = "tray")]
+pub use progress::{ProgressReporter, ProgressStyle};
+pub use quick_action::QuickAction;
+pub use radio_group::{NoDefault, RadioGroupBuilder, WithDefault};
+pub use radio_range::RadioRange;
+pub use recent::{RecentAction, RecentItems};
+#[cfg(feature = "tray")]
+pub use registry::TrayRegistry;
+#[cfg(feature = "runtime")]
+pub use runtime::TrayRuntime;
+pub use scheduler::{Schedule, ScheduleId, ScheduledAction};
+pub use scoped_ids::ScopedIds;
+pub use settings::SettingsBinding;
+#[cfg(feature = "single-instance")]
+pub use single_instance::SingleInstance;
+#[cfg(all(feature = "ksni", target_os = "linux"))]
+pub use sni::SniTray;
+pub use state::{GroupSnapshot, MenuItemState, MenuState, StateChange};
+pub use stats::{ClickStats, ItemStats};
+pub use status::StatusItem;
+pub use stepper::Stepper;
+#[cfg(feature = "system-lists")]
+pub use system_list::{AudioOutputs, Displays, SystemDevice, SystemList, SystemListMenu};
+pub use timer::TimerItem;
+pub use typed_id::TypedId;
+
+#[doc(hidden)]
+pub use muda::MenuId as __MenuId;
 
 type DefaultMenuId = MenuId;
 
+/// Insertion-ordered map with a fast hasher for menu IDs and groups
+pub type FxIndexMap<K, V> = IndexMap<K, V, FxBuildHasher>;
+type FxIndexSet<T> = IndexSet<T, FxBuildHasher>;
+
+/// Check state of a checkbox group, see [`MenuManager::bind_master_checkbox`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupCheckState {
+    /// All checkboxes of the group are checked
+    Checked,
+    /// No checkbox of the group is checked
+    Unchecked,
+    /// Some checkboxes of the group are checked
+    Indeterminate,
+}
+
+/// Kind of a menu control, see [`MenuManager::kind_of`]
+///
+/// Unlike [`MenuControl`], it is `Copy` and holds no menu, so hot event loops can branch on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlKind {
+    MenuItem,
+    Icon,
+    /// A check menu
+    Check(CheckKind),
+    /// A checkable icon menu
+    CheckIcon(CheckKind),
+}
+
+/// Kind of a check menu or checkable icon menu, see [`ControlKind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckKind {
+    /// A checkbox belonging to a group
+    CheckBox,
+    /// A radio button belonging to a group
+    Radio,
+    /// A standalone checkbox
+    Separate,
+}
+
+/// What happens when the checked radio of a radio group is clicked again, see [`MenuManager::set_radio_policy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadioPolicy {
+    /// The default radio menu is checked, the group is left empty if there is none
+    #[default]
+    Reselect,
+    /// The group is left empty, even if there is a default radio menu
+    AllowNone,
+    /// The clicked radio stays checked
+    RevertClick,
+}
+
+/// What happens when a user callback panics, see [`MenuManager::set_panic_policy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// The panic unwinds through the caller
+    #[default]
+    Propagate,
+    /// The panic is caught, and the event loop goes on
+    ///
+    /// The panic hook still reports the panic, e.g. on stderr.
+    Continue,
+}
+
+/// Which changes are sent to the subscribers of a menu manager, see [`MenuManager::set_notify`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Notify {
+    /// Every change is sent
+    #[default]
+    Always,
+    /// Only changes made by the user, see [`ChangeOrigin::is_user`]
+    OnlyUser,
+    /// No change is sent
+    Never,
+}
+
+/// Calls a user callback under a panic policy, `None` if a panic was caught.
+fn call_with_policy<R>(policy: PanicPolicy, callback: impl FnOnce() -> R) -> Option<R> {
+    match policy {
+        PanicPolicy::Propagate => Some(callback()),
+        PanicPolicy::Continue => std::panic::catch_unwind(std::panic::AssertUnwindSafe(callback))
+            .inspect_err(|_| {
+                trace_event!(error, "user callback panicked");
+            })
+            .ok(),
+    }
+}
+
+/// Gets a live menu item behind the `IsMenuItem` trait.
+fn as_is_menu_item(item: &MenuItemKind) -> &dyn IsMenuItem {
+    match item {
+        MenuItemKind::MenuItem(menu_item) => menu_item,
+        MenuItemKind::Submenu(submenu) => submenu,
+        MenuItemKind::Predefined(predefined) => predefined,
+        MenuItemKind::Check(check_menu) => check_menu,
+        MenuItemKind::Icon(icon_menu) => icon_menu,
+    }
+}
+
+type PopulateSubmenu<G> = Rc<dyn Fn() -> Vec<MenuControl<G>>>;
+type ErrorHandler = Rc<dyn Fn(&Error)>;
+
+/// Maps are shrunk after removals once at most a quarter of their capacity is used
+const SPARSE_RATIO: usize = 4;
+/// Small maps are never shrunk automatically
+const SHRINK_MIN_CAPACITY: usize = 64;
+
+fn is_sparse(len: usize, capacity: usize) -> bool {
+    capacity >= SHRINK_MIN_CAPACITY && len.saturating_mul(SPARSE_RATIO) <= capacity
+}
+
 /// Represents different types of checkable menu items with their associated data
 ///
 /// This enum defines three types of checkable menu items:
@@ -23,7 +289,10 @@ type DefaultMenuId = MenuId;
 /// - Default ID:   
 ///   If `Some`, specifies which menu should be selected when all radios in the group are unchecked.   
 ///   If `None`, no action is taken when all radios are unchecked.   
-/// - Grouping: All radio buttons with the same `G` value form a single selection group
+///   **Deprecated**: pass `None` and use [`MenuManager::set_group_default`] instead,
+///   see [`MenuManager::migrate_radio_defaults`]
+/// - Grouping: All radio buttons with the same `G` value form a single selection group,
+///   even if they are spread over several submenus
 ///
 /// ### `Separate`
 /// - Contains: `Rc<CheckMenuItem>` only
@@ -74,6 +343,8 @@ pub enum CheckMenuKind<G> {
     /// - Second parameter: Optional default menu ID to select when no radio is checked.
     ///   If `Some`, this menu will be selected when all radios in the group are unchecked.
     ///   If `None`, no menu will be selected when all radios are unchecked.
+    ///   **Deprecated**: pass `None` and use [`MenuManager::set_group_default`] instead,
+    ///   or build it with [`CheckMenuKind::radio`].
     /// - Third parameter: Group identifier for exclusive selection
     Radio(Rc<CheckMenuItem>, Option<Rc<DefaultMenuId>>, G),
 
@@ -83,14 +354,48 @@ pub enum CheckMenuKind<G> {
     Separate(Rc<CheckMenuItem>),
 }
 
+impl<G> CheckMenuKind<G> {
+    /// Creates a radio button of the group `G`, see [`MenuManager::set_group_default`] for
+    /// its default radio.
+    pub fn radio(check_menu: Rc<CheckMenuItem>, group: G) -> Self {
+        CheckMenuKind::Radio(check_menu, None, group)
+    }
+
+    /// Creates a radio button with a per-radio default radio menu ID.
+    #[deprecated(note = "use `CheckMenuKind::radio` and `MenuManager::set_group_default`")]
+    pub fn radio_with_default(check_menu: Rc<CheckMenuItem>, default_id: MenuId, group: G) -> Self {
+        CheckMenuKind::Radio(check_menu, Some(Rc::new(default_id)), group)
+    }
+}
+
 #[derive(Clone)]
 pub enum MenuControl<G> {
     MenuItem(MenuItem),
     IconMenu(IconMenuItem),
     CheckMenu(CheckMenuKind<G>),
+    CheckIconMenu(CheckIconKind<G>),
 }
 
 impl<G> MenuControl<G> {
+    pub fn kind(&self) -> ControlKind {
+        match self {
+            MenuControl::MenuItem(_) => ControlKind::MenuItem,
+            MenuControl::IconMenu(_) => ControlKind::Icon,
+            MenuControl::CheckMenu(check_menu_kind) => ControlKind::Check(match check_menu_kind {
+                CheckMenuKind::CheckBox(..) => CheckKind::CheckBox,
+                CheckMenuKind::Radio(..) => CheckKind::Radio,
+                CheckMenuKind::Separate(_) => CheckKind::Separate,
+            }),
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                ControlKind::CheckIcon(match check_icon_kind {
+                    CheckIconKind::CheckBox(..) => CheckKind::CheckBox,
+                    CheckIconKind::Radio(..) => CheckKind::Radio,
+                    CheckIconKind::Separate(_) => CheckKind::Separate,
+                })
+            }
+        }
+    }
+
     pub fn id(&self) -> &MenuId {
         match self {
             MenuControl::MenuItem(menu_item) => menu_item.id(),
@@ -100,9 +405,11 @@ impl<G> MenuControl<G> {
                 | CheckMenuKind::Radio(check_menu, _, _)
                 | CheckMenuKind::Separate(check_menu) => check_menu.id(),
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => check_icon_kind.item().id(),
         }
     }
 
+    /// Gets the text, a copy since the platform menu owns it.
     pub fn text(&self) -> String {
         match self {
             MenuControl::MenuItem(menu_item) => menu_item.text(),
@@ -112,6 +419,9 @@ impl<G> MenuControl<G> {
                 | CheckMenuKind::Radio(check_menu, _, _)
                 | CheckMenuKind::Separate(check_menu) => check_menu.text(),
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                check_icon_kind.item().icon_menu().text()
+            }
         }
     }
 
@@ -125,10 +435,29 @@ impl<G> MenuControl<G> {
                     true
                 }
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                check_icon_kind.item().set_checked(checked);
+                true
+            }
             _ => false,
         }
     }
 
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            MenuControl::MenuItem(menu_item) => menu_item.is_enabled(),
+            MenuControl::IconMenu(icon_menu) => icon_menu.is_enabled(),
+            MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
+                CheckMenuKind::CheckBox(check_menu, _)
+                | CheckMenuKind::Radio(check_menu, _, _)
+                | CheckMenuKind::Separate(check_menu) => check_menu.is_enabled(),
+            },
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                check_icon_kind.item().icon_menu().is_enabled()
+            }
+        }
+    }
+
     pub fn set_enabled(&self, enabled: bool) {
         match self {
             MenuControl::MenuItem(menu_item) => menu_item.set_enabled(enabled),
@@ -138,6 +467,9 @@ impl<G> MenuControl<G> {
                 | CheckMenuKind::Radio(check_menu, _, _)
                 | CheckMenuKind::Separate(check_menu) => check_menu.set_enabled(enabled),
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                check_icon_kind.item().icon_menu().set_enabled(enabled)
+            }
         }
     }
 
@@ -150,13 +482,13 @@ impl<G> MenuControl<G> {
                 | CheckMenuKind::Radio(check_menu, _, _)
                 | CheckMenuKind::Separate(check_menu) => check_menu.set_text(text),
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => {
+                check_icon_kind.item().icon_menu().set_text(text)
+            }
         }
     }
 
-    pub fn set_accelerator(
-        &self,
-        accelerator: Option<Accelerator>,
-    ) -> Result<(), tray_icon::menu::Error> {
+    pub fn set_accelerator(&self, accelerator: Option<Accelerator>) -> Result<(), muda::Error> {
         match self {
             MenuControl::MenuItem(menu_item) => menu_item.set_accelerator(accelerator),
             MenuControl::IconMenu(icon_menu) => icon_menu.set_accelerator(accelerator),
@@ -165,6 +497,10 @@ impl<G> MenuControl<G> {
                 | CheckMenuKind::Radio(check_menu, _, _)
                 | CheckMenuKind::Separate(check_menu) => check_menu.set_accelerator(accelerator),
             },
+            MenuControl::CheckIconMenu(check_icon_kind) => check_icon_kind
+                .item()
+                .icon_menu()
+                .set_accelerator(accelerator),
         }
     }
 
@@ -176,24 +512,172 @@ impl<G> MenuControl<G> {
     }
 
     pub fn as_icon_menu(&self) -> Option<&IconMenuItem> {
+        match self {
+            MenuControl::IconMenu(icon_menu) => Some(icon_menu),
+            MenuControl::CheckIconMenu(check_icon_kind) => Some(check_icon_kind.item().icon_menu()),
+            _ => None,
+        }
+    }
+
+    /// Gets the icon menu whose icon can be set, `None` for a checkable icon menu, whose icon
+    /// follows its checked state.
+    fn as_plain_icon_menu(&self) -> Option<&IconMenuItem> {
         match self {
             MenuControl::IconMenu(icon_menu) => Some(icon_menu),
             _ => None,
         }
     }
 
+    /// Gets the check menu the platform menus toggle before the menu event of a click.
+    ///
+    /// Checkable icon menus aren't toggled by the platform, [`MenuManager::update`] does it.
+    pub(crate) fn platform_check_menu(&self) -> Option<&CheckMenuItem> {
+        match self {
+            MenuControl::CheckMenu(_) => self.as_check_menu(),
+            _ => None,
+        }
+    }
+
+    /// Gets the check menu, for a checkable icon menu this is the hidden check state.
     pub fn as_check_menu(&self) -> Option<&CheckMenuItem> {
-        if let MenuControl::CheckMenu(check_menu) = self {
-            let check_menu = match check_menu {
+        match self {
+            MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
                 CheckMenuKind::CheckBox(check_menu, _)
                 | CheckMenuKind::Radio(check_menu, _, _)
-                | CheckMenuKind::Separate(check_menu) => check_menu,
-            };
-            Some(check_menu)
+                | CheckMenuKind::Separate(check_menu) => Some(check_menu),
+            },
+            MenuControl::CheckIconMenu(check_icon_kind) => Some(check_icon_kind.item().state()),
+            _ => None,
+        }
+    }
+
+    pub fn as_is_menu_item(&self) -> &dyn IsMenuItem {
+        match self {
+            MenuControl::MenuItem(menu_item) => menu_item,
+            MenuControl::IconMenu(icon_menu) => icon_menu,
+            MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
+                CheckMenuKind::CheckBox(check_menu, _)
+                | CheckMenuKind::Radio(check_menu, _, _)
+                | CheckMenuKind::Separate(check_menu) => check_menu.as_ref(),
+            },
+            MenuControl::CheckIconMenu(check_icon_kind) => check_icon_kind.item().icon_menu(),
+        }
+    }
+}
+
+/// Borrowed view of a check menu or checkable icon menu, driving the group logic
+enum CheckRef<'a, G> {
+    CheckBox(&'a Rc<CheckMenuItem>, &'a G),
+    Radio(&'a Rc<CheckMenuItem>, &'a Option<Rc<DefaultMenuId>>, &'a G),
+    Separate(&'a Rc<CheckMenuItem>),
+}
+
+impl<G> MenuControl<G> {
+    fn check_ref(&self) -> Option<CheckRef<'_, G>> {
+        match self {
+            MenuControl::CheckMenu(check_menu_kind) => Some(match check_menu_kind {
+                CheckMenuKind::CheckBox(check_menu, group) => CheckRef::CheckBox(check_menu, group),
+                CheckMenuKind::Radio(check_menu, default_id, group) => {
+                    CheckRef::Radio(check_menu, default_id, group)
+                }
+                CheckMenuKind::Separate(check_menu) => CheckRef::Separate(check_menu),
+            }),
+            MenuControl::CheckIconMenu(check_icon_kind) => Some(match check_icon_kind {
+                CheckIconKind::CheckBox(item, group) => CheckRef::CheckBox(item.state(), group),
+                CheckIconKind::Radio(item, default_id, group) => {
+                    CheckRef::Radio(item.state(), default_id, group)
+                }
+                CheckIconKind::Separate(item) => CheckRef::Separate(item.state()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Clears the default radio menu ID of a radio if it is `default_id`.
+    fn detach_default(&mut self, default_id: &MenuId) -> bool {
+        let default = match self {
+            MenuControl::CheckMenu(CheckMenuKind::Radio(_, default, _))
+            | MenuControl::CheckIconMenu(CheckIconKind::Radio(_, default, _)) => default,
+            _ => return false,
+        };
+
+        if default.as_deref() == Some(default_id) {
+            *default = None;
+            true
         } else {
-            None
+            false
+        }
+    }
+}
+
+/// A live `Menu` or `Submenu` that menu controls can be inserted into
+///
+/// Register it with [`MenuManager::insert_parent`], then use [`MenuManager::insert_into`]
+/// to keep the live menu and the manager registration in sync.
+#[derive(Clone)]
+pub enum MenuParent {
+    Menu(Menu),
+    Submenu(Submenu),
+}
+
+impl MenuParent {
+    pub fn id(&self) -> &MenuId {
+        match self {
+            MenuParent::Menu(menu) => menu.id(),
+            MenuParent::Submenu(submenu) => submenu.id(),
+        }
+    }
+
+    pub fn append(&self, item: &dyn IsMenuItem) -> Result<(), muda::Error> {
+        match self {
+            MenuParent::Menu(menu) => menu.append(item),
+            MenuParent::Submenu(submenu) => submenu.append(item),
+        }
+    }
+
+    pub fn insert(&self, item: &dyn IsMenuItem, position: usize) -> Result<(), muda::Error> {
+        match self {
+            MenuParent::Menu(menu) => menu.insert(item, position),
+            MenuParent::Submenu(submenu) => submenu.insert(item, position),
+        }
+    }
+
+    pub fn remove(&self, item: &dyn IsMenuItem) -> Result<(), muda::Error> {
+        match self {
+            MenuParent::Menu(menu) => menu.remove(item),
+            MenuParent::Submenu(submenu) => submenu.remove(item),
+        }
+    }
+
+    /// Gets the live children, in menu order.
+    pub fn items(&self) -> Vec<MenuItemKind> {
+        match self {
+            MenuParent::Menu(menu) => menu.items(),
+            MenuParent::Submenu(submenu) => submenu.items(),
         }
     }
+
+    /// Gets the position of a live child.
+    pub fn position_of(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<usize> {
+        let menu_id = menu_id.as_ref();
+        self.items()
+            .iter()
+            .position(|item| item.id().as_ref() == menu_id)
+    }
+}
+
+#[derive(Clone)]
+struct LazySubmenu<G> {
+    populate: PopulateSubmenu<G>,
+    children: Vec<MenuId>,
+}
+
+/// Text and enabled state of a busy menu control, as if it wasn't busy
+#[derive(Clone)]
+struct BusyItem {
+    text: String,
+    enabled: bool,
+    suffix: String,
 }
 
 /// Menu manager that provides centralized menu item management and group state handling
@@ -286,8 +770,51 @@ pub struct MenuManager<G>
 where
     G: Clone + Eq + Hash + PartialEq,
 {
-    id_to_menu: HashMap<Rc<MenuId>, MenuControl<G>>,
-    grouped_check_items: HashMap<G, HashMap<Rc<MenuId>, Rc<CheckMenuItem>>>,
+    id_to_menu: FxIndexMap<Rc<MenuId>, MenuControl<G>>,
+    grouped_check_items: FxIndexMap<G, FxIndexMap<Rc<MenuId>, Rc<CheckMenuItem>>>,
+    id_to_group: FxIndexMap<Rc<MenuId>, G>,
+    profiles: FxIndexMap<String, FxIndexSet<MenuId>>,
+    active_profile: Option<String>,
+    parents: HashMap<MenuId, MenuParent>,
+    id_to_parent: FxIndexMap<Rc<MenuId>, MenuId>,
+    lazy_submenus: FxIndexMap<MenuId, LazySubmenu<G>>,
+    label_templates: FxIndexMap<MenuId, String>,
+    template_vars: HashMap<String, String>,
+    master_checkboxes: FxIndexMap<MenuId, G>,
+    dependencies: FxIndexMap<MenuId, Vec<MenuId>>,
+    conditions: FxIndexMap<MenuId, ItemConditions>,
+    hidden: FxIndexMap<MenuId, Option<MenuId>>,
+    scheduler: Scheduler,
+    parent_groups: FxIndexMap<G, G>,
+    group_metas: FxIndexMap<G, GroupMeta>,
+    radio_policies: FxIndexMap<G, RadioPolicy>,
+    group_defaults: FxIndexMap<G, MenuId>,
+    default_checked: FxIndexMap<Rc<MenuId>, bool>,
+    accelerators: FxIndexMap<MenuId, Accelerator>,
+    translation_keys: FxIndexMap<MenuId, String>,
+    locales: HashMap<String, HashMap<String, String>>,
+    locale: Option<String>,
+    locale_accelerators: HashMap<String, HashMap<String, Accelerator>>,
+    named_icons: HashMap<String, Icon>,
+    item_icon_names: FxIndexMap<MenuId, String>,
+    descriptions: FxIndexMap<MenuId, String>,
+    handles: HandleSlots<G>,
+    stats: Option<ClickStats>,
+    groups_normalized: bool,
+    observers: Observers<G>,
+    panic_policy: PanicPolicy,
+    notify: Notify,
+    origin: Option<ChangeOrigin>,
+    busy: RefCell<FxIndexMap<MenuId, BusyItem>>,
+    busy_suffix: String,
+    quick_actions: FxIndexSet<MenuId>,
+    oneshots: FxIndexSet<MenuId>,
+    actions: FxIndexMap<MenuId, Action>,
+    action_context: ActionContext,
+    #[cfg(feature = "dialogs")]
+    confirmations: FxIndexMap<MenuId, Confirmation>,
+    timers: FxIndexMap<MenuId, TimerItem>,
+    error_handler: Option<ErrorHandler>,
 }
 
 impl<G> Default for MenuManager<G>
@@ -305,136 +832,3659 @@ where
 {
     pub fn new() -> Self {
         MenuManager {
-            id_to_menu: HashMap::new(),
-            grouped_check_items: HashMap::new(),
+            id_to_menu: FxIndexMap::default(),
+            grouped_check_items: FxIndexMap::default(),
+            id_to_group: FxIndexMap::default(),
+            profiles: FxIndexMap::default(),
+            active_profile: None,
+            parents: HashMap::new(),
+            id_to_parent: FxIndexMap::default(),
+            lazy_submenus: FxIndexMap::default(),
+            label_templates: FxIndexMap::default(),
+            template_vars: HashMap::new(),
+            master_checkboxes: FxIndexMap::default(),
+            dependencies: FxIndexMap::default(),
+            conditions: FxIndexMap::default(),
+            hidden: FxIndexMap::default(),
+            scheduler: Scheduler::default(),
+            parent_groups: FxIndexMap::default(),
+            group_metas: FxIndexMap::default(),
+            radio_policies: FxIndexMap::default(),
+            group_defaults: FxIndexMap::default(),
+            default_checked: FxIndexMap::default(),
+            accelerators: FxIndexMap::default(),
+            translation_keys: FxIndexMap::default(),
+            locales: HashMap::new(),
+            locale: None,
+            locale_accelerators: HashMap::new(),
+            named_icons: HashMap::new(),
+            item_icon_names: FxIndexMap::default(),
+            descriptions: FxIndexMap::default(),
+            handles: HandleSlots::new(),
+            stats: None,
+            groups_normalized: false,
+            observers: Observers::new(),
+            panic_policy: PanicPolicy::Propagate,
+            notify: Notify::Always,
+            origin: None,
+            busy: RefCell::new(FxIndexMap::default()),
+            busy_suffix: " …".to_owned(),
+            quick_actions: FxIndexSet::default(),
+            oneshots: FxIndexSet::default(),
+            actions: FxIndexMap::default(),
+            action_context: ActionContext::default(),
+            #[cfg(feature = "dialogs")]
+            confirmations: FxIndexMap::default(),
+            timers: FxIndexMap::default(),
+            error_handler: None,
         }
     }
 
     /// Inserts a menu control from the menu manager.
-    pub fn insert(&mut self, menu_control: MenuControl<G>) {
-        match &menu_control {
-            MenuControl::MenuItem(menu_item) => {
-                self.id_to_menu
-                    .insert(Rc::new(menu_item.id().clone()), menu_control);
-            }
-            MenuControl::IconMenu(icon_menu) => {
-                self.id_to_menu
-                    .insert(Rc::new(icon_menu.id().clone()), menu_control);
-            }
-            MenuControl::CheckMenu(check_menu_mind) => match check_menu_mind {
-                CheckMenuKind::Separate(check_menu) => {
-                    self.id_to_menu
-                        .insert(Rc::new(check_menu.id().clone()), menu_control);
-                }
-                CheckMenuKind::Radio(check_menu, _default_menu_id, menu_group) => {
-                    let menu_id = Rc::new(check_menu.id().clone());
-                    let menu_group = menu_group.clone();
-                    let check_menu = check_menu.clone();
-
-                    self.id_to_menu.insert(menu_id.clone(), menu_control);
-                    self.grouped_check_items
-                        .entry(menu_group)
-                        .or_default()
-                        .insert(menu_id, check_menu);
-                }
-                CheckMenuKind::CheckBox(check_menu, menu_group) => {
-                    let menu_id = Rc::new(check_menu.id().clone());
-                    let menu_group = menu_group.clone();
-                    let check_menu = check_menu.clone();
-
-                    self.id_to_menu.insert(menu_id.clone(), menu_control);
-                    self.grouped_check_items
-                        .entry(menu_group)
-                        .or_default()
-                        .insert(menu_id, check_menu);
-                }
-            },
+    ///
+    /// The checked state of a check menu at insert time is its default,
+    /// restored by [`MenuManager::reset_group`] and [`MenuManager::reset_all`].
+    ///
+    /// Returns a [`MenuHandle`] for cheap lookups, inserting a menu control
+    /// with the ID of another one keeps the handle.
+    pub fn insert(&mut self, menu_control: MenuControl<G>) -> MenuHandle {
+        self.begin_observe();
+        // The Rc'd ID is shared by every index of the menu control
+        let menu_id = Rc::new(menu_control.id().clone());
+
+        if let Some(check_menu) = menu_control.as_check_menu() {
+            self.default_checked
+                .insert(menu_id.clone(), check_menu.is_checked());
         }
-    }
 
-    /// Removes a menu control from the menu manager.
-    pub fn remove(&mut self, menu_id: &MenuId) {
-        let remove_menu = self.id_to_menu.remove(menu_id);
-
-        if let Some(remove_menu) = remove_menu {
-            match &remove_menu {
-                MenuControl::MenuItem(_) | MenuControl::IconMenu(_) => {}
-                MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
-                    CheckMenuKind::Separate(_) => {}
-                    CheckMenuKind::CheckBox(_, group) | CheckMenuKind::Radio(_, _, group) => {
-                        if let Some(map) = self.grouped_check_items.get_mut(group) {
-                            map.remove(menu_id);
-                        }
-                    }
-                },
+        // A menu control replaced under the same ID may have moved to another group,
+        // else it keeps its position in the group
+        let moved_group = match menu_control.check_ref() {
+            Some(CheckRef::CheckBox(_, menu_group) | CheckRef::Radio(_, _, menu_group)) => {
+                self.id_to_group.get(&menu_id) != Some(menu_group)
             }
+            _ => true,
+        };
+        if moved_group {
+            self.remove_from_group(&menu_id);
         }
+
+        if let Some(
+            CheckRef::CheckBox(check_menu, menu_group) | CheckRef::Radio(check_menu, _, menu_group),
+        ) = menu_control.check_ref()
+        {
+            self.grouped_check_items
+                .entry(menu_group.clone())
+                .or_default()
+                .insert(menu_id.clone(), check_menu.clone());
+            self.id_to_group.insert(menu_id.clone(), menu_group.clone());
+        }
+
+        trace_event!(debug, menu_id = ?menu_id, "insert menu control");
+
+        self.observe_item(&menu_id);
+        let handle = self.handles.insert(&menu_id, &menu_control);
+        self.id_to_menu.insert(menu_id, menu_control);
+        self.end_observe();
+        handle
     }
 
-    /// Updates the menu control state based on the provided menu ID, and callback the menu control.
+    /// Swaps the menu item of a menu control, e.g. to rebuild it with a new accelerator.
     ///
-    /// NOTE: If the menu control is a radio:    
-    ///     there is a default radio menu, the cllback menu control is the cheked menu   
-    ///     there is no default radio menu, the callback menu control is the click menu   
-    pub fn update(&mut self, menu_id: &MenuId, callback: impl Fn(Option<&MenuControl<G>>)) {
-        let menu_control = self.id_to_menu.get(menu_id);
+    /// The new menu control must have the same ID. It keeps the registration order, the
+    /// position in its group (unless it moves to another group), the position in the live
+    /// parent menu, the handle and the default checked state. With `keep_checked`, it also
+    /// takes over the checked state of the replaced check menu.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{Menu, MenuId, MenuItem, accelerator::Accelerator};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert_parent(MenuParent::Menu(Menu::with_id("tray")));
+    /// let open = MenuItem::with_id("open", "Open", true, None);
+    /// let quit = MenuItem::with_id("quit", "Quit", true, None);
+    /// manager.insert_into(&MenuId::new("tray"), MenuControl::MenuItem(open), None).unwrap();
+    /// manager.insert_into(&MenuId::new("tray"), MenuControl::MenuItem(quit), None).unwrap();
+    ///
+    /// let accelerator: Option<Accelerator> = "Ctrl+O".parse().ok();
+    /// let open = MenuItem::with_id("open", "Open", true, accelerator);
+    /// manager.replace(&MenuId::new("open"), MenuControl::MenuItem(open), false).unwrap();
+    ///
+    /// assert_eq!(manager.position_of(&MenuId::new("open")), Some(0));
+    /// ```
+    pub fn replace(
+        &mut self,
+        menu_id: &MenuId,
+        menu_control: MenuControl<G>,
+        keep_checked: bool,
+    ) -> Result<MenuHandle, Error> {
+        if menu_control.id() != menu_id {
+            return Err(Error::IdMismatch {
+                expected: menu_id.clone(),
+                found: menu_control.id().clone(),
+            });
+        }
 
-        if let Some(menu) = menu_control {
-            match menu {
-                MenuControl::MenuItem(_) | MenuControl::IconMenu(_) => {}
-                MenuControl::CheckMenu(check_menu_kind) => match check_menu_kind {
-                    CheckMenuKind::CheckBox(_, _) | CheckMenuKind::Separate(_) => {}
-                    CheckMenuKind::Radio(check_menu, default_menu_id, group) => {
-                        if let Some(check_menus) = self.get_check_items_from_grouped(group) {
-                            let click_menu_state = check_menu.is_checked();
-
-                            let (is_checked_menu_id, is_checked_menu) = if click_menu_state {
-                                (check_menu.id(), Some(menu))
-                            } else {
-                                let Some(default_menu_id) = default_menu_id else {
-                                    return callback(menu_control);
-                                };
-
-                                let default_menu = self.get_menu_item_from_id(default_menu_id);
-
-                                if let Some(MenuControl::CheckMenu(CheckMenuKind::Radio(
-                                    menu,
-                                    _,
-                                    _,
-                                ))) = default_menu
-                                {
-                                    menu.set_checked(true);
-                                    (default_menu_id.as_ref(), default_menu)
-                                } else {
-                                    return callback(menu_control);
-                                }
-                            };
-
-                            check_menus
-                                .iter()
-                                .filter(|(menu_id, _)| menu_id.as_ref().ne(is_checked_menu_id))
-                                .for_each(|(_, check_menu)| check_menu.set_checked(false));
-
-                            return callback(is_checked_menu);
-                        }
-                    }
-                },
-            }
+        let old_control = self
+            .id_to_menu
+            .get(menu_id)
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+
+        if keep_checked
+            && let (Some(old_check_menu), Some(check_menu)) =
+                (old_control.as_check_menu(), menu_control.as_check_menu())
+        {
+            check_menu.set_checked(old_check_menu.is_checked());
+        }
+
+        if let Some(parent) = self
+            .id_to_parent
+            .get(menu_id)
+            .and_then(|parent_id| self.parents.get(parent_id))
+            && let Some(position) = parent.position_of(menu_id)
+        {
+            parent.remove(old_control.as_is_menu_item())?;
+            parent.insert(menu_control.as_is_menu_item(), position)?;
+        }
+
+        let default_checked = self.default_checked.get(menu_id).copied();
+        let handle = self.insert(menu_control);
+
+        if let Some(default_checked) = default_checked
+            && let Some(checked) = self.default_checked.get_mut(menu_id)
+        {
+            *checked = default_checked;
         }
+        self.refresh_check_icons();
 
-        callback(menu_control);
+        Ok(handle)
     }
 
-    /// Gets a menu control from the menu manager based on the provided menu ID.
-    pub fn get_menu_item_from_id(&self, menu_id: &MenuId) -> Option<&MenuControl<G>> {
-        self.id_to_menu.get(menu_id)
+    /// Registers a live `Menu` or `Submenu` that menu controls can be inserted into.
+    pub fn insert_parent(&mut self, parent: MenuParent) {
+        self.parents.insert(parent.id().clone(), parent);
     }
 
-    /// Gets grouped check menu items from the menu manager based on the provided menu group id.
-    pub fn get_check_items_from_grouped(
-        &self,
-        group_id: &G,
-    ) -> Option<&HashMap<Rc<MenuId>, Rc<CheckMenuItem>>> {
-        self.grouped_check_items.get(group_id)
+    /// Unregisters a parent menu, e.g. a submenu dropped from the menu, and returns it.
+    ///
+    /// The menu controls still inserted into it are removed too, like with
+    /// [`MenuManager::remove`]. A lazy submenu is no longer refreshed.
+    pub fn remove_parent(&mut self, parent_id: &MenuId) -> Option<MenuParent> {
+        let child_ids: Vec<MenuId> = self
+            .id_to_parent
+            .iter()
+            .filter(|(_, child_parent_id)| *child_parent_id == parent_id)
+            .map(|(menu_id, _)| menu_id.as_ref().clone())
+            .collect();
+        for child_id in &child_ids {
+            // The parent is gone, so a removed default radio is expected
+            let _ = self.remove(child_id);
+        }
+
+        self.lazy_submenus.shift_remove(parent_id);
+        self.parents.remove(parent_id)
+    }
+
+    /// Inserts a menu control into the menu manager and into the live parent menu.
+    ///
+    /// If `position` is `None`, the menu control is appended to the parent menu.
+    pub fn insert_into(
+        &mut self,
+        parent_id: &MenuId,
+        menu_control: MenuControl<G>,
+        position: Option<usize>,
+    ) -> Result<MenuHandle, Error> {
+        let parent = self.parents.get(parent_id).ok_or_else(|| {
+            trace_event!(warn, parent_id = ?parent_id, "parent menu not registered");
+            Error::ParentNotFound(parent_id.clone())
+        })?;
+
+        let item = menu_control.as_is_menu_item();
+        match position {
+            Some(position) => parent.insert(item, position)?,
+            None => parent.append(item)?,
+        }
+
+        let menu_id = menu_control.id().clone();
+        let handle = self.insert(menu_control);
+
+        if let Some((menu_id, _)) = self.id_to_menu.get_key_value(&menu_id) {
+            self.id_to_parent.insert(menu_id.clone(), parent_id.clone());
+        }
+
+        Ok(handle)
+    }
+
+    /// Inserts a menu control into the live parent menu that removes itself once activated,
+    /// e.g. "Dismiss this warning" or "Install pending update".
+    ///
+    /// It is removed from the menu manager and the parent menu by [`MenuManager::update`],
+    /// after the callback. With an action (see [`MenuManager::set_action`]), it is removed by
+    /// [`MenuManager::run_action`] instead, unless the action failed or was cancelled.
+    /// Subscribers get a [`ChangeEvent::ItemRemoved`] with the [`ChangeOrigin::UserClick`] origin.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{ChangeEvent, MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{Menu, MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let tray_menu = Menu::new();
+    /// manager.insert_parent(MenuParent::Menu(tray_menu.clone()));
+    ///
+    /// let dismiss = MenuItem::with_id("dismiss", "Dismiss this warning", true, None);
+    /// manager
+    ///     .insert_oneshot(tray_menu.id(), MenuControl::MenuItem(dismiss), Some(0))
+    ///     .unwrap();
+    /// let events = manager.subscribe();
+    ///
+    /// // In the menu event handler
+    /// manager.update(&MenuId::new("dismiss"), |_| {});
+    ///
+    /// assert!(manager.get_menu_item_from_id("dismiss").is_none());
+    /// assert!(tray_menu.items().is_empty());
+    /// assert!(matches!(events.try_recv().unwrap(), ChangeEvent::ItemRemoved { .. }));
+    /// ```
+    pub fn insert_oneshot(
+        &mut self,
+        parent_id: &MenuId,
+        menu_control: MenuControl<G>,
+        position: Option<usize>,
+    ) -> Result<MenuHandle, Error> {
+        let menu_id = menu_control.id().clone();
+        let handle = self.insert_into(parent_id, menu_control, position)?;
+        self.oneshots.insert(menu_id);
+        Ok(handle)
+    }
+
+    pub fn is_oneshot(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> bool {
+        self.oneshots.contains(&MenuKey::new(menu_id))
+    }
+
+    /// Removes an activated one-shot menu control.
+    fn spend_oneshot(&mut self, menu_id: &MenuId) {
+        if !self.oneshots.contains(menu_id) {
+            return;
+        }
+
+        trace_event!(debug, menu_id = ?menu_id, "remove activated one-shot menu control");
+        let removed = self.with_origin(ChangeOrigin::UserClick, |manager| manager.remove(menu_id));
+        self.report(removed);
+    }
+
+    /// Inserts a menu control into the menu manager and into the live parent menu at a position.
+    pub fn insert_at(
+        &mut self,
+        parent_id: &MenuId,
+        position: usize,
+        menu_control: MenuControl<G>,
+    ) -> Result<MenuHandle, Error> {
+        self.insert_into(parent_id, menu_control, Some(position))
+    }
+
+    /// Inserts a menu control right above another one, e.g. plugin entries above "Quit".
+    ///
+    /// The other menu control must have been inserted with [`MenuManager::insert_into`].
+    pub fn insert_before(
+        &mut self,
+        before_id: &MenuId,
+        menu_control: MenuControl<G>,
+    ) -> Result<MenuHandle, Error> {
+        let (parent_id, position) = self.locate(before_id)?;
+        self.insert_into(&parent_id, menu_control, Some(position))
+    }
+
+    /// Moves a menu control to another position of its live parent menu.
+    ///
+    /// The menu control must have been inserted with [`MenuManager::insert_into`].
+    pub fn move_item(&mut self, menu_id: &MenuId, position: usize) -> Result<(), Error> {
+        let (parent_id, _) = self.locate(menu_id)?;
+        let (Some(parent), Some(menu)) =
+            (self.parents.get(&parent_id), self.id_to_menu.get(menu_id))
+        else {
+            return Err(Error::MenuNotFound(menu_id.clone()));
+        };
+
+        let item = menu.as_is_menu_item();
+        parent.remove(item)?;
+        parent.insert(item, position.min(parent.items().len()))?;
+
+        Ok(())
+    }
+
+    /// Appends a registered menu control to another menu too, e.g. a window menu bar or a
+    /// context menu created with `muda`.
+    ///
+    /// Both menus show the same menu item, so they share its text, enabled and checked
+    /// state, and clicks in either emit the same `MenuEvent` for [`MenuManager::update`].
+    /// The parent of [`MenuManager::insert_into`] stays the tracked one, e.g. for
+    /// [`MenuManager::move_item`].
+    pub fn share_into(&self, parent: &MenuParent, menu_id: &MenuId) -> Result<(), Error> {
+        let menu = self
+            .id_to_menu
+            .get(menu_id)
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+
+        parent.append(menu.as_is_menu_item())?;
+        Ok(())
+    }
+
+    /// Appends the check menus of a group to another menu too, in group order.
+    ///
+    /// One group definition then drives both the tray menu and the window menu, see
+    /// [`MenuManager::share_into`].
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use muda::{CheckMenuItem, Menu, MenuId, Submenu};
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, MenuParent};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// let tray_menu = Menu::new();
+    /// manager.insert_parent(MenuParent::Menu(tray_menu.clone()));
+    /// for (id, checked) in [("light", true), ("dark", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     let radio = MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme"));
+    ///     manager.insert_into(tray_menu.id(), radio, None).unwrap();
+    /// }
+    ///
+    /// // The "View > Theme" submenu of the window menu bar
+    /// let theme_submenu = Submenu::new("Theme", true);
+    /// manager
+    ///     .share_group_into(&MenuParent::Submenu(theme_submenu.clone()), &"theme")
+    ///     .unwrap();
+    ///
+    /// // A click in either menu checks the radio in both
+    /// manager.update(&MenuId::new("dark"), |_| {});
+    /// assert_eq!(theme_submenu.items().len(), 2);
+    /// ```
+    pub fn share_group_into(&self, parent: &MenuParent, group_id: &G) -> Result<(), Error> {
+        let Some(check_menus) = self.grouped_check_items.get(group_id) else {
+            return Ok(());
+        };
+
+        check_menus
+            .keys()
+            .try_for_each(|menu_id| self.share_into(parent, menu_id))
+    }
+
+    /// Gets the position of a menu control in its live parent menu.
+    pub fn position_of(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<usize> {
+        self.get_parent(menu_id)?.position_of(menu_id)
+    }
+
+    fn locate(&self, menu_id: &MenuId) -> Result<(MenuId, usize), Error> {
+        let parent_id = self
+            .id_to_parent
+            .get(menu_id)
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+        let position = self
+            .parents
+            .get(parent_id)
+            .ok_or_else(|| Error::ParentNotFound(parent_id.clone()))?
+            .position_of(menu_id)
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+
+        Ok((parent_id.clone(), position))
+    }
+
+    /// Registers a submenu whose children are produced by a callback.
+    ///
+    /// The children are populated immediately, and again on every refresh.
+    /// [`TrayController`] refreshes lazy submenus when the mouse enters the tray icon,
+    /// right before the menu can be opened (**Windows / macOS**). Elsewhere, call
+    /// [`MenuManager::refresh_submenu`] whenever the children may have changed.
+    pub fn insert_lazy_submenu(
+        &mut self,
+        submenu: Submenu,
+        populate: impl Fn() -> Vec<MenuControl<G>> + 'static,
+    ) -> Result<(), Error> {
+        let submenu_id = submenu.id().clone();
+
+        self.insert_parent(MenuParent::Submenu(submenu));
+        self.lazy_submenus.insert(
+            submenu_id.clone(),
+            LazySubmenu {
+                populate: Rc::new(populate),
+                children: Vec::new(),
+            },
+        );
+
+        self.refresh_submenu(&submenu_id).map(|_| ())
+    }
+
+    /// Replaces the children of a lazy submenu with the ones produced by its callback.
+    ///
+    /// Returns `false` if the submenu was not registered with [`MenuManager::insert_lazy_submenu`].
+    pub fn refresh_submenu(&mut self, submenu_id: &MenuId) -> Result<bool, Error> {
+        let Some(lazy_submenu) = self.lazy_submenus.get_mut(submenu_id) else {
+            return Ok(false);
+        };
+
+        let old_children = std::mem::take(&mut lazy_submenu.children);
+        let populate = lazy_submenu.populate.clone();
+
+        // The children come back with the same IDs, so they stay the default radios
+        old_children
+            .iter()
+            .for_each(|child_id| self.remove_control(child_id));
+
+        let mut children = Vec::new();
+        for menu_control in populate() {
+            children.push(menu_control.id().clone());
+            self.insert_into(submenu_id, menu_control, None)?;
+        }
+
+        if let Some(lazy_submenu) = self.lazy_submenus.get_mut(submenu_id) {
+            lazy_submenu.children = children;
+        }
+
+        Ok(true)
+    }
+
+    /// Refreshes all lazy submenus.
+    pub fn refresh_lazy_submenus(&mut self) -> Result<(), Error> {
+        let submenu_ids: Vec<MenuId> = self.lazy_submenus.keys().cloned().collect();
+
+        for submenu_id in submenu_ids {
+            self.refresh_submenu(&submenu_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the parent menu of a menu control inserted with [`MenuManager::insert_into`].
+    pub fn get_parent(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&MenuParent> {
+        self.id_to_parent
+            .get(&MenuKey::new(menu_id))
+            .and_then(|parent_id| self.parents.get(parent_id))
+    }
+
+    /// Removes a menu control from the menu manager.
+    ///
+    /// If the menu control was inserted with [`MenuManager::insert_into`],
+    /// it is also detached from the live parent menu. Its settings are dropped too, e.g. its
+    /// dependencies, and it is no longer a dependency or a member of a profile.
+    ///
+    /// If the menu control was the default radio of its group, it is no longer the default
+    /// and [`Error::DefaultRadioRemoved`] is returned as a warning, the removal is done anyway.
+    /// Use [`MenuManager::remove_and_reassign_default`] to pick another default radio.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, Error, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// for id in ["system", "dark"] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, false, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+    /// }
+    /// manager.set_group_default("theme", MenuId::new("system")).unwrap();
+    ///
+    /// let removed = manager.remove(&MenuId::new("system"));
+    /// assert!(matches!(removed, Err(Error::DefaultRadioRemoved(_))));
+    /// assert_eq!(manager.group_default(&"theme"), None);
+    /// ```
+    pub fn remove(&mut self, menu_id: &MenuId) -> Result<(), Error> {
+        let group = self.group_of(menu_id).cloned();
+        self.remove_control(menu_id);
+
+        let Some(group) = group else {
+            return Ok(());
+        };
+
+        if self.detach_default(&group, menu_id) {
+            trace_event!(warn, menu_id = ?menu_id, "removed the default radio of a group");
+            return Err(Error::DefaultRadioRemoved(menu_id.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Removes a radio, and makes another radio of its group the default radio.
+    ///
+    /// Fails with [`Error::MenuNotFound`] before removing anything if `new_default_id`
+    /// is not another radio of the same group.
+    pub fn remove_and_reassign_default(
+        &mut self,
+        menu_id: &MenuId,
+        new_default_id: &MenuId,
+    ) -> Result<(), Error> {
+        let group = self
+            .group_of(menu_id)
+            .cloned()
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+
+        if menu_id == new_default_id || !self.is_radio_of(new_default_id, &group) {
+            return Err(Error::MenuNotFound(new_default_id.clone()));
+        }
+
+        self.remove_control(menu_id);
+        self.detach_default(&group, menu_id);
+        self.set_group_default(group, new_default_id.clone())
+    }
+
+    /// Removes a default radio menu ID from a group, and from the radios of the group.
+    ///
+    /// Returns `true` if anything referenced it.
+    fn detach_default(&mut self, group: &G, default_id: &MenuId) -> bool {
+        let mut detached = false;
+
+        if self.group_defaults.get(group) == Some(default_id) {
+            self.group_defaults.shift_remove(group);
+            detached = true;
+        }
+
+        let radio_ids: Vec<Rc<MenuId>> = self
+            .grouped_check_items
+            .get(group)
+            .map(|check_menus| check_menus.keys().cloned().collect())
+            .unwrap_or_default();
+
+        for radio_id in radio_ids {
+            if let Some(menu) = self.id_to_menu.get_mut(&radio_id)
+                && menu.detach_default(default_id)
+            {
+                self.handles.insert(&radio_id, menu);
+                detached = true;
+            }
+        }
+
+        detached
+    }
+
+    /// Removes a menu control without touching the default radio menu IDs that refer to it.
+    fn remove_control(&mut self, menu_id: &MenuId) {
+        self.begin_observe();
+        self.observe_item(menu_id);
+        let remove_menu = self.id_to_menu.shift_remove(menu_id);
+        trace_event!(
+            debug,
+            menu_id = ?menu_id,
+            found = remove_menu.is_some(),
+            "remove menu control",
+        );
+
+        self.handles.remove(menu_id);
+        self.label_templates.swap_remove(menu_id);
+        self.default_checked.swap_remove(menu_id);
+        self.accelerators.swap_remove(menu_id);
+        self.translation_keys.swap_remove(menu_id);
+        self.item_icon_names.swap_remove(menu_id);
+        self.descriptions.swap_remove(menu_id);
+        self.conditions.swap_remove(menu_id);
+        let hidden = self.hidden.swap_remove(menu_id).is_some();
+        self.scheduler.cancel_menu(menu_id);
+        self.busy.get_mut().swap_remove(menu_id);
+        // Quick actions are listed in the order they were set
+        self.quick_actions.shift_remove(menu_id);
+        self.oneshots.swap_remove(menu_id);
+        self.actions.swap_remove(menu_id);
+        #[cfg(feature = "dialogs")]
+        self.confirmations.swap_remove(menu_id);
+        self.timers.swap_remove(menu_id);
+        self.master_checkboxes.swap_remove(menu_id);
+        // Dependents keep the rest of their dependencies
+        self.dependencies.retain(|dependent_id, depends_on| {
+            depends_on.retain(|depends_on_id| depends_on_id != menu_id);
+            dependent_id != menu_id && !depends_on.is_empty()
+        });
+        self.profiles.values_mut().for_each(|profile_ids| {
+            profile_ids.shift_remove(menu_id);
+        });
+        self.lazy_submenus
+            .values_mut()
+            .for_each(|lazy_submenu| lazy_submenu.children.retain(|child_id| child_id != menu_id));
+
+        if let Some(parent) = self
+            .id_to_parent
+            .swap_remove(menu_id)
+            .and_then(|parent_id| self.parents.get(&parent_id))
+            && let Some(remove_menu) = &remove_menu
+            && !hidden
+        {
+            self.report(parent.remove(remove_menu.as_is_menu_item()));
+        }
+
+        self.remove_from_group(menu_id);
+        if is_sparse(self.id_to_menu.len(), self.id_to_menu.capacity()) {
+            trace_event!(
+                debug,
+                len = self.id_to_menu.len(),
+                "shrink sparse menu maps"
+            );
+            self.shrink_to_fit();
+        }
+        self.end_observe();
+    }
+
+    /// Removes a check menu from its group, and the group once it is empty.
+    fn remove_from_group(&mut self, menu_id: &MenuId) {
+        let Some(group) = self.id_to_group.swap_remove(menu_id) else {
+            return;
+        };
+
+        if let Some(check_menus) = self.grouped_check_items.get_mut(&group) {
+            check_menus.shift_remove(menu_id);
+
+            if check_menus.is_empty() {
+                trace_event!(debug, menu_id = ?menu_id, "remove empty group");
+                self.grouped_check_items.shift_remove(&group);
+            } else if is_sparse(check_menus.len(), check_menus.capacity()) {
+                check_menus.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Releases the memory kept by the maps of the menu manager after removals.
+    ///
+    /// Maps are also shrunk automatically once removals leave them mostly empty, e.g. after
+    /// tearing down a big dynamic submenu, and a group is dropped with its last check menu.
+    /// Call it to release the memory right away. Group settings (e.g. the radio policies)
+    /// are kept, so a rebuilt group gets them back.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// for i in 0..1000 {
+    ///     manager.insert(MenuControl::MenuItem(MenuItem::with_id(i.to_string(), "Item", true, None)));
+    /// }
+    /// for i in 0..1000 {
+    ///     manager.remove(&MenuId::new(i.to_string())).unwrap();
+    /// }
+    ///
+    /// manager.shrink_to_fit();
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.id_to_menu.shrink_to_fit();
+        self.grouped_check_items
+            .values_mut()
+            .for_each(|check_menus| check_menus.shrink_to_fit());
+        self.grouped_check_items.shrink_to_fit();
+        self.id_to_group.shrink_to_fit();
+        self.profiles
+            .values_mut()
+            .for_each(|menu_ids| menu_ids.shrink_to_fit());
+        self.profiles.shrink_to_fit();
+        self.parents.shrink_to_fit();
+        self.id_to_parent.shrink_to_fit();
+        self.lazy_submenus.shrink_to_fit();
+        self.label_templates.shrink_to_fit();
+        self.template_vars.shrink_to_fit();
+        self.master_checkboxes.shrink_to_fit();
+        self.dependencies.shrink_to_fit();
+        self.conditions.shrink_to_fit();
+        self.hidden.shrink_to_fit();
+        self.scheduler.shrink_to_fit();
+        self.parent_groups.shrink_to_fit();
+        self.group_metas.shrink_to_fit();
+        self.radio_policies.shrink_to_fit();
+        self.group_defaults.shrink_to_fit();
+        self.default_checked.shrink_to_fit();
+        self.accelerators.shrink_to_fit();
+        self.translation_keys.shrink_to_fit();
+        self.locales.shrink_to_fit();
+        self.locale_accelerators.shrink_to_fit();
+        self.named_icons.shrink_to_fit();
+        self.item_icon_names.shrink_to_fit();
+        self.descriptions.shrink_to_fit();
+        self.handles.shrink_to_fit();
+        self.busy.get_mut().shrink_to_fit();
+        self.quick_actions.shrink_to_fit();
+        self.oneshots.shrink_to_fit();
+        self.actions.shrink_to_fit();
+        #[cfg(feature = "dialogs")]
+        self.confirmations.shrink_to_fit();
+        self.timers.shrink_to_fit();
+    }
+
+    /// Updates the menu control state based on the provided menu ID, and callback the menu control.
+    ///
+    /// NOTE: If the menu control is a radio:    
+    ///     there is a default radio menu, the callback menu control is the checked menu   
+    ///     there is no default radio menu, the callback menu control is the click menu   
+    ///     see [`MenuManager::set_radio_policy`] to change what happens when the checked radio is clicked
+    pub fn update(&mut self, menu_id: &MenuId, callback: impl Fn(Option<&MenuControl<G>>)) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("update", menu_id = ?menu_id).entered();
+
+        // The platform already toggled a clicked check menu
+        self.begin_observe_as(ChangeOrigin::UserClick);
+        if let Some(menu) = self.id_to_menu.get(menu_id)
+            && let Some(check_menu) = menu.platform_check_menu()
+        {
+            let checked = !check_menu.is_checked();
+            self.observers.record(menu_id, || {
+                Some(self.item_state(menu_id, menu).with_checked(checked))
+            });
+        }
+
+        if !self.groups_normalized {
+            self.groups_normalized = true;
+            self.normalize_groups_except(self.group_of(menu_id));
+        }
+
+        if let Some(stats) = &mut self.stats
+            && self.id_to_menu.contains_key(menu_id)
+        {
+            stats.record(menu_id);
+        }
+
+        // Unlike check menus, checkable icon menus aren't toggled by the platform
+        if let Some(MenuControl::CheckIconMenu(check_icon_kind)) = self.id_to_menu.get(menu_id) {
+            self.observe_item(menu_id);
+            let state = check_icon_kind.item().state();
+            state.set_checked(!state.is_checked());
+        }
+
+        self.sync_master_checkboxes(menu_id);
+        self.apply_dependencies();
+
+        let menu_control = self.settle_clicked_radio(menu_id);
+        self.refresh_check_icons();
+        self.end_observe();
+
+        #[cfg(debug_assertions)]
+        self.debug_check_invariants();
+
+        call_with_policy(self.panic_policy, || callback(menu_control));
+
+        if !self.actions.contains_key(menu_id) {
+            self.spend_oneshot(menu_id);
+        }
+    }
+
+    /// Clicks a menu control without the platform menus, e.g. from a test, another tray
+    /// backend or another process.
+    ///
+    /// Like the platform menus, a check menu is toggled before [`MenuManager::update`].
+    /// Returns `false` without an update if the menu control is unknown or disabled.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let autostart = CheckMenuItem::with_id("autostart", "Autostart", true, false, None);
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(autostart))));
+    ///
+    /// let autostart = MenuId::new("autostart");
+    /// assert!(manager.activate(&autostart, |_| {}));
+    /// let menu = manager.get_menu_item_from_id(&autostart).unwrap();
+    /// assert!(menu.as_check_menu().unwrap().is_checked());
+    /// ```
+    pub fn activate(
+        &mut self,
+        menu_id: &MenuId,
+        callback: impl Fn(Option<&MenuControl<G>>),
+    ) -> bool {
+        let Some(menu) = self
+            .id_to_menu
+            .get(menu_id)
+            .filter(|menu| menu.is_enabled())
+        else {
+            return false;
+        };
+
+        if let Some(check_menu) = menu.platform_check_menu() {
+            check_menu.set_checked(!check_menu.is_checked());
+        }
+
+        self.update(menu_id, callback);
+        true
+    }
+
+    /// Marks a menu control as busy, e.g. during async work started by a click.
+    ///
+    /// A busy menu control is disabled and its text gets the busy suffix, see
+    /// [`MenuManager::set_busy_suffix`]. Clearing it restores its text and enabled state.
+    ///
+    /// Changes made through the menu manager while busy, e.g. a new text or a dependency
+    /// enabling it, are kept for when it is done. [`MenuManager::state`] reports it as if
+    /// it wasn't busy.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("sync", "Sync Now", true, None)));
+    ///
+    /// let sync = MenuId::new("sync");
+    /// manager.set_busy(&sync, true);
+    /// assert_eq!(manager.get_menu_item_from_id(&sync).unwrap().text(), "Sync Now …");
+    ///
+    /// manager.batch(|batch| {
+    ///     batch.set_text(&sync, "Sync Again");
+    /// });
+    /// assert_eq!(manager.get_menu_item_from_id(&sync).unwrap().text(), "Sync Again …");
+    ///
+    /// manager.set_busy(&sync, false);
+    /// assert_eq!(manager.get_menu_item_from_id(&sync).unwrap().text(), "Sync Again");
+    /// assert!(manager.get_menu_item_from_id(&sync).unwrap().is_enabled());
+    /// ```
+    pub fn set_busy(&mut self, menu_id: &MenuId, busy: bool) {
+        let Some(menu) = self.id_to_menu.get(menu_id) else {
+            return;
+        };
+
+        self.begin_observe();
+        self.observe_item(menu_id);
+        let busy_items = self.busy.get_mut();
+        match (busy, busy_items.contains_key(menu_id)) {
+            (true, false) => {
+                let text = menu.text();
+                menu.set_text(&format!("{text}{}", self.busy_suffix));
+                busy_items.insert(
+                    menu_id.clone(),
+                    BusyItem {
+                        text,
+                        enabled: menu.is_enabled(),
+                        suffix: self.busy_suffix.clone(),
+                    },
+                );
+                menu.set_enabled(false);
+            }
+            (false, true) => {
+                if let Some(busy_item) = busy_items.shift_remove(menu_id) {
+                    menu.set_text(&busy_item.text);
+                    menu.set_enabled(busy_item.enabled);
+                }
+            }
+            _ => {}
+        }
+        self.end_observe();
+    }
+
+    pub fn is_busy(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> bool {
+        self.busy.borrow().contains_key(&MenuKey::new(menu_id))
+    }
+
+    /// Sets the suffix appended to the text of busy menu controls, `" …"` by default.
+    ///
+    /// Menu controls that are already busy keep their suffix.
+    pub fn set_busy_suffix(&mut self, suffix: &str) {
+        self.busy_suffix = suffix.to_owned();
+    }
+
+    /// Gets the text of a menu control without the suffix of a busy one.
+    pub(crate) fn item_text(&self, menu_id: &MenuId, menu: &MenuControl<G>) -> String {
+        match self.busy.borrow().get(menu_id) {
+            Some(busy_item) => busy_item.text.clone(),
+            None => menu.text(),
+        }
+    }
+
+    /// Gets the enabled state of a menu control as if it wasn't busy.
+    pub(crate) fn is_item_enabled(&self, menu_id: &MenuId, menu: &MenuControl<G>) -> bool {
+        match self.busy.borrow().get(menu_id) {
+            Some(busy_item) => busy_item.enabled,
+            None => menu.is_enabled(),
+        }
+    }
+
+    /// Sets the text of a menu control, a busy one keeps its suffix.
+    pub(crate) fn set_item_text(&self, menu_id: &MenuId, menu: &MenuControl<G>, text: &str) {
+        match self.busy.borrow_mut().get_mut(menu_id) {
+            Some(busy_item) => {
+                menu.set_text(&format!("{text}{}", busy_item.suffix));
+                busy_item.text = text.to_owned();
+            }
+            None => menu.set_text(text),
+        }
+    }
+
+    /// Enables or disables a menu control, a busy one stays disabled until it is done.
+    pub(crate) fn set_item_enabled(&self, menu_id: &MenuId, menu: &MenuControl<G>, enabled: bool) {
+        match self.busy.borrow_mut().get_mut(menu_id) {
+            Some(busy_item) => busy_item.enabled = enabled,
+            None => menu.set_enabled(enabled),
+        }
+    }
+
+    /// Attaches a declarative action to a menu control, `None` detaches it.
+    ///
+    /// Returns `false` if the menu control is not registered.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{Action, MenuAction, MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// for (id, text) in [("staging", "Use Staging"), ("report", "Report a Bug")] {
+    ///     manager.insert(MenuControl::MenuItem(MenuItem::with_id(id, text, true, None)));
+    /// }
+    ///
+    /// let staging = Action::SetEnv { key: "API_URL".into(), value: "https://staging".into() };
+    /// manager.set_action(&MenuId::new("staging"), Some(staging));
+    /// manager.set_action(&MenuId::new("report"), Some(Action::Custom("report".into())));
+    ///
+    /// // In the menu event handler
+    /// assert_eq!(manager.run_action(&MenuId::new("staging")).unwrap().unwrap(), MenuAction::Done);
+    /// match manager.run_action(&MenuId::new("report")) {
+    ///     Some(Ok(MenuAction::Custom(name))) => assert_eq!(name, "report"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn set_action(&mut self, menu_id: &MenuId, action: Option<Action>) -> bool {
+        if !self.id_to_menu.contains_key(menu_id) {
+            return false;
+        }
+
+        match action {
+            Some(action) => self.actions.insert(menu_id.clone(), action),
+            None => self.actions.shift_remove(menu_id),
+        };
+        true
+    }
+
+    pub fn action(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&Action> {
+        self.actions.get(&MenuKey::new(menu_id))
+    }
+
+    /// Sets the accessible description of a menu control, `None` removes it.
+    ///
+    /// It describes what the menu control does beyond its label, e.g. for an icon-only or
+    /// abbreviated label. It is part of [`MenuManager::state`] and the `get_state` command of
+    /// an [`IpcServer`](crate::IpcServer), so the menu contents can be audited for screen readers.
+    ///
+    /// Returns `false` if the menu control is not registered.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows / macOS / Linux:** `muda` has no accessibility property for menu items,
+    ///   so screen readers only announce the label for now.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let vpn = MenuItem::with_id("vpn", "VPN ●", true, None);
+    /// manager.insert(MenuControl::MenuItem(vpn));
+    ///
+    /// manager.set_accessible_description(&MenuId::new("vpn"), Some("VPN, connected".into()));
+    /// assert_eq!(manager.accessible_description("vpn"), Some("VPN, connected"));
+    /// assert_eq!(manager.state().get("vpn").unwrap().description(), Some("VPN, connected"));
+    /// ```
+    pub fn set_accessible_description(
+        &mut self,
+        menu_id: &MenuId,
+        description: Option<String>,
+    ) -> bool {
+        if !self.id_to_menu.contains_key(menu_id) {
+            return false;
+        }
+
+        match description {
+            Some(description) => self.descriptions.insert(menu_id.clone(), description),
+            None => self.descriptions.shift_remove(menu_id),
+        };
+        true
+    }
+
+    pub fn accessible_description(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&str> {
+        self.descriptions
+            .get(&MenuKey::new(menu_id))
+            .map(String::as_str)
+    }
+
+    /// Runs the action of a clicked menu control, `None` if it has no action.
+    ///
+    /// Built-in actions run here, a [`Action::Custom`] action is returned as
+    /// [`MenuAction::Custom`] for the application. With the `dialogs` feature, the
+    /// [`Confirmation`] of the menu control is asked first.
+    pub fn run_action(&mut self, menu_id: &MenuId) -> Option<Result<MenuAction, Error>> {
+        let action = self.actions.get(menu_id)?;
+        trace_event!(debug, menu_id = ?menu_id, action = ?action, "run menu action");
+
+        #[cfg(feature = "dialogs")]
+        if let Some(confirmation) = self.confirmations.get(menu_id) {
+            match confirmation.ask() {
+                Ok(true) => {}
+                Ok(false) => return Some(Ok(MenuAction::Cancelled)),
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+
+        let result = action.run(&mut self.action_context);
+        let spent = match &result {
+            #[cfg(feature = "dialogs")]
+            Ok(MenuAction::Cancelled) => false,
+            Ok(_) => true,
+            Err(_) => false,
+        };
+        if spent {
+            self.spend_oneshot(menu_id);
+        }
+        Some(result)
+    }
+
+    /// Asks a yes/no question before the action of a menu control runs, `None` removes it
+    /// (**requires the `dialogs` feature**).
+    ///
+    /// [`MenuManager::run_action`] returns [`MenuAction::Cancelled`] if the question is
+    /// not confirmed, so destructive actions only run once confirmed. Returns `false` if
+    /// the menu control is not registered.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{Action, Confirmation, MenuControl, MenuManager, Platform};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("quit", "Quit", true, None)));
+    ///
+    /// let quit = MenuId::new("quit");
+    /// manager.set_action(&quit, Some(Action::Custom("quit".into())));
+    /// manager.set_confirmation(
+    ///     &quit,
+    ///     Some(Confirmation::new("Really quit?").platforms(&[Platform::Windows, Platform::Linux])),
+    /// );
+    /// ```
+    #[cfg(feature = "dialogs")]
+    pub fn set_confirmation(
+        &mut self,
+        menu_id: &MenuId,
+        confirmation: Option<Confirmation>,
+    ) -> bool {
+        if !self.id_to_menu.contains_key(menu_id) {
+            return false;
+        }
+
+        match confirmation {
+            Some(confirmation) => self.confirmations.insert(menu_id.clone(), confirmation),
+            None => self.confirmations.shift_remove(menu_id),
+        };
+        true
+    }
+
+    #[cfg(feature = "dialogs")]
+    pub fn confirmation(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&Confirmation> {
+        self.confirmations.get(&MenuKey::new(menu_id))
+    }
+
+    /// Registers the provider of [`ClipboardText::Provider`] texts (**requires the `clipboard` feature**).
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{Action, ClipboardText, MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let copy_ip = MenuItem::with_id("copy_ip", "Copy Local IP", true, None);
+    /// manager.insert(MenuControl::MenuItem(copy_ip));
+    ///
+    /// manager.set_clipboard_provider("local_ip", || "192.168.1.20".to_owned());
+    /// let action = Action::CopyToClipboard(ClipboardText::Provider("local_ip".into()));
+    /// manager.set_action(&MenuId::new("copy_ip"), Some(action));
+    /// ```
+    #[cfg(feature = "clipboard")]
+    pub fn set_clipboard_provider(&mut self, name: &str, provider: impl Fn() -> String + 'static) {
+        self.action_context
+            .clipboard_providers
+            .insert(name.to_owned(), Rc::new(provider));
+    }
+
+    /// Marks a menu control as a quick action, returns `false` if it is not registered.
+    ///
+    /// Quick actions are the single source of truth for the shortcuts shown outside the
+    /// tray menu, see [`MenuManager::quick_actions`].
+    pub fn set_quick_action(&mut self, menu_id: &MenuId, quick_action: bool) -> bool {
+        if !self.id_to_menu.contains_key(menu_id) {
+            return false;
+        }
+
+        if quick_action {
+            self.quick_actions.insert(menu_id.clone());
+        } else {
+            self.quick_actions.shift_remove(menu_id);
+        }
+        true
+    }
+
+    pub fn is_quick_action(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> bool {
+        self.quick_actions.contains(&MenuKey::new(menu_id))
+    }
+
+    /// Gets the current label and enabled state of the quick actions, in marking order.
+    ///
+    /// Export them to a taskbar jump list or a dock menu, and export them again when a
+    /// [`ChangeEvent`] of a quick action is observed, so both surfaces stay in sync.
+    ///
+    /// ## Platform-specific:
+    ///
+    /// - **Windows:** `JumpList` exports them to the taskbar jump list (**requires the
+    ///   `jump-list` feature**).
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager, QuickAction};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("new", "&New Note", true, None)));
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("quit", "&Quit", true, None)));
+    /// manager.set_quick_action(&MenuId::new("new"), true);
+    ///
+    /// manager.set_busy(&MenuId::new("new"), true);
+    /// assert_eq!(
+    ///     manager.quick_actions(),
+    ///     [QuickAction {
+    ///         menu_id: MenuId::new("new"),
+    ///         text: "New Note …".to_owned(),
+    ///         enabled: false,
+    ///     }]
+    /// );
+    /// ```
+    pub fn quick_actions(&self) -> Vec<QuickAction> {
+        self.quick_actions
+            .iter()
+            .filter(|menu_id| !self.hidden.contains_key(*menu_id))
+            .filter_map(|menu_id| {
+                let menu = self.id_to_menu.get(menu_id)?;
+                Some(QuickAction {
+                    menu_id: menu_id.clone(),
+                    text: mnemonic::strip_mnemonic(&menu.text()),
+                    enabled: menu.is_enabled(),
+                })
+            })
+            .collect()
+    }
+
+    /// Updates the menu state like [`MenuManager::update`], and returns a [`PendingChange`]
+    /// to commit or roll back once the action of the click is confirmed.
+    ///
+    /// With `disable`, the check menus of the clicked group stay disabled while pending.
+    /// Returns `None` if the clicked menu control is not a check menu.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// for (id, checked) in [("eu", true), ("us", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "region")));
+    /// }
+    ///
+    /// // The platform checks a clicked radio
+    /// let us = MenuId::new("us");
+    /// manager.get_menu_item_from_id(&us).unwrap().set_checked(true);
+    /// let pending = manager.update_pending(&us, true, |_| {}).unwrap();
+    /// assert!(!manager.get_menu_item_from_id(&us).unwrap().is_enabled());
+    ///
+    /// // The backend refused to switch the server region
+    /// pending.rollback(&mut manager);
+    /// assert_eq!(manager.selected_radio(&"region").unwrap().id(), "eu");
+    /// assert!(manager.get_menu_item_from_id(&us).unwrap().is_enabled());
+    /// ```
+    pub fn update_pending(
+        &mut self,
+        menu_id: &MenuId,
+        disable: bool,
+        callback: impl Fn(Option<&MenuControl<G>>),
+    ) -> Option<PendingChange> {
+        let menu_ids: Vec<MenuId> = match self.group_of(menu_id) {
+            Some(group) => self
+                .grouped_check_items
+                .get(group)
+                .into_iter()
+                .flat_map(|check_menus| check_menus.keys())
+                .map(|menu_id| menu_id.as_ref().clone())
+                .collect(),
+            None => vec![menu_id.clone()],
+        };
+
+        let checked: Vec<(MenuId, bool)> = menu_ids
+            .iter()
+            .filter_map(|id| {
+                let menu = self.id_to_menu.get(id)?;
+                let checked = menu.as_check_menu()?.is_checked();
+                // The platform already toggled a clicked check menu
+                let toggled = id == menu_id && menu.platform_check_menu().is_some();
+                Some((id.clone(), checked != toggled))
+            })
+            .collect();
+
+        self.update(menu_id, callback);
+        if checked.is_empty() {
+            return None;
+        }
+
+        let enabled = if disable {
+            let enabled: Vec<(MenuId, bool)> = checked
+                .iter()
+                .filter_map(|(id, _)| {
+                    Some((
+                        id.clone(),
+                        self.is_item_enabled(id, self.id_to_menu.get(id)?),
+                    ))
+                })
+                .collect();
+            self.batch(|batch| {
+                for (id, _) in &enabled {
+                    batch.set_enabled(id, false);
+                }
+            });
+            enabled
+        } else {
+            Vec::new()
+        };
+
+        Some(PendingChange::new(menu_id.clone(), checked, enabled))
+    }
+
+    /// Unchecks the other radios of a clicked radio, or checks the default radio menu
+    /// if the click unchecked it. Returns the menu control to callback.
+    fn settle_clicked_radio(&self, menu_id: &MenuId) -> Option<&MenuControl<G>> {
+        let menu_control = self.id_to_menu.get(menu_id);
+
+        let Some(CheckRef::Radio(check_menu, _, group)) =
+            menu_control.and_then(|menu| menu.check_ref())
+        else {
+            return menu_control;
+        };
+
+        let Some(check_menus) = self.get_check_items_from_grouped(group) else {
+            return menu_control;
+        };
+
+        let policy = self.radio_policy(group);
+        let (is_checked_menu_id, is_checked_menu) = if check_menu.is_checked() {
+            (check_menu.id(), menu_control)
+        } else if policy == RadioPolicy::AllowNone {
+            return menu_control;
+        } else if policy == RadioPolicy::RevertClick {
+            self.observe_item(menu_id);
+            check_menu.set_checked(true);
+            (check_menu.id(), menu_control)
+        } else {
+            let Some(default_menu_id) = self.default_radio_of(group) else {
+                return menu_control;
+            };
+
+            let default_menu = self.get_menu_item_from_id(default_menu_id);
+            let Some(menu) = default_menu.and_then(|menu| menu.as_check_menu()) else {
+                return menu_control;
+            };
+
+            trace_event!(
+                debug,
+                default_id = ?default_menu_id,
+                "radio group unchecked, falling back to the default radio",
+            );
+            self.observe_item(default_menu_id);
+            menu.set_checked(true);
+            (default_menu_id, default_menu)
+        };
+
+        check_menus
+            .iter()
+            .filter(|(menu_id, _)| menu_id.as_ref().ne(is_checked_menu_id))
+            .for_each(|(menu_id, check_menu)| {
+                self.observe_item(menu_id);
+                check_menu.set_checked(false);
+            });
+
+        is_checked_menu
+    }
+
+    /// Shows the icons matching the checked state of checkable icon menus.
+    fn refresh_check_icons(&self) {
+        self.id_to_menu.values().for_each(|menu| {
+            if let MenuControl::CheckIconMenu(check_icon_kind) = menu {
+                check_icon_kind.item().sync_icon();
+            }
+        });
+    }
+
+    /// Cross-references the live menu tree against the registered menu controls.
+    ///
+    /// Reports live menu items that were never registered, and registered menu controls
+    /// that were removed from the menu (or never appended) but are still kept alive.
+    pub fn audit(&self, menu: &Menu) -> AuditReport {
+        let mut live_ids = FxIndexSet::default();
+        audit::collect_live_ids(menu.items(), &mut live_ids);
+
+        let unregistered = live_ids
+            .iter()
+            .filter(|menu_id| !self.id_to_menu.contains_key(*menu_id))
+            .cloned()
+            .collect();
+        let detached = self
+            .id_to_menu
+            .keys()
+            .filter(|menu_id| !live_ids.contains(menu_id.as_ref()))
+            .map(|menu_id| menu_id.as_ref().clone())
+            .collect();
+
+        AuditReport::new(unregistered, detached)
+    }
+
+    /// Finds the enabled menu controls sharing a label in the same live parent menu.
+    ///
+    /// Returns each set of duplicates in registration order. Only menu controls inserted with
+    /// [`MenuManager::insert_into`] have a parent, mnemonic markers are ignored.
+    pub fn duplicate_labels(&self) -> Vec<Vec<MenuId>> {
+        let mut labels: FxIndexMap<(&MenuId, String), Vec<MenuId>> = FxIndexMap::default();
+
+        for (menu_id, menu) in &self.id_to_menu {
+            let Some(parent_id) = self.id_to_parent.get(menu_id.as_ref()) else {
+                continue;
+            };
+
+            if menu.is_enabled() {
+                labels
+                    .entry((parent_id, mnemonic::strip_mnemonic(&menu.text())))
+                    .or_default()
+                    .push(menu_id.as_ref().clone());
+            }
+        }
+
+        labels
+            .into_values()
+            .filter(|menu_ids| menu_ids.len() > 1)
+            .collect()
+    }
+
+    /// Suffixes the duplicate labels of [`MenuManager::duplicate_labels`].
+    ///
+    /// The first menu control keeps its label, the next ones become `"Device (2)"`,
+    /// `"Device (3)"`, and so on. Returns the number of renamed menu controls.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{MenuId, MenuItem, Submenu};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert_parent(MenuParent::Submenu(Submenu::with_id("devices", "Devices", true)));
+    /// for id in ["usb0", "usb1"] {
+    ///     let item = MenuItem::with_id(id, "Headset", true, None);
+    ///     manager.insert_into(&MenuId::new("devices"), MenuControl::MenuItem(item), None).unwrap();
+    /// }
+    ///
+    /// assert_eq!(manager.duplicate_labels(), [[MenuId::new("usb0"), MenuId::new("usb1")]]);
+    /// assert_eq!(manager.disambiguate_labels(), 1);
+    /// assert_eq!(manager.get_menu_item_from_id(&MenuId::new("usb1")).unwrap().text(), "Headset (2)");
+    /// ```
+    pub fn disambiguate_labels(&mut self) -> usize {
+        let mut renamed = 0;
+
+        for menu_ids in self.duplicate_labels() {
+            for (index, menu_id) in menu_ids.iter().enumerate().skip(1) {
+                if let Some(menu) = self.id_to_menu.get(menu_id) {
+                    let text = format!("{} ({})", self.item_text(menu_id, menu), index + 1);
+                    self.set_item_text(menu_id, menu, &text);
+                    renamed += 1;
+                }
+            }
+        }
+
+        renamed
+    }
+
+    /// Rebuilds a menu with new containers, and returns the new menu.
+    ///
+    /// The menu items are moved into a new `Menu` and new `Submenu`s with the same IDs,
+    /// which recreates the platform menus from the current state, e.g. when some Linux
+    /// status notifier hosts no longer reflect in-place changes. Registered parent menus
+    /// are replaced by their rebuilt container. Set the new menu on the tray icon, or use
+    /// [`TrayController::rebuild_menu`].
+    pub fn rebuild_menu(&mut self, menu: &Menu) -> Result<Menu, Error> {
+        let rebuilt = Menu::with_id(menu.id().clone());
+        for item in menu.items() {
+            let rebuilt_item = self.rebuild_item(&item)?;
+            rebuilt.append(as_is_menu_item(&rebuilt_item))?;
+            menu.remove(as_is_menu_item(&item))?;
+        }
+
+        if self.parents.contains_key(rebuilt.id()) {
+            self.insert_parent(MenuParent::Menu(rebuilt.clone()));
+        }
+
+        Ok(rebuilt)
+    }
+
+    /// Rebuilds the container of a submenu, other menu items are kept.
+    fn rebuild_item(&mut self, item: &MenuItemKind) -> Result<MenuItemKind, Error> {
+        let MenuItemKind::Submenu(submenu) = item else {
+            return Ok(item.clone());
+        };
+
+        let rebuilt = Submenu::with_id(submenu.id().clone(), submenu.text(), submenu.is_enabled());
+        for child in submenu.items() {
+            let rebuilt_child = self.rebuild_item(&child)?;
+            rebuilt.append(as_is_menu_item(&rebuilt_child))?;
+            submenu.remove(as_is_menu_item(&child))?;
+        }
+
+        if self.parents.contains_key(rebuilt.id()) {
+            self.insert_parent(MenuParent::Submenu(rebuilt.clone()));
+        }
+
+        Ok(MenuItemKind::Submenu(rebuilt))
+    }
+
+    /// Starts or stops counting the clicks of the menu controls, see [`ClickStats`].
+    ///
+    /// Counting is off by default, stopping it drops the collected statistics.
+    pub fn set_stats_enabled(&mut self, enabled: bool) {
+        match (enabled, self.stats.is_some()) {
+            (true, false) => self.stats = Some(ClickStats::default()),
+            (false, true) => self.stats = None,
+            _ => {}
+        }
+    }
+
+    /// Gets the click statistics, `None` if counting is off.
+    pub fn stats(&self) -> Option<&ClickStats> {
+        self.stats.as_ref()
+    }
+
+    /// Gets the click statistics to clear them, `None` if counting is off.
+    pub fn stats_mut(&mut self) -> Option<&mut ClickStats> {
+        self.stats.as_mut()
+    }
+
+    /// Verifies the consistency of the groups, e.g. after inserting check menus by hand.
+    ///
+    /// Checks that each radio group has at most one checked radio, that every grouped
+    /// check menu is registered, and that every default radio menu ID is a radio of the
+    /// same group, including the group defaults. With `debug_assertions`, [`MenuManager::update`]
+    /// panics if a group is corrupted, and reports the default radio menu IDs that don't
+    /// resolve (yet) to the error handler.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, InvariantViolation, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// for id in ["light", "dark"] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, true, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+    /// }
+    ///
+    /// assert_eq!(
+    ///     manager.check_invariants(),
+    ///     Err(vec![InvariantViolation::MultipleCheckedRadios {
+    ///         group: "theme",
+    ///         checked: vec![MenuId::new("light"), MenuId::new("dark")],
+    ///     }])
+    /// );
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), Vec<InvariantViolation<G>>> {
+        let mut violations = Vec::new();
+
+        for (group, check_menus) in &self.grouped_check_items {
+            let mut checked_radios = Vec::new();
+
+            for (menu_id, check_menu) in check_menus {
+                match self
+                    .id_to_menu
+                    .get(menu_id)
+                    .and_then(|menu| menu.check_ref())
+                {
+                    None => violations.push(InvariantViolation::UnregisteredGroupedItem {
+                        group: group.clone(),
+                        menu_id: menu_id.as_ref().clone(),
+                    }),
+                    Some(CheckRef::Radio(..)) if check_menu.is_checked() => {
+                        checked_radios.push(menu_id.as_ref().clone());
+                    }
+                    _ => {}
+                }
+            }
+
+            if checked_radios.len() > 1 {
+                trace_event!(
+                    warn,
+                    checked = ?checked_radios,
+                    "radio group has several checked radios",
+                );
+                violations.push(InvariantViolation::MultipleCheckedRadios {
+                    group: group.clone(),
+                    checked: checked_radios,
+                });
+            }
+        }
+
+        for menu in self.id_to_menu.values() {
+            let Some(CheckRef::Radio(_, Some(default_id), group)) = menu.check_ref() else {
+                continue;
+            };
+
+            let default_group = match self
+                .id_to_menu
+                .get(default_id.as_ref())
+                .and_then(|default| default.check_ref())
+            {
+                Some(CheckRef::Radio(_, _, default_group)) => Some(default_group),
+                _ => None,
+            };
+
+            if default_group != Some(group) {
+                violations.push(InvariantViolation::UnresolvedDefault {
+                    menu_id: menu.id().clone(),
+                    default_id: default_id.as_ref().clone(),
+                });
+            }
+        }
+
+        // The group default of a group without check menus waits for them
+        for (group, default_id) in &self.group_defaults {
+            if self.grouped_check_items.contains_key(group)
+                && self.default_radio_of(group) != Some(default_id)
+            {
+                violations.push(InvariantViolation::UnresolvedGroupDefault {
+                    group: group.clone(),
+                    default_id: default_id.clone(),
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Asserts that no group is corrupted, and reports the unresolved default radio menu IDs.
+    #[cfg(debug_assertions)]
+    fn debug_check_invariants(&self) {
+        let Err(violations) = self.check_invariants() else {
+            return;
+        };
+
+        let mut corrupted = Vec::new();
+        for violation in violations {
+            match violation {
+                InvariantViolation::UnresolvedDefault { default_id, .. }
+                | InvariantViolation::UnresolvedGroupDefault { default_id, .. } => {
+                    self.report(Err(Error::UnresolvedDefault(default_id)));
+                }
+                violation => corrupted.push(violation.to_string()),
+            }
+        }
+
+        debug_assert!(
+            corrupted.is_empty(),
+            "menu manager invariants broken: {}",
+            corrupted.join("; ")
+        );
+    }
+
+    /// Takes a snapshot of the text, enabled and checked state, and the accessible description
+    /// of all menu controls.
+    ///
+    /// Compare two snapshots with [`MenuState::diff`].
+    pub fn state(&self) -> MenuState {
+        MenuState::new(
+            self.id_to_menu
+                .iter()
+                .map(|(menu_id, menu)| (menu_id.clone(), self.item_state(menu_id, menu)))
+                .collect(),
+        )
+    }
+
+    fn item_state(&self, menu_id: &MenuId, menu: &MenuControl<G>) -> MenuItemState {
+        let checked = menu
+            .as_check_menu()
+            .map(|check_menu| check_menu.is_checked());
+        MenuItemState::new(
+            self.item_text(menu_id, menu),
+            self.is_item_enabled(menu_id, menu),
+            checked,
+        )
+        .with_description(self.accessible_description(menu_id))
+    }
+
+    /// Takes a snapshot of the enabled and checked state of the check menus of a group.
+    ///
+    /// Restore it with [`MenuManager::restore_group`], e.g. when the action of a click failed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// for (id, checked) in [("eu", true), ("us", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "region")));
+    /// }
+    ///
+    /// let snapshot = manager.snapshot_group(&"region");
+    /// manager.batch(|batch| {
+    ///     batch.set_checked(&MenuId::new("us"), true);
+    /// });
+    ///
+    /// // Switching the server region failed
+    /// manager.restore_group(&snapshot);
+    /// assert_eq!(manager.selected_radio(&"region").unwrap().id(), "eu");
+    /// ```
+    pub fn snapshot_group(&self, group_id: &G) -> GroupSnapshot<G> {
+        let items = self
+            .grouped_check_items
+            .get(group_id)
+            .into_iter()
+            .flat_map(|check_menus| check_menus.keys())
+            .filter_map(|menu_id| self.id_to_menu.get_key_value(menu_id))
+            .map(|(menu_id, menu)| (menu_id.as_ref().clone(), self.item_state(menu_id, menu)))
+            .collect();
+
+        GroupSnapshot::new(group_id.clone(), items)
+    }
+
+    /// Restores the enabled and checked state of a group snapshot, other groups are untouched.
+    ///
+    /// The changes are applied as one [`MenuManager::batch`], check menus removed since the
+    /// snapshot are skipped. Returns the menu IDs whose state changed.
+    pub fn restore_group(&mut self, snapshot: &GroupSnapshot<G>) -> Vec<MenuId> {
+        self.with_origin(ChangeOrigin::Restore, |manager| {
+            manager.batch(|batch| {
+                for (menu_id, item) in snapshot.iter() {
+                    if let Some(checked) = item.is_checked() {
+                        batch.set_checked(menu_id, checked);
+                    }
+                    batch.set_enabled(menu_id, item.is_enabled());
+                }
+            })
+        })
+    }
+
+    /// Subscribes to the changes made through the menu manager.
+    ///
+    /// Every mutation, e.g. [`MenuManager::insert`], [`MenuManager::update`] or
+    /// [`MenuManager::set_var`], sends the changes it made once it is done. Only the menu
+    /// controls a mutation changed are compared, changes made directly on the menu items
+    /// aren't sent. Subscribers are dropped with their receiver, and aren't cloned with the
+    /// menu manager.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{ChangeEvent, ChangeOrigin, CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<&str>::new();
+    /// let events = manager.subscribe();
+    ///
+    /// for (id, checked) in [("light", true), ("dark", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+    /// }
+    /// assert_eq!(events.try_iter().count(), 2);
+    ///
+    /// let changed = manager.batch(|batch| {
+    ///     batch.set_checked(&MenuId::new("dark"), true);
+    /// });
+    /// assert_eq!(changed.len(), 2);
+    /// assert!(events.try_iter().any(|event| {
+    ///     event == ChangeEvent::RadioSelected {
+    ///         group: "theme",
+    ///         menu_id: MenuId::new("dark"),
+    ///         origin: ChangeOrigin::Api,
+    ///     }
+    /// }));
+    /// ```
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<ChangeEvent<G>> {
+        self.observers.subscribe()
+    }
+
+    /// Sets which changes are sent to the subscribers, e.g. [`Notify::OnlyUser`] so that
+    /// mirroring external changes into the menu doesn't trigger the change handlers again.
+    pub fn set_notify(&mut self, notify: Notify) {
+        self.notify = notify;
+    }
+
+    pub fn notify(&self) -> Notify {
+        self.notify
+    }
+
+    /// Runs changes with an origin, e.g. [`ChangeOrigin::HotKey`] around [`MenuManager::update`]
+    /// for a keyboard shortcut.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{ChangeEvent, ChangeOrigin, CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let mute = CheckMenuItem::with_id("mute", "Mute", true, false, None);
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(mute))));
+    /// let events = manager.subscribe();
+    ///
+    /// // The accelerator of "Mute" was pressed
+    /// manager.with_origin(ChangeOrigin::HotKey, |manager| {
+    ///     manager.batch(|batch| {
+    ///         batch.toggle(&MenuId::new("mute"));
+    ///     });
+    /// });
+    ///
+    /// let event = events.try_recv().unwrap();
+    /// assert_eq!(event.origin(), ChangeOrigin::HotKey);
+    /// ```
+    pub fn with_origin<R>(
+        &mut self,
+        origin: ChangeOrigin,
+        changes: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let previous = self.origin.replace(origin);
+        let result = changes(self);
+        self.origin = previous;
+        result
+    }
+
+    /// Runs changes without sending them to the subscribers, e.g. restoring a saved state.
+    pub fn silently<R>(&mut self, changes: impl FnOnce(&mut Self) -> R) -> R {
+        let notify = std::mem::replace(&mut self.notify, Notify::Never);
+        let result = changes(self);
+        self.notify = notify;
+        result
+    }
+
+    /// Sets the checked state of a check menu without notifying the subscribers.
+    ///
+    /// Radio groups and dependencies are kept consistent, as with [`MenuManager::batch`].
+    /// Returns `false` if there is no check menu.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let autostart = CheckMenuItem::with_id("autostart", "Autostart", true, false, None);
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(autostart))));
+    ///
+    /// let events = manager.subscribe();
+    /// assert!(manager.set_checked_silent(&MenuId::new("autostart"), true));
+    /// assert_eq!(events.try_iter().count(), 0);
+    /// ```
+    pub fn set_checked_silent(&mut self, menu_id: &MenuId, checked: bool) -> bool {
+        self.silently(|manager| {
+            let mut found = false;
+            manager.batch(|batch| found = batch.set_checked(menu_id, checked));
+            found
+        })
+    }
+
+    /// Starts a mutation, recording its changes if it is the outermost one and someone listens.
+    ///
+    /// Mutations record each menu control they change with [`MenuManager::observe_item`].
+    fn begin_observe(&self) {
+        self.begin_observe_as(ChangeOrigin::Api);
+    }
+
+    /// Starts a mutation, `origin` is used unless [`MenuManager::with_origin`] overrides it.
+    fn begin_observe_as(&self, origin: ChangeOrigin) {
+        let origin = self.origin.unwrap_or(origin);
+        let notify = match self.notify {
+            Notify::Always => true,
+            Notify::OnlyUser => origin.is_user(),
+            Notify::Never => false,
+        };
+
+        self.observers.enter(origin, notify);
+    }
+
+    /// Records the state of a menu control before it is changed, inserted or removed.
+    fn observe_item(&self, menu_id: &MenuId) {
+        self.observers.record(menu_id, || {
+            self.id_to_menu
+                .get(menu_id)
+                .map(|menu| self.item_state(menu_id, menu))
+        });
+    }
+
+    /// Ends a mutation started with [`MenuManager::begin_observe`], sending its changes.
+    fn end_observe(&self) {
+        let Some(recorded) = self.observers.leave() else {
+            return;
+        };
+
+        // Only the recorded menu controls are compared, in registration order
+        let mut recorded: Vec<_> = recorded.into_iter().collect();
+        recorded.sort_by_key(|(menu_id, _)| {
+            self.id_to_menu.get_index_of(menu_id).unwrap_or(usize::MAX)
+        });
+
+        let mut before = FxIndexMap::default();
+        let mut after = FxIndexMap::default();
+        for (menu_id, item) in recorded {
+            let menu_id = Rc::new(menu_id);
+            if let Some(menu) = self.id_to_menu.get(menu_id.as_ref()) {
+                after.insert(menu_id.clone(), self.item_state(&menu_id, menu));
+            }
+            if let Some(item) = item {
+                before.insert(menu_id, item);
+            }
+        }
+
+        let origin = self.observers.origin();
+        for change in MenuState::diff(&MenuState::new(before), &MenuState::new(after)) {
+            match change {
+                StateChange::Added(menu_id) => {
+                    self.observers
+                        .emit(ChangeEvent::ItemInserted { menu_id, origin });
+                }
+                StateChange::Removed(menu_id) => {
+                    self.observers
+                        .emit(ChangeEvent::ItemRemoved { menu_id, origin });
+                }
+                StateChange::CheckedChanged { menu_id, checked } => {
+                    self.observers.emit(ChangeEvent::CheckedChanged {
+                        menu_id: menu_id.clone(),
+                        checked,
+                        origin,
+                    });
+
+                    if checked
+                        && let Some(CheckRef::Radio(_, _, group)) = self
+                            .id_to_menu
+                            .get(&menu_id)
+                            .and_then(|menu| menu.check_ref())
+                    {
+                        self.observers.emit(ChangeEvent::RadioSelected {
+                            group: group.clone(),
+                            menu_id,
+                            origin,
+                        });
+                    }
+                }
+                StateChange::EnabledChanged { menu_id, enabled } => {
+                    self.observers.emit(ChangeEvent::EnabledChanged {
+                        menu_id,
+                        enabled,
+                        origin,
+                    });
+                }
+                StateChange::TextChanged { menu_id, after, .. } => {
+                    self.observers.emit(ChangeEvent::TextChanged {
+                        menu_id,
+                        text: after,
+                        origin,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Iterates over the menu controls in registration order.
+    ///
+    /// Menu controls are registered in menu order when built with [`MenuManager::insert_into`],
+    /// so dumps of the menu state are deterministic. Groups also keep their registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &MenuControl<G>> {
+        self.id_to_menu.values()
+    }
+
+    /// Finds the menu controls matching a predicate, in registration order.
+    pub fn find(&self, predicate: impl Fn(&MenuControl<G>) -> bool) -> Vec<&MenuControl<G>> {
+        self.id_to_menu
+            .values()
+            .filter(|menu| predicate(menu))
+            .collect()
+    }
+
+    /// Finds the menu controls whose text starts with a prefix, in registration order.
+    ///
+    /// The match ignores case and mnemonic markers, so `"lang"` finds `"&Language"`.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::MenuItem;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// for (id, text) in [("language", "&Language"), ("layout", "Layout")] {
+    ///     manager.insert(MenuControl::MenuItem(MenuItem::with_id(id, text, true, None)));
+    /// }
+    ///
+    /// let found = manager.find_by_text_prefix("lang");
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].id().as_ref(), "language");
+    /// ```
+    pub fn find_by_text_prefix(&self, prefix: &str) -> Vec<&MenuControl<G>> {
+        let prefix = prefix.to_lowercase();
+        self.find(|menu| {
+            mnemonic::strip_mnemonic(&menu.text())
+                .to_lowercase()
+                .starts_with(&prefix)
+        })
+    }
+
+    /// Gets a menu control from the menu manager based on the provided menu ID.
+    ///
+    /// Like the other lookups, it takes a `&MenuId` or the ID string, e.g. `"dark_mode"`.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::MenuItem;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("about", "About", true, None)));
+    ///
+    /// assert_eq!(manager.get_menu_item_from_id("about").unwrap().text(), "About");
+    /// ```
+    pub fn get_menu_item_from_id(
+        &self,
+        menu_id: &(impl AsRef<str> + ?Sized),
+    ) -> Option<&MenuControl<G>> {
+        self.id_to_menu.get(&MenuKey::new(menu_id))
+    }
+
+    /// Gets a menu control from the menu manager based on the provided handle.
+    ///
+    /// Unlike [`MenuManager::get_menu_item_from_id`], no `MenuId` is hashed, which suits
+    /// periodic updates. Returns `None` once the menu control was removed.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::MenuItem;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let speed = manager.insert(MenuControl::MenuItem(MenuItem::with_id("speed", "0 KB/s", true, None)));
+    ///
+    /// if let Some(menu) = manager.get(speed) {
+    ///     menu.set_text("12 KB/s");
+    /// }
+    ///
+    /// manager.remove(&manager.id_of(speed).unwrap().clone()).unwrap();
+    /// assert!(manager.get(speed).is_none());
+    /// ```
+    pub fn get(&self, handle: MenuHandle) -> Option<&MenuControl<G>> {
+        self.handles.get(handle).map(|(_, menu)| menu)
+    }
+
+    /// Gets the menu ID of a handle, to use it with the `MenuId` based methods.
+    pub fn id_of(&self, handle: MenuHandle) -> Option<&MenuId> {
+        self.handles
+            .get(handle)
+            .map(|(menu_id, _)| menu_id.as_ref())
+    }
+
+    /// Gets the kind of a menu control, e.g. to route menu events without calling
+    /// [`MenuManager::update`] for plain menu items.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{ControlKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuEvent, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("quit", "Quit", true, None)));
+    ///
+    /// let event = MenuEvent { id: "quit".into() };
+    /// match manager.kind_of(&event.id) {
+    ///     Some(ControlKind::MenuItem) => println!("clicked {:?}", event.id),
+    ///     // Check menus need their group logic
+    ///     Some(_) => manager.update(&event.id, |_| {}),
+    ///     None => {}
+    /// }
+    /// ```
+    pub fn kind_of(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<ControlKind> {
+        self.get_menu_item_from_id(menu_id).map(MenuControl::kind)
+    }
+
+    /// Gets the handle of a menu control, e.g. to keep the handle of a menu event.
+    pub fn handle_of(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<MenuHandle> {
+        self.handles.handle_of(MenuKey::new(menu_id))
+    }
+
+    /// Gets grouped check menu items from the menu manager based on the provided menu group id.
+    pub fn get_check_items_from_grouped(
+        &self,
+        group_id: &G,
+    ) -> Option<&FxIndexMap<Rc<MenuId>, Rc<CheckMenuItem>>> {
+        self.grouped_check_items.get(group_id)
+    }
+
+    /// Gets the group of a checkbox or radio menu control.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// let dark = Rc::new(CheckMenuItem::with_id("dark", "Dark", true, false, None));
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(dark, None, "theme")));
+    ///
+    /// assert_eq!(manager.group_of(&MenuId::new("dark")), Some(&"theme"));
+    /// ```
+    pub fn group_of(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&G> {
+        self.id_to_group.get(&MenuKey::new(menu_id))
+    }
+
+    /// Gets the number of check menus in a group, `0` once its last check menu was removed.
+    pub fn group_len(&self, group_id: &G) -> usize {
+        self.grouped_check_items
+            .get(group_id)
+            .map_or(0, |check_menus| check_menus.len())
+    }
+
+    /// Iterates over the groups that have check menus, in registration order.
+    ///
+    /// Groups are dropped when their last check menu is removed, their settings
+    /// (e.g. the radio policy or the group default) are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// let dark = Rc::new(CheckMenuItem::with_id("dark", "Dark", true, false, None));
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(dark, None, "theme")));
+    /// assert_eq!(manager.groups().collect::<Vec<_>>(), [&"theme"]);
+    ///
+    /// manager.remove(&MenuId::new("dark")).unwrap();
+    /// assert_eq!(manager.group_len(&"theme"), 0);
+    /// assert_eq!(manager.groups().count(), 0);
+    /// ```
+    pub fn groups(&self) -> impl Iterator<Item = &G> {
+        self.grouped_check_items.keys()
+    }
+
+    /// Gets the checked radio of a radio group.
+    pub fn selected_radio(&self, group_id: &G) -> Option<&MenuControl<G>> {
+        self.grouped_check_items
+            .get(group_id)?
+            .iter()
+            .find(|(_, check_menu)| check_menu.is_checked())
+            .and_then(|(menu_id, _)| self.id_to_menu.get(menu_id))
+    }
+
+    /// Gets the parent menu holding the checked radio of a radio group.
+    ///
+    /// Useful when a radio group spans several submenus, e.g. to tell which submenu to
+    /// highlight. Only menu controls inserted with [`MenuManager::insert_into`] have a parent.
+    pub fn selected_parent(&self, group_id: &G) -> Option<&MenuParent> {
+        self.selected_radio(group_id)
+            .and_then(|menu| self.get_parent(menu.id()))
+    }
+
+    /// Gets the parent menus holding the menu controls of a group, without duplicates.
+    pub fn group_menu_parents(&self, group_id: &G) -> Vec<&MenuParent> {
+        let mut parents: Vec<&MenuParent> = Vec::new();
+
+        self.grouped_check_items
+            .get(group_id)
+            .into_iter()
+            .flat_map(|check_menus| check_menus.keys())
+            .filter_map(|menu_id| self.get_parent(menu_id.as_ref()))
+            .for_each(|parent| {
+                if !parents.iter().any(|p| p.id() == parent.id()) {
+                    parents.push(parent);
+                }
+            });
+
+        parents
+    }
+
+    /// Sorts the check menus of a group, in the menu manager and in their live parent menus.
+    ///
+    /// In a live parent menu, the check menus of the group are reordered among the positions
+    /// they already take, other items keep their position. Returns `false` if the group has
+    /// no check menus.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId, Submenu};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// manager.insert_parent(MenuParent::Submenu(Submenu::with_id("servers", "Servers", true)));
+    /// for name in ["Tokyo", "Berlin", "Oslo"] {
+    ///     let radio = Rc::new(CheckMenuItem::with_id(name, name, true, false, None));
+    ///     let radio = MenuControl::CheckMenu(CheckMenuKind::Radio(radio, None, "server"));
+    ///     manager.insert_into(&MenuId::new("servers"), radio, None).unwrap();
+    /// }
+    ///
+    /// manager.sort_group(&"server", |a, b| a.text().cmp(&b.text())).unwrap();
+    /// assert_eq!(manager.position_of(&MenuId::new("Berlin")), Some(0));
+    /// ```
+    pub fn sort_group(
+        &mut self,
+        group_id: &G,
+        mut compare: impl FnMut(&MenuControl<G>, &MenuControl<G>) -> Ordering,
+    ) -> Result<bool, Error> {
+        let Some(check_menus) = self.grouped_check_items.get_mut(group_id) else {
+            return Ok(false);
+        };
+
+        let id_to_menu = &self.id_to_menu;
+        check_menus.sort_by(|a, _, b, _| match (id_to_menu.get(a), id_to_menu.get(b)) {
+            (Some(a), Some(b)) => compare(a, b),
+            _ => Ordering::Equal,
+        });
+
+        let sorted_ids: Vec<Rc<MenuId>> = check_menus.keys().cloned().collect();
+        let parent_ids: FxIndexSet<MenuId> = sorted_ids
+            .iter()
+            .filter_map(|menu_id| self.id_to_parent.get(menu_id).cloned())
+            .collect();
+
+        for parent_id in parent_ids {
+            let Some(parent) = self.parents.get(&parent_id) else {
+                continue;
+            };
+
+            let children: Vec<&MenuControl<G>> = sorted_ids
+                .iter()
+                .filter(|menu_id| self.id_to_parent.get(*menu_id) == Some(&parent_id))
+                .filter_map(|menu_id| self.id_to_menu.get(menu_id))
+                .filter(|menu| parent.position_of(menu.id()).is_some())
+                .collect();
+            let mut positions: Vec<usize> = children
+                .iter()
+                .filter_map(|menu| parent.position_of(menu.id()))
+                .collect();
+            positions.sort_unstable();
+
+            for menu in &children {
+                parent.remove(menu.as_is_menu_item())?;
+            }
+            // Filling the lowest position first keeps the later positions valid
+            for (menu, position) in children.iter().zip(positions) {
+                parent.insert(menu.as_is_menu_item(), position)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Sorts the check menus of a group alphabetically by text, see [`MenuManager::sort_group`].
+    pub fn sort_group_by_text(&mut self, group_id: &G) -> Result<bool, Error> {
+        // Read each text once, not once per comparison
+        let texts: FxIndexMap<MenuId, String> = self
+            .grouped_check_items
+            .get(group_id)
+            .into_iter()
+            .flat_map(|check_menus| check_menus.values())
+            .map(|check_menu| {
+                let text = mnemonic::strip_mnemonic(&check_menu.text());
+                (check_menu.id().clone(), text)
+            })
+            .collect();
+
+        self.sort_group(group_id, |a, b| texts.get(a.id()).cmp(&texts.get(b.id())))
+    }
+
+    /// Defines a profile (e.g. "logged in" / "logged out") from the menu IDs that belong to it.
+    ///
+    /// Redefining an existing profile replaces its menu IDs.
+    pub fn define_profile<I>(&mut self, name: &str, menu_ids: I)
+    where
+        I: IntoIterator<Item = MenuId>,
+    {
+        self.profiles
+            .insert(name.to_owned(), menu_ids.into_iter().collect());
+
+        if self.active_profile.as_deref() == Some(name) {
+            self.activate_profile(name);
+        }
+    }
+
+    /// Activates a profile, returns `false` if the profile is not defined.
+    ///
+    /// Menu controls of the active profile are shown, and menu controls that only belong
+    /// to other profiles are detached from their live parent menu, see
+    /// [`MenuManager::set_visible`]. Menu controls without a parent (not inserted with
+    /// [`MenuManager::insert_into`]) are enabled or disabled instead. Menu controls that
+    /// belong to no profile are untouched. The menu controls stay registered, so their
+    /// checked state is kept between profiles.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{Menu, MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let tray_menu = Menu::new();
+    /// manager.insert_parent(MenuParent::Menu(tray_menu.clone()));
+    /// for (id, text) in [("login", "Log In"), ("sync", "Sync Now"), ("logout", "Log Out")] {
+    ///     let item = MenuItem::with_id(id, text, true, None);
+    ///     manager.insert_into(tray_menu.id(), MenuControl::MenuItem(item), None).unwrap();
+    /// }
+    ///
+    /// manager.define_profile("logged_out", [MenuId::new("login")]);
+    /// manager.define_profile("logged_in", [MenuId::new("sync"), MenuId::new("logout")]);
+    ///
+    /// manager.activate_profile("logged_out");
+    /// assert_eq!(tray_menu.items().len(), 1);
+    ///
+    /// manager.activate_profile("logged_in");
+    /// let ids: Vec<_> = tray_menu.items().iter().map(|item| item.id().clone()).collect();
+    /// assert_eq!(ids, [MenuId::new("sync"), MenuId::new("logout")]);
+    /// ```
+    pub fn activate_profile(&mut self, name: &str) -> bool {
+        let Some(active_ids) = self.profiles.get(name) else {
+            return false;
+        };
+
+        let profile_ids: FxIndexSet<&MenuId> = self.profiles.values().flatten().collect();
+        let mut hidden_ids = Vec::new();
+        let mut shown_ids = Vec::new();
+
+        self.begin_observe();
+        for (menu_id, menu) in &self.id_to_menu {
+            let menu_id = menu_id.as_ref();
+            if !profile_ids.contains(menu_id) {
+                continue;
+            }
+
+            let active = active_ids.contains(menu_id);
+            match (self.id_to_parent.contains_key(menu_id), active) {
+                (true, true) => shown_ids.push(menu_id.clone()),
+                (true, false) => hidden_ids.push(menu_id.clone()),
+                (false, _) => {
+                    self.observe_item(menu_id);
+                    self.set_item_enabled(menu_id, menu, active);
+                }
+            }
+        }
+        self.end_observe();
+
+        // Hidden first, then shown in registration order, so each shown menu control
+        // finds the sibling it followed
+        for (menu_id, visible) in hidden_ids
+            .into_iter()
+            .map(|menu_id| (menu_id, false))
+            .chain(shown_ids.into_iter().map(|menu_id| (menu_id, true)))
+        {
+            let result = self.set_visible(&menu_id, visible);
+            self.report(result);
+        }
+
+        self.active_profile = Some(name.to_owned());
+        true
+    }
+
+    /// Gets the name of the active profile.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// Sets a label template like `"Download: {speed}"` for a menu control, and renders it.
+    ///
+    /// Placeholders are replaced by the variables of [`MenuManager::set_var`].
+    pub fn set_label_template(&mut self, menu_id: &MenuId, template: &str) {
+        if let Some(menu) = self.id_to_menu.get(menu_id) {
+            self.begin_observe();
+            self.observe_item(menu_id);
+            let text = template::render_template(template, &self.template_vars);
+            self.set_item_text(menu_id, menu, &text);
+            self.end_observe();
+        }
+
+        self.label_templates
+            .insert(menu_id.clone(), template.to_owned());
+    }
+
+    /// Removes the label template of a menu control, the current text is kept.
+    pub fn remove_label_template(&mut self, menu_id: &MenuId) {
+        self.label_templates.shift_remove(menu_id);
+    }
+
+    /// Sets a template variable, and re-renders all labels referencing it.
+    pub fn set_var(&mut self, name: &str, value: impl Display) {
+        let value = value.to_string();
+        if self.template_vars.get(name) == Some(&value) {
+            return;
+        }
+
+        self.template_vars.insert(name.to_owned(), value);
+
+        self.begin_observe();
+        self.label_templates
+            .iter()
+            .filter(|(_, label_template)| template::references_var(label_template, name))
+            .for_each(|(menu_id, label_template)| {
+                if let Some(menu) = self.id_to_menu.get(menu_id) {
+                    self.observe_item(menu_id);
+                    let text = template::render_template(label_template, &self.template_vars);
+                    self.set_item_text(menu_id, menu, &text);
+                }
+            });
+        self.end_observe();
+    }
+
+    /// Gets a template variable.
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.template_vars.get(name).map(String::as_str)
+    }
+
+    /// Binds a "Select all" checkbox to a checkbox group.
+    ///
+    /// Checking the master checks all checkboxes of the group, unchecking it clears them.
+    /// When a checkbox of the group is toggled, the master is checked only if all of them are.
+    /// The master should be registered as a `CheckMenuKind::Separate` checkbox.
+    pub fn bind_master_checkbox(&mut self, master_id: &MenuId, group: G) {
+        self.master_checkboxes
+            .insert(master_id.clone(), group.clone());
+
+        self.begin_observe();
+        self.sync_master_checkbox(&group);
+        self.end_observe();
+    }
+
+    /// Removes the binding of a "Select all" checkbox.
+    pub fn unbind_master_checkbox(&mut self, master_id: &MenuId) {
+        self.master_checkboxes.shift_remove(master_id);
+    }
+
+    /// Gets the check state of a checkbox group.
+    pub fn group_check_state(&self, group_id: &G) -> Option<GroupCheckState> {
+        let check_menus = self.grouped_check_items.get(group_id)?;
+        let checked_count = check_menus
+            .values()
+            .filter(|check_menu| check_menu.is_checked())
+            .count();
+
+        Some(if checked_count == 0 {
+            GroupCheckState::Unchecked
+        } else if checked_count == check_menus.len() {
+            GroupCheckState::Checked
+        } else {
+            GroupCheckState::Indeterminate
+        })
+    }
+
+    fn sync_master_checkboxes(&self, menu_id: &MenuId) {
+        if let Some(group) = self.master_checkboxes.get(menu_id) {
+            let Some(checked) = self
+                .id_to_menu
+                .get(menu_id)
+                .and_then(|menu| menu.as_check_menu())
+                .map(|master| master.is_checked())
+            else {
+                return;
+            };
+
+            trace_event!(trace, master_id = ?menu_id, checked, "sync group from master checkbox");
+            if let Some(check_menus) = self.grouped_check_items.get(group) {
+                check_menus.iter().for_each(|(menu_id, check_menu)| {
+                    self.observe_item(menu_id);
+                    check_menu.set_checked(checked);
+                });
+            }
+        } else if let Some(CheckRef::CheckBox(_, group)) = self
+            .id_to_menu
+            .get(menu_id)
+            .and_then(|menu| menu.check_ref())
+        {
+            self.sync_master_checkbox(group);
+        }
+    }
+
+    fn sync_master_checkbox(&self, group: &G) {
+        let checked = self.group_check_state(group) == Some(GroupCheckState::Checked);
+
+        self.master_checkboxes
+            .iter()
+            .filter(|(_, master_group)| *master_group == group)
+            .filter_map(|(master_id, _)| self.id_to_menu.get(master_id))
+            .for_each(|master| {
+                self.observe_item(master.id());
+                trace_event!(
+                    trace,
+                    master_id = ?master.id(),
+                    checked,
+                    "sync master checkbox from group",
+                );
+                master.set_checked(checked);
+            });
+    }
+
+    /// Enables a menu control only while a check menu it depends on is checked.
+    ///
+    /// A menu control can depend on several check menus, it is enabled when all of them are
+    /// checked and enabled, so disabling cascades through chains of dependencies.
+    /// Dependencies are applied immediately and after every [`MenuManager::update`].
+    pub fn add_dependency(&mut self, dependent_id: &MenuId, depends_on_id: &MenuId) {
+        let depends_on = self.dependencies.entry(dependent_id.clone()).or_default();
+        if !depends_on.contains(depends_on_id) {
+            depends_on.push(depends_on_id.clone());
+        }
+
+        self.apply_dependencies();
+    }
+
+    /// Removes all dependencies of a menu control, its enabled state is kept.
+    pub fn remove_dependencies(&mut self, dependent_id: &MenuId) {
+        self.dependencies.shift_remove(dependent_id);
+    }
+
+    /// Enables or disables the dependent menu controls based on the check menus they depend on.
+    pub fn apply_dependencies(&self) {
+        self.begin_observe();
+        // Each pass settles at least one more level of a dependency chain
+        for _ in 0..=self.dependencies.len() {
+            let mut changed = false;
+
+            for (dependent_id, depends_on) in &self.dependencies {
+                let Some(dependent) = self.id_to_menu.get(dependent_id) else {
+                    continue;
+                };
+
+                let enabled = depends_on.iter().all(|depends_on_id| {
+                    self.id_to_menu.get(depends_on_id).is_some_and(|menu| {
+                        self.is_item_enabled(depends_on_id, menu)
+                            && menu
+                                .as_check_menu()
+                                .is_some_and(|check_menu| check_menu.is_checked())
+                    })
+                });
+
+                if self.is_item_enabled(dependent_id, dependent) != enabled {
+                    self.observe_item(dependent_id);
+                    self.set_item_enabled(dependent_id, dependent, enabled);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+        self.end_observe();
+    }
+
+    /// Registers a condition enabling a menu control, see [`MenuManager::refresh_conditions`].
+    pub fn enabled_if<C: 'static>(
+        &mut self,
+        menu_id: &MenuId,
+        condition: impl Fn(&C) -> bool + 'static,
+    ) {
+        self.conditions
+            .entry(menu_id.clone())
+            .or_default()
+            .enabled_if = Some(condition::condition(condition));
+    }
+
+    /// Registers a condition showing a menu control, see [`MenuManager::refresh_conditions`].
+    ///
+    /// Only menu controls inserted with [`MenuManager::insert_into`] can be hidden.
+    pub fn visible_if<C: 'static>(
+        &mut self,
+        menu_id: &MenuId,
+        condition: impl Fn(&C) -> bool + 'static,
+    ) {
+        self.conditions
+            .entry(menu_id.clone())
+            .or_default()
+            .visible_if = Some(condition::condition(condition));
+    }
+
+    /// Removes the conditions of a menu control, its enabled and visible states are kept.
+    pub fn remove_conditions(&mut self, menu_id: &MenuId) {
+        self.conditions.shift_remove(menu_id);
+    }
+
+    /// Evaluates the conditions registered for the context type `C`, e.g. before the menu is shown.
+    ///
+    /// Conditions registered for another context type are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager, MenuParent};
+    /// use tray_icon::menu::{Menu, MenuId, MenuItem};
+    ///
+    /// struct AppState {
+    ///     logged_in: bool,
+    /// }
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.insert_parent(MenuParent::Menu(Menu::with_id("tray")));
+    /// for (id, text) in [("sync", "Sync Now"), ("logout", "Log Out")] {
+    ///     let item = MenuItem::with_id(id, text, true, None);
+    ///     manager.insert_into(&MenuId::new("tray"), MenuControl::MenuItem(item), None).unwrap();
+    /// }
+    ///
+    /// manager.enabled_if(&MenuId::new("sync"), |state: &AppState| state.logged_in);
+    /// manager.visible_if(&MenuId::new("logout"), |state: &AppState| state.logged_in);
+    ///
+    /// manager.refresh_conditions(&AppState { logged_in: false }).unwrap();
+    /// assert!(!manager.is_visible(&MenuId::new("logout")));
+    /// assert_eq!(manager.position_of(&MenuId::new("logout")), None);
+    ///
+    /// manager.refresh_conditions(&AppState { logged_in: true }).unwrap();
+    /// assert_eq!(manager.position_of(&MenuId::new("logout")), Some(1));
+    /// ```
+    pub fn refresh_conditions<C: 'static>(&mut self, ctx: &C) -> Result<(), Error> {
+        self.begin_observe();
+        let mut visibility = Vec::new();
+
+        for (menu_id, conditions) in &self.conditions {
+            let Some(menu) = self.id_to_menu.get(menu_id) else {
+                continue;
+            };
+
+            if let Some(enabled) = conditions.enabled_if.as_ref().and_then(|c| c(ctx))
+                && self.is_item_enabled(menu_id, menu) != enabled
+            {
+                self.observe_item(menu_id);
+                self.set_item_enabled(menu_id, menu, enabled);
+            }
+
+            if let Some(visible) = conditions.visible_if.as_ref().and_then(|c| c(ctx)) {
+                visibility.push((menu_id.clone(), visible));
+            }
+        }
+        self.end_observe();
+
+        for (menu_id, visible) in visibility {
+            self.set_visible(&menu_id, visible)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows or hides a menu control inserted with [`MenuManager::insert_into`].
+    ///
+    /// A hidden menu control is detached from its live parent menu but stays registered,
+    /// showing it again puts it back after the sibling it followed.
+    pub fn set_visible(&mut self, menu_id: &MenuId, visible: bool) -> Result<(), Error> {
+        let (Some(parent), Some(menu)) = (
+            self.id_to_parent
+                .get(menu_id)
+                .and_then(|parent_id| self.parents.get(parent_id)),
+            self.id_to_menu.get(menu_id),
+        ) else {
+            return Err(Error::MenuNotFound(menu_id.clone()));
+        };
+
+        let item = menu.as_is_menu_item();
+        match (visible, self.hidden.contains_key(menu_id)) {
+            (false, false) => {
+                let position = parent
+                    .position_of(menu_id)
+                    .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+                let previous_id = position.checked_sub(1).and_then(|previous| {
+                    parent.items().get(previous).map(|item| item.id().clone())
+                });
+
+                parent.remove(item)?;
+                self.hidden.insert(menu_id.clone(), previous_id);
+            }
+            (true, true) => {
+                // A hidden previous sibling is skipped for the one it followed
+                let mut previous_id = self.hidden.get(menu_id).cloned().flatten();
+                let mut position = 0;
+                for _ in 0..=self.hidden.len() {
+                    let Some(id) = previous_id else {
+                        break;
+                    };
+                    if let Some(previous) = parent.position_of(&id) {
+                        position = previous + 1;
+                        break;
+                    }
+                    previous_id = self.hidden.get(&id).cloned().flatten();
+                }
+
+                // Siblings shown since it was hidden, e.g. by a profile, stay before it
+                let parent_id = parent.id();
+                let registered_position = self
+                    .id_to_menu
+                    .get_index_of(menu_id)
+                    .and_then(|index| {
+                        self.id_to_menu
+                            .keys()
+                            .take(index)
+                            .rev()
+                            .find_map(|sibling_id| {
+                                (self.id_to_parent.get(sibling_id.as_ref()) == Some(parent_id))
+                                    .then(|| parent.position_of(sibling_id.as_ref()))
+                                    .flatten()
+                            })
+                    })
+                    .map_or(0, |previous| previous + 1);
+
+                parent.insert(item, position.max(registered_position))?;
+                self.hidden.shift_remove(menu_id);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a menu control is not hidden, see [`MenuManager::set_visible`].
+    pub fn is_visible(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> bool {
+        !self.hidden.contains_key(&MenuKey::new(menu_id))
+    }
+
+    /// Nests a group under a parent group, so group-wide operations can recurse into it.
+    ///
+    /// Returns `false` if the parent group is nested under the child group (a cycle).
+    /// The parent group doesn't need any menu controls of its own.
+    pub fn set_parent_group(&mut self, child: G, parent: G) -> bool {
+        if child == parent || self.nested_groups(&child, true).contains(&parent) {
+            trace_event!(warn, "rejected parent group, it would create a cycle");
+            return false;
+        }
+
+        self.parent_groups.insert(child, parent);
+        true
+    }
+
+    /// Removes a group from its parent group.
+    pub fn remove_parent_group(&mut self, child: &G) {
+        self.parent_groups.shift_remove(child);
+    }
+
+    /// Gets the parent group of a group.
+    pub fn parent_group(&self, child: &G) -> Option<&G> {
+        self.parent_groups.get(child)
+    }
+
+    /// Gets a group followed by its child groups, or by all its descendant groups if `recursive`.
+    pub fn nested_groups(&self, group_id: &G, recursive: bool) -> Vec<G> {
+        let mut groups = vec![group_id.clone()];
+        let mut index = 0;
+
+        while index < groups.len() {
+            let parent = groups[index].clone();
+            groups.extend(
+                self.parent_groups
+                    .iter()
+                    .filter(|(child, group_parent)| {
+                        **group_parent == parent && !groups.contains(child)
+                    })
+                    .map(|(child, _)| child.clone())
+                    .collect::<Vec<_>>(),
+            );
+
+            if !recursive {
+                break;
+            }
+            index += 1;
+        }
+
+        groups
+    }
+
+    /// Enables or disables the check menus of a group, and of its nested groups if `recursive`.
+    pub fn set_group_enabled(&self, group_id: &G, enabled: bool, recursive: bool) {
+        self.begin_observe();
+        self.nested_groups(group_id, recursive)
+            .iter()
+            .filter_map(|group| self.grouped_check_items.get(group))
+            .flat_map(|check_menus| check_menus.keys())
+            .filter_map(|menu_id| self.id_to_menu.get_key_value(menu_id))
+            .for_each(|(menu_id, menu)| {
+                self.observe_item(menu_id);
+                self.set_item_enabled(menu_id, menu, enabled);
+            });
+        self.end_observe();
+    }
+
+    /// Attaches metadata to a group, replacing the previous one.
+    pub fn set_group_meta(&mut self, group_id: G, meta: GroupMeta) {
+        self.group_metas.insert(group_id, meta);
+    }
+
+    /// Gets the metadata of a group.
+    pub fn group_meta(&self, group_id: &G) -> Option<&GroupMeta> {
+        self.group_metas.get(group_id)
+    }
+
+    /// Removes the metadata of a group.
+    pub fn remove_group_meta(&mut self, group_id: &G) -> Option<GroupMeta> {
+        self.group_metas.shift_remove(group_id)
+    }
+
+    /// Sets what happens when the callback of [`MenuManager::update`] panics.
+    ///
+    /// With [`PanicPolicy::Continue`], a buggy handler doesn't take down the tray event loop.
+    /// The menu state is already updated when the callback is called.
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panic_policy = policy;
+    }
+
+    pub fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy
+    }
+
+    /// Sets a handler for the errors of menu operations that can't be returned, e.g. detaching
+    /// a removed menu control from its live parent menu.
+    ///
+    /// Such an error means the live menu may be out of sync with the menu manager, and should
+    /// be rebuilt. The handler is called under the panic policy.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::MenuManager;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// manager.set_error_handler(|error| eprintln!("tray menu out of sync: {error}"));
+    /// ```
+    pub fn set_error_handler(&mut self, handler: impl Fn(&Error) + 'static) {
+        self.error_handler = Some(Rc::new(handler));
+    }
+
+    /// Reports the error of a menu operation to the error handler.
+    pub(crate) fn report<E: Into<Error>>(&self, result: Result<(), E>) {
+        let Err(error) = result.map_err(Into::into) else {
+            return;
+        };
+
+        trace_event!(warn, error = %error, "menu operation failed");
+        if let Some(handler) = &self.error_handler {
+            call_with_policy(self.panic_policy, || handler(&error));
+        }
+    }
+
+    /// Sets what happens when the checked radio of a radio group is clicked again.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, RadioPolicy};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// let dark = Rc::new(CheckMenuItem::with_id("dark", "Dark", true, true, None));
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(dark.clone(), None, "theme")));
+    /// manager.set_radio_policy("theme", RadioPolicy::RevertClick);
+    ///
+    /// // The platform unchecks the clicked radio, the manager checks it back
+    /// dark.set_checked(false);
+    /// manager.update(&MenuId::new("dark"), |_| {});
+    /// assert!(dark.is_checked());
+    /// ```
+    pub fn set_radio_policy(&mut self, group_id: G, policy: RadioPolicy) {
+        self.radio_policies.insert(group_id, policy);
+    }
+
+    /// Gets the radio policy of a radio group, [`RadioPolicy::Reselect`] unless set.
+    pub fn radio_policy(&self, group_id: &G) -> RadioPolicy {
+        self.radio_policies
+            .get(group_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Overrides the default checked state of a check menu.
+    pub fn set_default_checked(&mut self, menu_id: &MenuId, checked: bool) {
+        if let Some((menu_id, menu)) = self.id_to_menu.get_key_value(menu_id)
+            && menu.as_check_menu().is_some()
+        {
+            self.default_checked.insert(menu_id.clone(), checked);
+        }
+    }
+
+    /// Gets the default checked state of a check menu.
+    pub fn default_checked(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<bool> {
+        self.default_checked.get(&MenuKey::new(menu_id)).copied()
+    }
+
+    /// Restores the default checked state of a group and of its nested groups.
+    ///
+    /// If no radio of a radio group is checked by default, the default radio menu is checked.
+    /// Returns the menu IDs whose checked state changed, so they can be handled like clicks.
+    pub fn reset_group(&self, group_id: &G) -> Vec<MenuId> {
+        self.begin_observe();
+        let mut changed = Vec::new();
+
+        for group in self.nested_groups(group_id, true) {
+            self.reset_check_menus(&group, &mut changed);
+        }
+
+        self.apply_dependencies();
+        self.refresh_check_icons();
+        self.end_observe();
+        changed
+    }
+
+    /// Restores the default checked state of all check menus.
+    ///
+    /// Returns the menu IDs whose checked state changed, so they can be handled like clicks.
+    pub fn reset_all(&self) -> Vec<MenuId> {
+        self.begin_observe();
+        let mut changed = Vec::new();
+
+        for group in self.grouped_check_items.keys() {
+            self.reset_check_menus(group, &mut changed);
+        }
+
+        for menu in self.id_to_menu.values() {
+            if let Some(CheckRef::Separate(check_menu)) = menu.check_ref()
+                && let Some(&checked) = self.default_checked.get(check_menu.id())
+                && check_menu.is_checked() != checked
+            {
+                self.observe_item(check_menu.id());
+                check_menu.set_checked(checked);
+                changed.push(check_menu.id().clone());
+            }
+        }
+
+        self.master_checkboxes
+            .values()
+            .for_each(|group| self.sync_master_checkbox(group));
+        self.apply_dependencies();
+        self.refresh_check_icons();
+        self.end_observe();
+        changed
+    }
+
+    /// Gets the default radio menu ID of a radio group, if it is a radio of the group.
+    ///
+    /// The default set with [`MenuManager::set_group_default`] wins over the deprecated
+    /// per-radio default radio menu IDs, of which the first one in the group is used.
+    fn default_radio_of(&self, group: &G) -> Option<&MenuId> {
+        let check_menus = self.grouped_check_items.get(group)?;
+        let is_radio_of_group = |menu_id: &MenuId| {
+            check_menus.contains_key(menu_id)
+                && matches!(
+                    self.id_to_menu
+                        .get(menu_id)
+                        .and_then(|menu| menu.check_ref()),
+                    Some(CheckRef::Radio(..))
+                )
+        };
+
+        if let Some(default_id) = self.group_defaults.get(group) {
+            return is_radio_of_group(default_id).then_some(default_id);
+        }
+
+        check_menus.keys().find_map(|menu_id| {
+            match self
+                .id_to_menu
+                .get(menu_id.as_ref())
+                .and_then(|menu| menu.check_ref())
+            {
+                Some(CheckRef::Radio(_, Some(default_id), _)) if is_radio_of_group(default_id) => {
+                    Some(default_id.as_ref())
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Sets the radio checked when the checked radio of a radio group is unchecked.
+    ///
+    /// Replaces the deprecated default radio menu ID of [`CheckMenuKind::Radio`], which
+    /// every radio of the group had to repeat. The group default wins over them.
+    ///
+    /// Fails with [`Error::MenuNotFound`] if `default_id` is not a registered radio of the
+    /// group, so insert the radios first.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// for (id, checked) in [("system", true), ("dark", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+    /// }
+    /// manager.set_group_default("theme", MenuId::new("system")).unwrap();
+    ///
+    /// assert_eq!(manager.group_default(&"theme"), Some(&MenuId::new("system")));
+    /// assert!(manager.set_group_default("theme", MenuId::new("missing")).is_err());
+    /// ```
+    pub fn set_group_default(&mut self, group_id: G, default_id: MenuId) -> Result<(), Error> {
+        if !self.is_radio_of(&default_id, &group_id) {
+            trace_event!(warn, default_id = ?default_id, "group default is not a radio of the group");
+            return Err(Error::MenuNotFound(default_id));
+        }
+
+        self.group_defaults.insert(group_id, default_id);
+        Ok(())
+    }
+
+    /// Checks if a menu control is a radio of a group.
+    fn is_radio_of(&self, menu_id: &MenuId, group_id: &G) -> bool {
+        matches!(
+            self.id_to_menu.get(menu_id).and_then(|menu| menu.check_ref()),
+            Some(CheckRef::Radio(_, _, group)) if group == group_id
+        )
+    }
+
+    /// Gets the default radio menu ID set for a radio group.
+    pub fn group_default(&self, group_id: &G) -> Option<&MenuId> {
+        self.group_defaults.get(group_id)
+    }
+
+    /// Removes the default radio menu ID of a radio group.
+    pub fn remove_group_default(&mut self, group_id: &G) -> Option<MenuId> {
+        self.group_defaults.shift_remove(group_id)
+    }
+
+    /// Moves the per-radio default radio menu IDs to group defaults.
+    ///
+    /// Groups that already have a group default are left as they are. Returns the groups
+    /// whose radios disagreed on their default, the first default in the group was kept.
+    pub fn migrate_radio_defaults(&mut self) -> Vec<G> {
+        let migrations: Vec<(G, MenuId, bool)> = self
+            .grouped_check_items
+            .iter()
+            .filter(|(group, _)| !self.group_defaults.contains_key(*group))
+            .filter_map(|(group, check_menus)| {
+                let default_id = self.default_radio_of(group)?;
+                let disagree = check_menus.keys().any(|menu_id| {
+                    matches!(
+                        self.id_to_menu.get(menu_id.as_ref()).and_then(|menu| menu.check_ref()),
+                        Some(CheckRef::Radio(_, Some(other_id), _))
+                            if other_id.as_ref() != default_id
+                    )
+                });
+
+                Some((group.clone(), default_id.clone(), disagree))
+            })
+            .collect();
+
+        let mut disagreeing_groups = Vec::new();
+        for (group, default_id, disagree) in migrations {
+            if disagree {
+                disagreeing_groups.push(group.clone());
+            }
+            self.group_defaults.insert(group, default_id);
+        }
+
+        disagreeing_groups
+    }
+
+    /// Leaves exactly one checked radio in each radio group, e.g. after building the menu.
+    ///
+    /// If several radios are checked, the default radio menu is kept if it is one of them,
+    /// else the first checked radio. If none is checked, the default radio menu is checked,
+    /// a radio group without one is left empty. This also happens on the first
+    /// [`MenuManager::update`] for the groups other than the clicked one, which is settled
+    /// by the click. Returns the menu IDs whose checked state changed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::new();
+    /// let default_id = Some(Rc::new(MenuId::new("dark")));
+    /// for id in ["light", "dark"] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, true, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(
+    ///         Rc::new(radio),
+    ///         default_id.clone(),
+    ///         "theme",
+    ///     )));
+    /// }
+    ///
+    /// assert_eq!(manager.normalize_groups(), [MenuId::new("light")]);
+    /// assert!(manager.check_invariants().is_ok());
+    /// ```
+    pub fn normalize_groups(&mut self) -> Vec<MenuId> {
+        self.groups_normalized = true;
+        self.normalize_groups_except(None)
+    }
+
+    fn normalize_groups_except(&self, skip_group: Option<&G>) -> Vec<MenuId> {
+        self.begin_observe();
+        let mut changed = Vec::new();
+
+        for (group, check_menus) in &self.grouped_check_items {
+            if Some(group) == skip_group {
+                continue;
+            }
+
+            let radios: Vec<(&MenuId, &Rc<CheckMenuItem>)> = check_menus
+                .iter()
+                .filter(|(menu_id, _)| {
+                    matches!(
+                        self.id_to_menu
+                            .get(menu_id.as_ref())
+                            .and_then(|menu| menu.check_ref()),
+                        Some(CheckRef::Radio(..))
+                    )
+                })
+                .map(|(menu_id, check_menu)| (menu_id.as_ref(), check_menu))
+                .collect();
+
+            let checked_ids: Vec<&MenuId> = radios
+                .iter()
+                .filter(|(_, check_menu)| check_menu.is_checked())
+                .map(|(menu_id, _)| *menu_id)
+                .collect();
+
+            if checked_ids.len() == 1 {
+                continue;
+            }
+
+            let default_id = self.default_radio_of(group);
+            let keep_id = if checked_ids.is_empty() {
+                if self.radio_policy(group) == RadioPolicy::AllowNone {
+                    continue;
+                }
+                default_id
+            } else {
+                default_id
+                    .filter(|default_id| checked_ids.contains(default_id))
+                    .or(checked_ids.first().copied())
+            };
+
+            for (menu_id, check_menu) in radios {
+                let checked = Some(menu_id) == keep_id;
+                if check_menu.is_checked() != checked {
+                    trace_event!(debug, menu_id = ?menu_id, checked, "normalize radio");
+                    self.observe_item(menu_id);
+                    check_menu.set_checked(checked);
+                    changed.push(menu_id.clone());
+                }
+            }
+        }
+
+        self.refresh_check_icons();
+        self.end_observe();
+        changed
+    }
+
+    fn reset_check_menus(&self, group: &G, changed: &mut Vec<MenuId>) {
+        let Some(check_menus) = self.grouped_check_items.get(group) else {
+            return;
+        };
+
+        let is_default = |menu_id: &MenuId| self.default_checked.get(menu_id).copied();
+
+        // A radio group without a checked default falls back to its default radio menu
+        let default_radio_id = if check_menus
+            .keys()
+            .any(|menu_id| is_default(menu_id) == Some(true))
+        {
+            None
+        } else {
+            self.default_radio_of(group)
+        };
+
+        for (menu_id, check_menu) in check_menus {
+            let checked = if default_radio_id == Some(menu_id.as_ref()) {
+                true
+            } else if let Some(checked) = is_default(menu_id) {
+                checked
+            } else {
+                continue;
+            };
+
+            if check_menu.is_checked() != checked {
+                self.observe_item(menu_id);
+                check_menu.set_checked(checked);
+                changed.push(menu_id.as_ref().clone());
+            }
+        }
+
+        self.sync_master_checkbox(group);
+    }
+
+    /// Applies several changes at once.
+    ///
+    /// Returns the menu IDs changed by the batch, including the other radios of their groups.
+    ///
+    /// Radio groups, "Select all" checkboxes and dependencies are enforced once after
+    /// all changes, e.g. when restoring a saved configuration item by item.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<&str>::new();
+    /// for (id, checked) in [("light", true), ("dark", false)] {
+    ///     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+    ///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+    /// }
+    ///
+    /// let changed = manager.batch(|batch| {
+    ///     batch.set_checked(&MenuId::new("dark"), true);
+    /// });
+    /// assert_eq!(changed.len(), 2);
+    /// ```
+    pub fn batch(&mut self, changes: impl FnOnce(&mut Batch<G>)) -> Vec<MenuId> {
+        self.begin_observe();
+        let mut batch = Batch::new(self);
+        changes(&mut batch);
+        let changed = batch.commit();
+        self.end_observe();
+        changed
+    }
+
+    /// Schedules an action on a menu control, e.g. to uncheck "Do Not Disturb" after an hour.
+    ///
+    /// Scheduled actions run in [`MenuManager::tick`], and are dropped with their menu control.
+    ///
+    /// # Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, Schedule, ScheduledAction};
+    /// use tray_icon::menu::{CheckMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    /// let dnd = CheckMenuItem::with_id("dnd", "Do Not Disturb", true, true, None);
+    /// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(dnd))));
+    ///
+    /// manager.schedule(
+    ///     &MenuId::new("dnd"),
+    ///     Schedule::After(Duration::ZERO),
+    ///     ScheduledAction::Uncheck,
+    /// );
+    ///
+    /// // Call periodically, e.g. from your event loop
+    /// assert_eq!(manager.tick(), [MenuId::new("dnd")]);
+    /// assert_eq!(manager.next_schedule(), None);
+    /// ```
+    pub fn schedule(
+        &mut self,
+        menu_id: &MenuId,
+        schedule: Schedule,
+        action: ScheduledAction,
+    ) -> ScheduleId {
+        self.scheduler.schedule(menu_id.clone(), schedule, action)
+    }
+
+    /// Cancels a scheduled action, returns `false` if it already ran or was cancelled.
+    pub fn cancel_schedule(&mut self, id: ScheduleId) -> bool {
+        self.scheduler.cancel(id)
+    }
+
+    /// Gets when the next scheduled action is due, or the label of a running
+    /// [`TimerItem`] changes, e.g. for `ControlFlow::WaitUntil`.
+    pub fn next_schedule(&self) -> Option<Instant> {
+        let now = Instant::now();
+        self.timers
+            .values()
+            .filter_map(|timer| timer.next_update(now))
+            .chain(self.scheduler.next_due())
+            .min()
+    }
+
+    /// Runs the scheduled actions that are due, returns the menu IDs they changed.
+    ///
+    /// The actions are applied as one [`MenuManager::batch`], so subscribers see their changes.
+    pub fn tick(&mut self) -> Vec<MenuId> {
+        let tasks = self.scheduler.take_due(Instant::now());
+        if tasks.is_empty() {
+            return Vec::new();
+        }
+
+        self.batch(|batch| {
+            for task in &tasks {
+                trace_event!(debug, menu_id = ?task.menu_id, action = ?task.action, "run scheduled action");
+
+                match task.action {
+                    ScheduledAction::Check => batch.set_checked(&task.menu_id, true),
+                    ScheduledAction::Uncheck => batch.set_checked(&task.menu_id, false),
+                    ScheduledAction::Toggle => batch.toggle(&task.menu_id),
+                    ScheduledAction::Enable => batch.set_enabled(&task.menu_id, true),
+                    ScheduledAction::Disable => batch.set_enabled(&task.menu_id, false),
+                };
+            }
+        })
+    }
+
+    /// Inserts the menu item of a countdown, see [`TimerItem`].
+    pub fn insert_timer(&mut self, timer: TimerItem) -> MenuHandle {
+        let handle = self.insert(timer.menu_control());
+        self.timers.insert(timer.id().clone(), timer);
+        handle
+    }
+
+    /// Starts or resumes a countdown, an expired countdown starts over.
+    ///
+    /// Returns `false` if the menu control is not a timer.
+    pub fn start_timer(&mut self, menu_id: &MenuId) -> bool {
+        self.update_timer(menu_id, |timer, now| timer.start(now))
+    }
+
+    /// Pauses a countdown, returns `false` if the menu control is not a timer.
+    pub fn pause_timer(&mut self, menu_id: &MenuId) -> bool {
+        self.update_timer(menu_id, |timer, now| timer.pause(now))
+    }
+
+    /// Stops a countdown, and sets it back to its duration.
+    ///
+    /// Returns `false` if the menu control is not a timer.
+    pub fn reset_timer(&mut self, menu_id: &MenuId) -> bool {
+        self.update_timer(menu_id, |timer, _| timer.reset())
+    }
+
+    pub fn is_timer_running(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> bool {
+        self.timers
+            .get(&MenuKey::new(menu_id))
+            .is_some_and(TimerItem::is_running)
+    }
+
+    pub fn timer_remaining(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<Duration> {
+        self.timers
+            .get(&MenuKey::new(menu_id))
+            .map(|timer| timer.remaining(Instant::now()))
+    }
+
+    fn update_timer(
+        &mut self,
+        menu_id: &MenuId,
+        update: impl FnOnce(&mut TimerItem, Instant),
+    ) -> bool {
+        let now = Instant::now();
+        let Some(timer) = self.timers.get_mut(menu_id) else {
+            return false;
+        };
+
+        update(timer, now);
+        let text = timer.text(now);
+        self.batch(|batch| {
+            batch.set_text(menu_id, &text);
+        });
+        true
+    }
+
+    /// Updates the labels of the running timers, and runs the actions of the expired ones.
+    ///
+    /// Returns the outcome of each expired timer, [`MenuAction::Done`] for a timer without
+    /// an action. The labels are updated as one [`MenuManager::batch`].
+    pub fn tick_timers(&mut self) -> Vec<(MenuId, Result<MenuAction, Error>)> {
+        let now = Instant::now();
+        let mut texts = Vec::new();
+        let mut expired = Vec::new();
+
+        for (menu_id, timer) in &mut self.timers {
+            if !timer.is_running() {
+                continue;
+            }
+            if timer.expire(now) {
+                expired.push(menu_id.clone());
+            }
+            texts.push((menu_id.clone(), timer.text(now)));
+        }
+
+        if texts.is_empty() {
+            return Vec::new();
+        }
+        self.batch(|batch| {
+            for (menu_id, text) in &texts {
+                batch.set_text(menu_id, text);
+            }
+        });
+
+        expired
+            .into_iter()
+            .map(|menu_id| {
+                trace_event!(debug, menu_id = ?menu_id, "timer expired");
+
+                let action = match self.timers.get(&menu_id).and_then(TimerItem::action) {
+                    Some(action) => action.run(&mut self.action_context),
+                    None => Ok(MenuAction::Done),
+                };
+                (menu_id, action)
+            })
+            .collect()
+    }
+
+    /// Keeps only one radio of a radio group checked, falling back to the default radio menu.
+    fn settle_radio_group(&self, group: &G, checked_id: Option<&MenuId>) {
+        let Some(check_menus) = self.grouped_check_items.get(group) else {
+            return;
+        };
+
+        let checked_id = checked_id
+            .or_else(|| {
+                check_menus
+                    .iter()
+                    .find(|(_, check_menu)| check_menu.is_checked())
+                    .map(|(menu_id, _)| menu_id.as_ref())
+            })
+            .or_else(|| {
+                self.default_radio_of(group)
+                    .filter(|_| self.radio_policy(group) != RadioPolicy::AllowNone)
+            });
+
+        check_menus.iter().for_each(|(menu_id, check_menu)| {
+            self.observe_item(menu_id);
+            check_menu.set_checked(Some(menu_id.as_ref()) == checked_id);
+        });
+    }
+
+    /// Sets the keyboard accelerator of a menu control, or removes it if `None`.
+    ///
+    /// Fails with [`Error::AcceleratorConflict`] if another menu control already uses it.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem, accelerator::Accelerator};
+    ///
+    /// let mut manager = MenuManager::<&str>::new();
+    /// manager.insert(MenuControl::MenuItem(MenuItem::with_id("quit", "Quit", true, None)));
+    ///
+    /// let accelerator: Accelerator = "Ctrl+Q".parse().unwrap();
+    /// manager.set_accelerator(&MenuId::new("quit"), Some(accelerator)).unwrap();
+    ///
+    /// let menu = manager.find_by_accelerator(&accelerator).unwrap();
+    /// assert_eq!(menu.id(), &MenuId::new("quit"));
+    /// ```
+    pub fn set_accelerator(
+        &mut self,
+        menu_id: &MenuId,
+        accelerator: Option<Accelerator>,
+    ) -> Result<(), Error> {
+        let menu = self
+            .id_to_menu
+            .get(menu_id)
+            .ok_or_else(|| Error::MenuNotFound(menu_id.clone()))?;
+
+        if let Some(accelerator) = accelerator
+            && let Some((conflict_id, _)) = self
+                .accelerators
+                .iter()
+                .find(|(id, used)| **used == accelerator && *id != menu_id)
+        {
+            trace_event!(
+                warn,
+                menu_id = ?menu_id,
+                conflict_id = ?conflict_id,
+                "accelerator already in use",
+            );
+            return Err(Error::AcceleratorConflict {
+                accelerator,
+                menu_id: conflict_id.clone(),
+            });
+        }
+
+        menu.set_accelerator(accelerator)?;
+
+        match accelerator {
+            Some(accelerator) => self.accelerators.insert(menu_id.clone(), accelerator),
+            None => self.accelerators.shift_remove(menu_id),
+        };
+
+        Ok(())
+    }
+
+    /// Gets the keyboard accelerator of a menu control set with [`MenuManager::set_accelerator`].
+    pub fn accelerator(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&Accelerator> {
+        self.accelerators.get(&MenuKey::new(menu_id))
+    }
+
+    /// Gets the menu control using a keyboard accelerator.
+    pub fn find_by_accelerator(&self, accelerator: &Accelerator) -> Option<&MenuControl<G>> {
+        self.accelerators
+            .iter()
+            .find(|(_, used)| *used == accelerator)
+            .and_then(|(menu_id, _)| self.id_to_menu.get(menu_id))
+    }
+
+    /// Adds or replaces the translations of a locale, a table of translation keys to texts.
+    ///
+    /// Texts can contain placeholders, rendered with the variables of [`MenuManager::set_var`],
+    /// and mnemonic markers like `"&File"`, which are stripped on **macOS**.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{MenuId, MenuItem};
+    ///
+    /// let mut manager = MenuManager::<&str>::new();
+    /// let quit = MenuItem::with_id("quit", "Quit", true, None);
+    /// manager.insert(MenuControl::MenuItem(quit.clone()));
+    /// manager.set_translation_key(&MenuId::new("quit"), "menu.quit");
+    ///
+    /// manager.add_locale("en", [("menu.quit", "Quit")]);
+    /// manager.add_locale("ja", [("menu.quit", "終了")]);
+    ///
+    /// manager.set_locale("ja");
+    /// assert_eq!(quit.text(), "終了");
+    /// ```
+    pub fn add_locale<I, K, V>(&mut self, locale: &str, translations: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.locales.insert(
+            locale.to_owned(),
+            translations
+                .into_iter()
+                .map(|(key, text)| (key.into(), text.into()))
+                .collect(),
+        );
+
+        if self.locale.as_deref() == Some(locale) {
+            self.retranslate();
+        }
+    }
+
+    /// Adds or replaces the accelerators of a locale, a table of translation keys to accelerators.
+    ///
+    /// When the locale is set, menu controls with these translation keys get the accelerators,
+    /// e.g. `Ctrl+Z` in one locale and `Ctrl+Y` in a locale with a QWERTZ layout.
+    /// An accelerator that conflicts with one set by [`MenuManager::set_accelerator`] is skipped.
+    pub fn add_locale_accelerators<I, K>(&mut self, locale: &str, accelerators: I)
+    where
+        I: IntoIterator<Item = (K, Accelerator)>,
+        K: Into<String>,
+    {
+        self.locale_accelerators.insert(
+            locale.to_owned(),
+            accelerators
+                .into_iter()
+                .map(|(key, accelerator)| (key.into(), accelerator))
+                .collect(),
+        );
+
+        if self.locale.as_deref() == Some(locale) {
+            self.retranslate();
+        }
+    }
+
+    /// Sets the translation key of a menu control, and translates it into the current locale.
+    pub fn set_translation_key(&mut self, menu_id: &MenuId, key: &str) {
+        self.translation_keys
+            .insert(menu_id.clone(), key.to_owned());
+
+        self.begin_observe();
+        self.retranslate_menu(menu_id);
+        self.end_observe();
+    }
+
+    /// Switches the locale and retranslates every menu control with a translation key,
+    /// returns `false` if the locale was not added with [`MenuManager::add_locale`].
+    ///
+    /// Menu controls whose key is missing from the locale keep their current text.
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        if !self.locales.contains_key(locale) {
+            return false;
+        }
+
+        self.locale = Some(locale.to_owned());
+
+        self.begin_observe();
+        self.retranslate();
+        self.end_observe();
+        true
+    }
+
+    /// Gets the current locale.
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Translates a key into the current locale.
+    pub fn translate(&self, key: &str) -> Option<&str> {
+        self.locale
+            .as_ref()
+            .and_then(|locale| self.locales.get(locale))
+            .and_then(|translations| translations.get(key))
+            .map(String::as_str)
+    }
+
+    fn retranslate(&mut self) {
+        let menu_ids: Vec<MenuId> = self.translation_keys.keys().cloned().collect();
+        menu_ids
+            .iter()
+            .for_each(|menu_id| self.retranslate_menu(menu_id));
+
+        self.apply_locale_accelerators();
+    }
+
+    fn apply_locale_accelerators(&mut self) {
+        let Some(accelerators) = self
+            .locale
+            .as_ref()
+            .and_then(|locale| self.locale_accelerators.get(locale))
+        else {
+            return;
+        };
+
+        let localized: Vec<(MenuId, Accelerator)> = self
+            .translation_keys
+            .iter()
+            .filter_map(|(menu_id, key)| {
+                accelerators
+                    .get(key)
+                    .map(|accelerator| (menu_id.clone(), *accelerator))
+            })
+            .collect();
+
+        // Clear first, so accelerators swapped between two menu controls don't conflict
+        for (menu_id, _) in &localized {
+            let result = self.set_accelerator(menu_id, None);
+            self.report(result);
+        }
+        for (menu_id, accelerator) in localized {
+            let result = self.set_accelerator(&menu_id, Some(accelerator));
+            self.report(result);
+        }
+    }
+
+    fn retranslate_menu(&mut self, menu_id: &MenuId) {
+        let Some(text) = self
+            .translation_keys
+            .get(menu_id)
+            .and_then(|key| self.translate(key))
+            .map(mnemonic::apply_mnemonic)
+        else {
+            return;
+        };
+
+        // Translated labels stay templates, so variables keep updating them
+        if self.label_templates.contains_key(menu_id) {
+            self.set_label_template(menu_id, &text);
+        } else if let Some(menu) = self.id_to_menu.get(menu_id) {
+            self.observe_item(menu_id);
+            let text = template::render_template(&text, &self.template_vars);
+            self.set_item_text(menu_id, menu, &text);
+        }
+    }
+
+    /// Sets the icon of an icon menu, returns `false` if there is none.
+    ///
+    /// A checkable icon menu is rejected as well, its icons follow the checked state, set
+    /// them with [`CheckIconMenuItem::set_icons`].
+    pub fn set_item_icon(&mut self, menu_id: &MenuId, icon: Option<Icon>) -> bool {
+        let Some(icon_menu) = self
+            .id_to_menu
+            .get(menu_id)
+            .and_then(|menu| menu.as_plain_icon_menu())
+        else {
+            return false;
+        };
+
+        icon_menu.set_icon(icon);
+        self.item_icon_names.shift_remove(menu_id);
+        true
+    }
+
+    /// Registers an icon by name, so several icon menus can share it.
+    ///
+    /// Registering a name again updates every icon menu using it.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuControl, MenuManager};
+    /// use tray_icon::menu::{Icon, IconMenuItem, MenuId};
+    ///
+    /// let mut manager = MenuManager::<&str>::new();
+    /// let server = IconMenuItem::with_id("server_eu", "EU server", true, None, None);
+    /// manager.insert(MenuControl::IconMenu(server));
+    ///
+    /// let online = Icon::from_rgba(vec![0, 255, 0, 255], 1, 1).unwrap();
+    /// manager.register_icon("online", online);
+    /// assert!(manager.set_item_named_icon(&MenuId::new("server_eu"), "online"));
+    /// ```
+    pub fn register_icon(&mut self, name: &str, icon: Icon) {
+        self.item_icon_names
+            .iter()
+            .filter(|(_, icon_name)| *icon_name == name)
+            .filter_map(|(menu_id, _)| self.id_to_menu.get(menu_id))
+            .filter_map(|menu| menu.as_plain_icon_menu())
+            .for_each(|icon_menu| icon_menu.set_icon(Some(icon.clone())));
+
+        self.named_icons.insert(name.to_owned(), icon);
+    }
+
+    /// Gets an icon registered with [`MenuManager::register_icon`].
+    pub fn named_icon(&self, name: &str) -> Option<&Icon> {
+        self.named_icons.get(name)
+    }
+
+    /// Sets the icon of an icon menu to a registered icon.
+    ///
+    /// Returns `false` if there is no such icon menu or no icon registered under the name.
+    /// Like [`MenuManager::set_item_icon`], a checkable icon menu is rejected.
+    pub fn set_item_named_icon(&mut self, menu_id: &MenuId, name: &str) -> bool {
+        let Some(icon) = self.named_icons.get(name) else {
+            return false;
+        };
+
+        let Some(icon_menu) = self
+            .id_to_menu
+            .get(menu_id)
+            .and_then(|menu| menu.as_plain_icon_menu())
+        else {
+            return false;
+        };
+
+        icon_menu.set_icon(Some(icon.clone()));
+        self.item_icon_names
+            .insert(menu_id.clone(), name.to_owned());
+        true
+    }
+
+    /// Gets the name of the registered icon shown by an icon menu.
+    pub fn item_icon_name(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&str> {
+        self.item_icon_names
+            .get(&MenuKey::new(menu_id))
+            .map(String::as_str)
     }
 }
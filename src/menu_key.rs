@@ -0,0 +1,35 @@
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use indexmap::Equivalent;
+use muda::MenuId;
+
+/// Menu ID borrowed as a string, looks up maps keyed by `MenuId` without building one
+///
+/// `MenuId` hashes like the string it wraps, so both find the same entries.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MenuKey<'a>(&'a str);
+
+impl<'a> MenuKey<'a> {
+    pub(crate) fn new(menu_id: &'a (impl AsRef<str> + ?Sized)) -> Self {
+        MenuKey(menu_id.as_ref())
+    }
+}
+
+impl Hash for MenuKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Equivalent<MenuId> for MenuKey<'_> {
+    fn equivalent(&self, key: &MenuId) -> bool {
+        self.0 == key.as_ref()
+    }
+}
+
+impl Equivalent<Rc<MenuId>> for MenuKey<'_> {
+    fn equivalent(&self, key: &Rc<MenuId>) -> bool {
+        self.0 == key.as_ref().as_ref()
+    }
+}
@@ -0,0 +1,428 @@
+use std::hash::Hash;
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+
+#[cfg(feature = "dialogs")]
+use crate::Confirmation;
+use crate::{
+    Action, CheckMenuKind, Error, MenuControl, MenuManager, MenuParent, Platform, ScopedIds,
+};
+
+#[derive(Clone)]
+enum TemplateCheck<G> {
+    CheckBox(G),
+    Radio { group: G, default: bool },
+    Separate,
+}
+
+#[derive(Clone)]
+enum TemplateEntry<G> {
+    Item {
+        id: String,
+        text: String,
+    },
+    Check {
+        id: String,
+        text: String,
+        checked: bool,
+        kind: TemplateCheck<G>,
+    },
+    Separator,
+    Submenu {
+        id: String,
+        text: String,
+        entries: Vec<TemplateEntry<G>>,
+    },
+}
+
+impl<G> TemplateEntry<G> {
+    fn id(&self) -> Option<&str> {
+        match self {
+            TemplateEntry::Item { id, .. }
+            | TemplateEntry::Check { id, .. }
+            | TemplateEntry::Submenu { id, .. } => Some(id),
+            TemplateEntry::Separator => None,
+        }
+    }
+}
+
+/// Menu subtree that can be instantiated several times, e.g. one submenu per connected device
+///
+/// Each instance gets a prefix. The menu IDs of an instance are `{prefix}/{id}` (see
+/// [`ScopedIds`]), and its group keys are mapped by the `scope_group` callback, so the
+/// radio groups of two instances stay independent.
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, MenuTemplate};
+/// use tray_icon::menu::{Menu, MenuId};
+///
+/// let mut manager = MenuManager::<String>::new();
+/// let tray_menu = Menu::new();
+///
+/// let device = MenuTemplate::new("device", |prefix, group: &String| format!("{prefix}/{group}"))
+///     .default_radio("low", "Low Power", "mode".to_string())
+///     .radio("high", "High Performance", "mode".to_string())
+///     .separator()
+///     .item("eject", "Eject");
+///
+/// for (serial, name) in [("usb0", "Keyboard"), ("usb1", "Headset")] {
+///     let submenu = device.instantiate(&mut manager, serial, name).unwrap();
+///     tray_menu.append(&submenu).unwrap();
+/// }
+///
+/// assert!(manager.get_menu_item_from_id(&MenuId::new("usb1/eject")).is_some());
+/// assert_eq!(device.local_id("usb1", &MenuId::new("usb1/eject")), Some("eject"));
+/// assert_eq!(manager.group_len(&"usb0/mode".to_string()), 2);
+/// ```
+#[derive(Clone)]
+pub struct MenuTemplate<G> {
+    id: String,
+    text: String,
+    entries: Vec<TemplateEntry<G>>,
+    /// Actions by template ID
+    actions: Vec<(String, Action)>,
+    /// Confirmations by template ID
+    #[cfg(feature = "dialogs")]
+    confirmations: Vec<(String, Confirmation)>,
+    /// Accessible descriptions by template ID
+    descriptions: Vec<(String, String)>,
+    scope_group: fn(&str, &G) -> G,
+}
+
+impl<G> MenuTemplate<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Creates an empty template, `id` is the template ID of the instantiated submenu.
+    pub fn new(id: impl Into<String>, scope_group: fn(&str, &G) -> G) -> Self {
+        MenuTemplate {
+            id: id.into(),
+            text: String::new(),
+            entries: Vec::new(),
+            actions: Vec::new(),
+            #[cfg(feature = "dialogs")]
+            confirmations: Vec::new(),
+            descriptions: Vec::new(),
+            scope_group,
+        }
+    }
+
+    /// Sets the label of a nested template, see [`MenuTemplate::submenu`].
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn item(mut self, id: impl Into<String>, text: impl Into<String>) -> Self {
+        self.entries.push(TemplateEntry::Item {
+            id: id.into(),
+            text: text.into(),
+        });
+        self
+    }
+
+    pub fn check_box(
+        self,
+        id: impl Into<String>,
+        text: impl Into<String>,
+        checked: bool,
+        group: G,
+    ) -> Self {
+        self.push_check(id, text, checked, TemplateCheck::CheckBox(group))
+    }
+
+    pub fn separate(self, id: impl Into<String>, text: impl Into<String>, checked: bool) -> Self {
+        self.push_check(id, text, checked, TemplateCheck::Separate)
+    }
+
+    pub fn radio(self, id: impl Into<String>, text: impl Into<String>, group: G) -> Self {
+        let kind = TemplateCheck::Radio {
+            group,
+            default: false,
+        };
+        self.push_check(id, text, false, kind)
+    }
+
+    /// Adds a radio that is checked initially, and is the default radio of its group in each instance.
+    pub fn default_radio(self, id: impl Into<String>, text: impl Into<String>, group: G) -> Self {
+        let kind = TemplateCheck::Radio {
+            group,
+            default: true,
+        };
+        self.push_check(id, text, true, kind)
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.entries.push(TemplateEntry::Separator);
+        self
+    }
+
+    /// Restricts the last added entry to some platforms, it is skipped on the others.
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuManager, MenuTemplate, Platform};
+    /// use tray_icon::menu::MenuId;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    ///
+    /// let settings = MenuTemplate::new("settings", |_, group: &()| *group)
+    ///     .separate("login", "Run at Login", false)
+    ///     .platforms(&[Platform::Windows, Platform::MacOs])
+    ///     .separate("autostart", "Add to Autostart", false)
+    ///     .platforms(&[Platform::Linux]);
+    /// settings.instantiate(&mut manager, "app", "Settings").unwrap();
+    ///
+    /// let login = manager.get_menu_item_from_id(&MenuId::new("app/login"));
+    /// assert_eq!(login.is_some(), !Platform::Linux.is_current());
+    /// ```
+    pub fn platforms(mut self, platforms: &[Platform]) -> Self {
+        if !platforms.contains(&Platform::current())
+            && let Some(entry) = self.entries.pop()
+            && let Some(id) = entry.id()
+        {
+            self.actions.retain(|(action_id, _)| action_id != id);
+            #[cfg(feature = "dialogs")]
+            self.confirmations
+                .retain(|(confirmation_id, _)| confirmation_id != id);
+            self.descriptions
+                .retain(|(description_id, _)| description_id != id);
+        }
+        self
+    }
+
+    /// Attaches an action to the last added entry, see [`MenuManager::set_action`].
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{Action, MenuManager, MenuTemplate};
+    /// use tray_icon::menu::MenuId;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    ///
+    /// let tools = MenuTemplate::new("tools", |_, group: &()| *group)
+    ///     .item("top", "Task Manager")
+    ///     .action(Action::Exec { program: "htop".into(), args: Vec::new() });
+    /// tools.instantiate(&mut manager, "app", "Tools").unwrap();
+    ///
+    /// assert!(manager.action(&MenuId::new("app/top")).is_some());
+    /// ```
+    pub fn action(mut self, action: Action) -> Self {
+        if let Some(id) = self.entries.last().and_then(TemplateEntry::id) {
+            self.actions.push((id.to_owned(), action));
+        }
+        self
+    }
+
+    /// Asks a question before the action of the last added entry runs, see
+    /// [`MenuManager::set_confirmation`] (**requires the `dialogs` feature**).
+    #[cfg(feature = "dialogs")]
+    pub fn confirm(mut self, confirmation: Confirmation) -> Self {
+        if let Some(id) = self.entries.last().and_then(TemplateEntry::id) {
+            self.confirmations.push((id.to_owned(), confirmation));
+        }
+        self
+    }
+
+    /// Sets the accessible description of the last added entry, see
+    /// [`MenuManager::set_accessible_description`].
+    ///
+    /// # Example
+    /// ```
+    /// use tray_controls::{MenuManager, MenuTemplate};
+    /// use tray_icon::menu::MenuId;
+    ///
+    /// let mut manager = MenuManager::<()>::new();
+    ///
+    /// let device = MenuTemplate::new("device", |_, group: &()| *group)
+    ///     .item("eject", "⏏")
+    ///     .describe("Eject the device");
+    /// device.instantiate(&mut manager, "usb0", "Keyboard").unwrap();
+    ///
+    /// assert_eq!(manager.accessible_description("usb0/eject"), Some("Eject the device"));
+    /// ```
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        if let Some(id) = self.entries.last().and_then(TemplateEntry::id) {
+            self.descriptions.push((id.to_owned(), description.into()));
+        }
+        self
+    }
+
+    /// Nests another template as a submenu, labelled by [`MenuTemplate::with_text`].
+    ///
+    /// The entries of the nested template share the namespace of the instance, and its
+    /// group keys are mapped by the `scope_group` callback of this template.
+    pub fn submenu(mut self, template: MenuTemplate<G>) -> Self {
+        self.actions.extend(template.actions);
+        #[cfg(feature = "dialogs")]
+        self.confirmations.extend(template.confirmations);
+        self.descriptions.extend(template.descriptions);
+        self.entries.push(TemplateEntry::Submenu {
+            id: template.id,
+            text: template.text,
+            entries: template.entries,
+        });
+        self
+    }
+
+    /// Gets the template ID of the instantiated submenu.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Gets the menu ID of an entry in an instance.
+    pub fn scoped_id(&self, prefix: &str, id: &str) -> MenuId {
+        ScopedIds::new(prefix).id(id)
+    }
+
+    /// Gets the template ID of a menu ID of an instance, `None` if it belongs to another instance.
+    pub fn local_id<'a>(&self, prefix: &str, menu_id: &'a MenuId) -> Option<&'a str> {
+        ScopedIds::new(prefix).action(menu_id)
+    }
+
+    /// Creates an instance of the template, and registers its menu controls in the menu manager.
+    ///
+    /// Returns the submenu of the instance, append it to a tray menu or register it with
+    /// [`MenuManager::insert_parent`].
+    pub fn instantiate(
+        &self,
+        manager: &mut MenuManager<G>,
+        prefix: &str,
+        text: &str,
+    ) -> Result<Submenu, Error> {
+        let submenu = Submenu::with_id(self.scoped_id(prefix, &self.id), text, true);
+        self.instantiate_entries(manager, prefix, &submenu, &self.entries)?;
+        for (id, action) in &self.actions {
+            manager.set_action(&self.scoped_id(prefix, id), Some(action.clone()));
+        }
+        #[cfg(feature = "dialogs")]
+        for (id, confirmation) in &self.confirmations {
+            manager.set_confirmation(&self.scoped_id(prefix, id), Some(confirmation.clone()));
+        }
+        for (id, description) in &self.descriptions {
+            manager
+                .set_accessible_description(&self.scoped_id(prefix, id), Some(description.clone()));
+        }
+        Ok(submenu)
+    }
+
+    /// Removes the menu controls of an instance from the menu manager.
+    ///
+    /// The submenu of the instance is left in its parent menu.
+    pub fn remove_instance(&self, manager: &mut MenuManager<G>, prefix: &str) {
+        self.remove_entries(manager, prefix, &self.entries);
+    }
+
+    fn push_check(
+        mut self,
+        id: impl Into<String>,
+        text: impl Into<String>,
+        checked: bool,
+        kind: TemplateCheck<G>,
+    ) -> Self {
+        self.entries.push(TemplateEntry::Check {
+            id: id.into(),
+            text: text.into(),
+            checked,
+            kind,
+        });
+        self
+    }
+
+    fn instantiate_entries(
+        &self,
+        manager: &mut MenuManager<G>,
+        prefix: &str,
+        submenu: &Submenu,
+        entries: &[TemplateEntry<G>],
+    ) -> Result<(), Error> {
+        let parent_id = submenu.id().clone();
+        manager.insert_parent(MenuParent::Submenu(submenu.clone()));
+
+        for entry in entries {
+            match entry {
+                TemplateEntry::Item { id, text } => {
+                    let menu_item = MenuItem::with_id(self.scoped_id(prefix, id), text, true, None);
+                    manager.insert_into(&parent_id, MenuControl::MenuItem(menu_item), None)?;
+                }
+                TemplateEntry::Check {
+                    id,
+                    text,
+                    checked,
+                    kind,
+                } => {
+                    let menu_id = self.scoped_id(prefix, id);
+                    let check_menu = Rc::new(CheckMenuItem::with_id(
+                        menu_id.clone(),
+                        text,
+                        true,
+                        *checked,
+                        None,
+                    ));
+
+                    let check_menu_kind = match kind {
+                        TemplateCheck::CheckBox(group) => {
+                            CheckMenuKind::CheckBox(check_menu, (self.scope_group)(prefix, group))
+                        }
+                        TemplateCheck::Radio { group, .. } => CheckMenuKind::Radio(
+                            check_menu,
+                            None,
+                            (self.scope_group)(prefix, group),
+                        ),
+                        TemplateCheck::Separate => CheckMenuKind::Separate(check_menu),
+                    };
+                    manager.insert_into(
+                        &parent_id,
+                        MenuControl::CheckMenu(check_menu_kind),
+                        None,
+                    )?;
+
+                    if let TemplateCheck::Radio {
+                        group,
+                        default: true,
+                    } = kind
+                    {
+                        manager.set_group_default((self.scope_group)(prefix, group), menu_id)?;
+                    }
+                }
+                TemplateEntry::Separator => submenu.append(&PredefinedMenuItem::separator())?,
+                TemplateEntry::Submenu { id, text, entries } => {
+                    let child = Submenu::with_id(self.scoped_id(prefix, id), text, true);
+                    self.instantiate_entries(manager, prefix, &child, entries)?;
+                    submenu.append(&child)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_entries(
+        &self,
+        manager: &mut MenuManager<G>,
+        prefix: &str,
+        entries: &[TemplateEntry<G>],
+    ) {
+        for entry in entries {
+            match entry {
+                TemplateEntry::Item { id, .. } => {
+                    let _ = manager.remove(&self.scoped_id(prefix, id));
+                }
+                TemplateEntry::Check { id, kind, .. } => {
+                    // The whole group goes away, so losing its default radio is expected
+                    let _ = manager.remove(&self.scoped_id(prefix, id));
+
+                    if let TemplateCheck::Radio { group, .. } = kind {
+                        manager.remove_group_default(&(self.scope_group)(prefix, group));
+                    }
+                }
+                TemplateEntry::Separator => {}
+                TemplateEntry::Submenu { entries, .. } => {
+                    self.remove_entries(manager, prefix, entries);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+/// Prepares a label with mnemonic markers like `"&File"` for the current platform.
+///
+/// Windows and Linux underline the character after `&`, macOS has no mnemonics,
+/// so the markers are stripped there. `&&` is a literal ampersand.
+pub(crate) fn apply_mnemonic(text: &str) -> String {
+    if cfg!(target_os = "macos") {
+        strip_mnemonic(text)
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Removes mnemonic markers from a label, `"Save &As && Close"` becomes `"Save As & Close"`.
+pub(crate) fn strip_mnemonic(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            stripped.push(c);
+        } else if let Some(next) = chars.next() {
+            stripped.push(next);
+        }
+    }
+
+    stripped
+}
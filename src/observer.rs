@@ -0,0 +1,177 @@
+use std::cell::{Cell, RefCell};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use muda::MenuId;
+
+use crate::FxIndexMap;
+use crate::state::MenuItemState;
+
+/// What made a change, see [`MenuManager::with_origin`](crate::MenuManager::with_origin)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ChangeOrigin {
+    /// A menu event, see [`MenuManager::update`](crate::MenuManager::update)
+    UserClick,
+    /// A call of the application
+    #[default]
+    Api,
+    /// A saved state being loaded, e.g. by `ConfigBinding` or [`SettingsBinding`](crate::SettingsBinding)
+    Restore,
+    /// A keyboard shortcut of the application
+    HotKey,
+    /// Another process, e.g. through an `IpcServer`
+    Remote,
+}
+
+impl ChangeOrigin {
+    /// Checks if the user made the change, by a click or a hot key.
+    pub fn is_user(&self) -> bool {
+        matches!(self, ChangeOrigin::UserClick | ChangeOrigin::HotKey)
+    }
+}
+
+/// Change made through a [`MenuManager`](crate::MenuManager), see
+/// [`MenuManager::subscribe`](crate::MenuManager::subscribe)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeEvent<G> {
+    /// A menu control was inserted
+    ItemInserted {
+        menu_id: MenuId,
+        origin: ChangeOrigin,
+    },
+    /// A menu control was removed
+    ItemRemoved {
+        menu_id: MenuId,
+        origin: ChangeOrigin,
+    },
+    /// A check menu was checked or unchecked
+    CheckedChanged {
+        menu_id: MenuId,
+        checked: bool,
+        origin: ChangeOrigin,
+    },
+    /// A menu control was enabled or disabled
+    EnabledChanged {
+        menu_id: MenuId,
+        enabled: bool,
+        origin: ChangeOrigin,
+    },
+    /// The text of a menu control changed
+    TextChanged {
+        menu_id: MenuId,
+        text: String,
+        origin: ChangeOrigin,
+    },
+    /// A radio of the group `G` was checked, follows its `CheckedChanged` event
+    RadioSelected {
+        group: G,
+        menu_id: MenuId,
+        origin: ChangeOrigin,
+    },
+}
+
+impl<G> ChangeEvent<G> {
+    pub fn menu_id(&self) -> &MenuId {
+        match self {
+            ChangeEvent::ItemInserted { menu_id, .. }
+            | ChangeEvent::ItemRemoved { menu_id, .. }
+            | ChangeEvent::CheckedChanged { menu_id, .. }
+            | ChangeEvent::EnabledChanged { menu_id, .. }
+            | ChangeEvent::TextChanged { menu_id, .. }
+            | ChangeEvent::RadioSelected { menu_id, .. } => menu_id,
+        }
+    }
+
+    pub fn origin(&self) -> ChangeOrigin {
+        match self {
+            ChangeEvent::ItemInserted { origin, .. }
+            | ChangeEvent::ItemRemoved { origin, .. }
+            | ChangeEvent::CheckedChanged { origin, .. }
+            | ChangeEvent::EnabledChanged { origin, .. }
+            | ChangeEvent::TextChanged { origin, .. }
+            | ChangeEvent::RadioSelected { origin, .. } => *origin,
+        }
+    }
+}
+
+/// Subscribers of a menu manager
+///
+/// Nested mutations are reported once, by the outermost one. While it runs, the state of
+/// each menu control is recorded before its first change, so only those are compared.
+pub(crate) struct Observers<G> {
+    senders: RefCell<Vec<Sender<ChangeEvent<G>>>>,
+    depth: Cell<u32>,
+    origin: Cell<ChangeOrigin>,
+    /// `None` for a removed or not yet inserted menu control
+    recorded: RefCell<Option<FxIndexMap<MenuId, Option<MenuItemState>>>>,
+}
+
+impl<G: Clone> Observers<G> {
+    pub(crate) fn new() -> Self {
+        Observers {
+            senders: RefCell::new(Vec::new()),
+            depth: Cell::new(0),
+            origin: Cell::new(ChangeOrigin::Api),
+            recorded: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> Receiver<ChangeEvent<G>> {
+        let (sender, receiver) = mpsc::channel();
+        self.senders.borrow_mut().push(sender);
+        receiver
+    }
+
+    /// Enters a mutation, its changes are recorded if it is the outermost one, `notify`
+    /// is set and someone listens.
+    ///
+    /// The outermost mutation gives the origin of the changes.
+    pub(crate) fn enter(&self, origin: ChangeOrigin, notify: bool) {
+        let depth = self.depth.get();
+        self.depth.set(depth + 1);
+        if depth == 0 {
+            self.origin.set(origin);
+            if notify && !self.senders.borrow().is_empty() {
+                *self.recorded.borrow_mut() = Some(FxIndexMap::default());
+            }
+        }
+    }
+
+    /// Records the state of a menu control before its first change, if changes are recorded.
+    pub(crate) fn record(&self, menu_id: &MenuId, state: impl FnOnce() -> Option<MenuItemState>) {
+        if let Some(recorded) = self.recorded.borrow_mut().as_mut()
+            && !recorded.contains_key(menu_id)
+        {
+            recorded.insert(menu_id.clone(), state());
+        }
+    }
+
+    pub(crate) fn origin(&self) -> ChangeOrigin {
+        self.origin.get()
+    }
+
+    /// Leaves a mutation, returns the recorded states if it was the outermost one.
+    pub(crate) fn leave(&self) -> Option<FxIndexMap<MenuId, Option<MenuItemState>>> {
+        let depth = self.depth.get().saturating_sub(1);
+        self.depth.set(depth);
+        if depth == 0 {
+            self.recorded.borrow_mut().take()
+        } else {
+            None
+        }
+    }
+
+    /// Sends an event, dropping the subscribers whose receiver is gone.
+    pub(crate) fn emit(&self, event: ChangeEvent<G>) {
+        self.senders
+            .borrow_mut()
+            .retain(|sender| sender.send(event.clone()).is_ok());
+    }
+}
+
+// Subscribers belong to the menu manager they subscribed to
+impl<G: Clone> Clone for Observers<G> {
+    fn clone(&self) -> Self {
+        Observers::new()
+    }
+}
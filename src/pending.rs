@@ -0,0 +1,66 @@
+use std::hash::Hash;
+
+use muda::MenuId;
+
+use crate::MenuManager;
+
+/// Click whose check state is not confirmed yet, see [`MenuManager::update_pending`]
+///
+/// Once the action of the click is done, e.g. a backend call, either [`commit`] or
+/// [`rollback`] it. Both enable again the check menus disabled while pending.
+///
+/// [`commit`]: PendingChange::commit
+/// [`rollback`]: PendingChange::rollback
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use = "a pending change must be committed or rolled back"]
+pub struct PendingChange {
+    menu_id: MenuId,
+    checked: Vec<(MenuId, bool)>,
+    enabled: Vec<(MenuId, bool)>,
+}
+
+impl PendingChange {
+    pub(crate) fn new(
+        menu_id: MenuId,
+        checked: Vec<(MenuId, bool)>,
+        enabled: Vec<(MenuId, bool)>,
+    ) -> Self {
+        PendingChange {
+            menu_id,
+            checked,
+            enabled,
+        }
+    }
+
+    /// Gets the menu ID of the clicked check menu.
+    pub fn menu_id(&self) -> &MenuId {
+        &self.menu_id
+    }
+
+    /// Keeps the new check state, returns the menu IDs whose state changed.
+    pub fn commit<G>(self, manager: &mut MenuManager<G>) -> Vec<MenuId>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        manager.batch(|batch| {
+            for (menu_id, enabled) in &self.enabled {
+                batch.set_enabled(menu_id, *enabled);
+            }
+        })
+    }
+
+    /// Puts back the check state from before the click, returns the menu IDs whose state changed.
+    pub fn rollback<G>(self, manager: &mut MenuManager<G>) -> Vec<MenuId>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        manager.batch(|batch| {
+            for (menu_id, checked) in &self.checked {
+                batch.set_checked(menu_id, *checked);
+            }
+            for (menu_id, enabled) in &self.enabled {
+                batch.set_enabled(menu_id, *enabled);
+            }
+        })
+    }
+}
@@ -0,0 +1,25 @@
+/// Desktop platform supported by tray-icon, see [`MenuTemplate::platforms`](crate::MenuTemplate::platforms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Windows,
+    MacOs,
+    /// Linux, and the BSDs where tray-icon uses GTK as well
+    Linux,
+}
+
+impl Platform {
+    /// Gets the platform the application is compiled for.
+    pub const fn current() -> Platform {
+        if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else {
+            Platform::Linux
+        }
+    }
+
+    pub fn is_current(self) -> bool {
+        self == Platform::current()
+    }
+}
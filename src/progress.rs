@@ -0,0 +1,238 @@
+use std::f32::consts::TAU;
+use std::hash::Hash;
+
+use muda::{MenuId, MenuItem};
+
+use crate::icons::{IconCache, IconImage, TRAY_ICON_SIZE};
+use crate::{Error, MenuControl, TrayController};
+
+const TRACK_COLOR: [u8; 4] = [0, 0, 0, 160];
+/// Every percentage fits by default
+const DEFAULT_CACHE_CAPACITY: usize = 101;
+
+/// Shape of the progress drawn onto the tray icon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressStyle {
+    /// Pie filled clockwise from the top, in the bottom-right quarter of the icon
+    #[default]
+    Pie,
+    /// Bar filled from the left, along the bottom edge of the icon
+    Bar,
+}
+
+/// Surfaces the progress of a task in the tray, e.g. a sync or a download
+///
+/// Each [`ProgressReporter::update`] sets the text of a disabled status menu item, the
+/// `progress` variable of the tooltip template (see
+/// [`TrayController::set_tooltip_template`]), and draws the progress onto the tray icon.
+/// [`ProgressReporter::finish`] restores the base icon.
+///
+/// `Icon` doesn't expose its pixels, so the base icon is given as an [`IconImage`]. The
+/// drawn icons are cached by percentage, see [`ProgressReporter::precache`].
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::icons::{self, TRAY_ICON_SIZE};
+/// use tray_controls::{MenuManager, ProgressReporter, ProgressStyle, TrayController};
+/// use tray_icon::TrayIconBuilder;
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let tray = TrayIconBuilder::new().build().unwrap();
+///
+/// let mut progress = ProgressReporter::new("sync_progress", "Syncing")
+///     .with_icon(icons::solid([255, 255, 255, 255], TRAY_ICON_SIZE))
+///     .with_style(ProgressStyle::Bar);
+/// manager.insert(progress.menu_control());
+///
+/// let mut controller = TrayController::new(tray, manager);
+/// controller.set_tooltip_template("MyApp {progress}").unwrap();
+///
+/// progress.update(&mut controller, 42).unwrap();
+/// assert_eq!(progress.menu_item().text(), "Syncing: 42%");
+///
+/// progress.finish(&mut controller).unwrap();
+/// ```
+pub struct ProgressReporter {
+    menu_item: MenuItem,
+    label: String,
+    base: Option<IconImage>,
+    style: ProgressStyle,
+    color: [u8; 4],
+    cache: IconCache<u8>,
+    /// Last reported percentage, `None` when no task is running
+    percent: Option<u8>,
+}
+
+impl ProgressReporter {
+    pub fn new<I: Into<MenuId>>(id: I, label: &str) -> Self {
+        ProgressReporter {
+            menu_item: MenuItem::with_id(id, label, false, None),
+            label: label.to_owned(),
+            base: None,
+            style: ProgressStyle::default(),
+            color: [46, 160, 67, 255],
+            cache: IconCache::new(DEFAULT_CACHE_CAPACITY),
+            percent: None,
+        }
+    }
+
+    /// Sets the icon the progress is drawn onto, restored when finished.
+    ///
+    /// Without it, the progress is drawn onto a transparent icon.
+    pub fn with_icon(mut self, icon: IconImage) -> Self {
+        self.base = Some(icon);
+        self.cache.clear();
+        self
+    }
+
+    pub fn with_style(mut self, style: ProgressStyle) -> Self {
+        self.style = style;
+        self.cache.clear();
+        self
+    }
+
+    /// Sets the RGBA color of the filled progress.
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self.cache.clear();
+        self
+    }
+
+    /// Sets how many drawn icons are cached, all 101 percentages by default.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache.set_capacity(capacity);
+        self
+    }
+
+    /// Draws and converts the icons of all percentages up-front, e.g. before a task starts.
+    ///
+    /// Only as many icons as the cache capacity are kept.
+    pub fn precache(&mut self) -> Result<(), Error> {
+        let frames: Vec<(u8, IconImage)> = (0..=100)
+            .map(|percent| (percent, self.draw(percent)))
+            .collect();
+        self.cache.precache(frames)
+    }
+
+    /// Reports the progress, clamped to 100.
+    ///
+    /// Nothing is updated if the percentage didn't change.
+    pub fn update<G>(
+        &mut self,
+        controller: &mut TrayController<G>,
+        percent: u8,
+    ) -> Result<(), Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let percent = percent.min(100);
+        if self.percent == Some(percent) {
+            return Ok(());
+        }
+        self.percent = Some(percent);
+
+        self.menu_item
+            .set_text(format!("{}: {percent}%", self.label));
+        controller.set_tooltip_var("progress", format!("{percent}%"))?;
+        let icon = self.cache.get_or_convert(percent, || {
+            draw(self.base.as_ref(), self.style, self.color, percent)
+        })?;
+        controller.set_icon(Some(icon))
+    }
+
+    /// Ends the reported task: restores the status text, the tooltip, and the base icon.
+    pub fn finish<G>(&mut self, controller: &mut TrayController<G>) -> Result<(), Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        if self.percent.take().is_none() {
+            return Ok(());
+        }
+
+        self.menu_item.set_text(&self.label);
+        controller.set_tooltip_var("progress", "")?;
+        let icon = match &self.base {
+            Some(base) => Some(base.to_tray_icon()?),
+            None => None,
+        };
+        controller.set_icon(icon)
+    }
+
+    /// Gets the last reported percentage, `None` when no task is running.
+    pub fn percent(&self) -> Option<u8> {
+        self.percent
+    }
+
+    pub fn id(&self) -> &MenuId {
+        self.menu_item.id()
+    }
+
+    pub fn menu_item(&self) -> &MenuItem {
+        &self.menu_item
+    }
+
+    /// Gets the menu control to insert into the menu manager.
+    pub fn menu_control<G>(&self) -> MenuControl<G> {
+        MenuControl::MenuItem(self.menu_item.clone())
+    }
+
+    fn draw(&self, percent: u8) -> IconImage {
+        draw(self.base.as_ref(), self.style, self.color, percent)
+    }
+}
+
+/// Draws the progress onto a copy of the base icon.
+fn draw(base: Option<&IconImage>, style: ProgressStyle, color: [u8; 4], percent: u8) -> IconImage {
+    let mut image = match base {
+        Some(base) => base.clone(),
+        None => IconImage::new(TRAY_ICON_SIZE, TRAY_ICON_SIZE),
+    };
+
+    let fraction = f32::from(percent) / 100.0;
+    match style {
+        ProgressStyle::Pie => draw_pie(&mut image, fraction, color),
+        ProgressStyle::Bar => draw_bar(&mut image, fraction, color),
+    }
+    image
+}
+
+fn draw_pie(image: &mut IconImage, fraction: f32, color: [u8; 4]) {
+    let (width, height) = (image.width(), image.height());
+    let radius = width.min(height) as f32 / 4.0;
+    let center_x = width as f32 - radius;
+    let center_y = height as f32 - radius;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+
+            // Angle clockwise from the top, in turns
+            let angle = dx.atan2(-dy).rem_euclid(TAU) / TAU;
+            let pixel_color = match angle < fraction {
+                true => color,
+                false => TRACK_COLOR,
+            };
+            image.blend_pixel(x, y, pixel_color);
+        }
+    }
+}
+
+fn draw_bar(image: &mut IconImage, fraction: f32, color: [u8; 4]) {
+    let (width, height) = (image.width(), image.height());
+    let bar_height = (height / 6).max(1);
+    let filled = (width as f32 * fraction).round() as u32;
+
+    for y in height.saturating_sub(bar_height)..height {
+        for x in 0..width {
+            let pixel_color = match x < filled {
+                true => color,
+                false => TRACK_COLOR,
+            };
+            image.blend_pixel(x, y, pixel_color);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use muda::MenuId;
+
+/// Menu control exported to another surface, e.g. a taskbar jump list or a dock menu
+///
+/// See [`MenuManager::quick_actions`](crate::MenuManager::quick_actions).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickAction {
+    pub menu_id: MenuId,
+    /// Label without mnemonic markers
+    pub text: String,
+    pub enabled: bool,
+}
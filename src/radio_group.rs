@@ -0,0 +1,164 @@
+use std::hash::Hash;
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, IsMenuItem, MenuId};
+
+use crate::{CheckMenuKind, Error, MenuControl, MenuManager};
+
+/// Typestate of a [`RadioGroupBuilder`] whose default radio is not chosen yet
+pub struct NoDefault;
+
+/// Typestate of a [`RadioGroupBuilder`] whose default radio is chosen
+pub struct WithDefault(MenuId);
+
+/// Radio group whose default radio can only be one of its own radios
+///
+/// The default radio is added with [`RadioGroupBuilder::default_radio`], which can only be
+/// called once, so a default radio menu ID outside of the group can't be expressed.
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, RadioGroupBuilder};
+/// use tray_icon::menu::{CheckMenuItem, Menu, MenuId};
+///
+/// let mut manager = MenuManager::<&str>::new();
+///
+/// let theme = RadioGroupBuilder::new("theme_group")
+///     .default_radio(CheckMenuItem::with_id("system", "System", true, true, None))
+///     .radio(CheckMenuItem::with_id("light", "Light", true, false, None))
+///     .radio(CheckMenuItem::with_id("dark", "Dark", true, false, None));
+///
+/// let menu = Menu::with_items(&theme.items()).unwrap();
+/// theme.register(&mut manager);
+///
+/// assert_eq!(manager.group_default(&"theme_group"), Some(&MenuId::new("system")));
+/// ```
+///
+/// A group default can't be set twice:
+/// ```compile_fail
+/// use tray_controls::RadioGroupBuilder;
+/// use tray_icon::menu::CheckMenuItem;
+///
+/// RadioGroupBuilder::new("theme_group")
+///     .default_radio(CheckMenuItem::with_id("light", "Light", true, true, None))
+///     .default_radio(CheckMenuItem::with_id("dark", "Dark", true, false, None));
+/// ```
+pub struct RadioGroupBuilder<G, D = NoDefault> {
+    group: G,
+    radios: Vec<Rc<CheckMenuItem>>,
+    default: D,
+}
+
+impl<G> RadioGroupBuilder<G, NoDefault>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new(group: G) -> Self {
+        RadioGroupBuilder {
+            group,
+            radios: Vec::new(),
+            default: NoDefault,
+        }
+    }
+
+    /// Adds the radio checked when the checked radio of the group is unchecked.
+    pub fn default_radio(self, radio: CheckMenuItem) -> RadioGroupBuilder<G, WithDefault> {
+        let default_id = radio.id().clone();
+        let mut radios = self.radios;
+        radios.push(Rc::new(radio));
+
+        RadioGroupBuilder {
+            group: self.group,
+            radios,
+            default: WithDefault(default_id),
+        }
+    }
+}
+
+impl<G, D> RadioGroupBuilder<G, D>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn radio(mut self, radio: CheckMenuItem) -> Self {
+        self.radios.push(Rc::new(radio));
+        self
+    }
+
+    /// Gets the radio buttons, e.g. for `Menu::with_items` or `Submenu::with_items`.
+    pub fn items(&self) -> Vec<&dyn IsMenuItem> {
+        self.radios
+            .iter()
+            .map(|radio| radio.as_ref() as &dyn IsMenuItem)
+            .collect()
+    }
+
+    fn menu_controls(&self) -> impl Iterator<Item = MenuControl<G>> + '_ {
+        self.radios.iter().map(|radio| {
+            MenuControl::CheckMenu(CheckMenuKind::Radio(
+                radio.clone(),
+                None,
+                self.group.clone(),
+            ))
+        })
+    }
+}
+
+impl<G> RadioGroupBuilder<G, NoDefault>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Registers the radio buttons in the menu manager, the group has no default radio.
+    pub fn register(&self, manager: &mut MenuManager<G>) {
+        self.menu_controls().for_each(|menu_control| {
+            manager.insert(menu_control);
+        });
+    }
+
+    /// Registers the radio buttons in the menu manager, and appends them to a registered parent menu.
+    pub fn insert_into(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+    ) -> Result<(), Error> {
+        for menu_control in self.menu_controls() {
+            manager.insert_into(parent_id, menu_control, None)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<G> RadioGroupBuilder<G, WithDefault>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Registers the radio buttons and the default radio in the menu manager.
+    pub fn register(&self, manager: &mut MenuManager<G>) {
+        self.menu_controls().for_each(|menu_control| {
+            manager.insert(menu_control);
+        });
+        manager
+            .set_group_default(self.group.clone(), self.default.0.clone())
+            .expect("the default radio was just registered");
+    }
+
+    /// Registers the radio buttons and the default radio in the menu manager, and appends
+    /// the radio buttons to a registered parent menu.
+    pub fn insert_into(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+    ) -> Result<(), Error> {
+        for menu_control in self.menu_controls() {
+            manager.insert_into(parent_id, menu_control, None)?;
+        }
+        manager.set_group_default(self.group.clone(), self.default.0.clone())?;
+
+        Ok(())
+    }
+
+    /// Gets the menu ID of the default radio.
+    pub fn default_id(&self) -> &MenuId {
+        &self.default.0
+    }
+}
@@ -0,0 +1,157 @@
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+use muda::{CheckMenuItem, IsMenuItem, MenuId};
+
+use crate::{CheckMenuKind, Error, MenuControl, MenuManager};
+
+/// Radio group generated from a numeric range
+///
+/// One radio button is created per step of the range, labelled by the `label` callback.
+/// Selections are converted back into the numeric value with [`RadioRange::value_of`].
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, RadioRange};
+/// use tray_icon::menu::{Menu, MenuId};
+///
+/// let mut manager = MenuManager::<&str>::new();
+///
+/// // 0%, 25%, 50%, 75%, 100%
+/// let opacity = RadioRange::new("opacity_group", 0..=100, 25, |v| format!("{v}%"))
+///     .with_default(100);
+/// opacity.register(&mut manager);
+///
+/// let menu = Menu::with_items(&opacity.items()).unwrap();
+/// assert_eq!(opacity.selected_value(), Some(100));
+///
+/// // Handle menu clicks - the checked radio is converted back into its value
+/// let click_menu_id = MenuId::new("");
+///
+/// manager.update(&click_menu_id, |menu| {
+///     if let Some(value) = menu.and_then(|menu| opacity.value_of(menu.id())) {
+///         println!("Opacity: {value}%");
+///     }
+/// });
+/// ```
+pub struct RadioRange<G> {
+    group: G,
+    items: Vec<(Rc<CheckMenuItem>, i64)>,
+    default_id: Option<MenuId>,
+}
+
+impl<G> RadioRange<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new(
+        group: G,
+        range: RangeInclusive<i64>,
+        step: usize,
+        label: impl Fn(i64) -> String,
+    ) -> Self {
+        let items = range
+            .step_by(step.max(1))
+            .map(|value| {
+                let check_menu = CheckMenuItem::new(label(value), true, false, None);
+                (Rc::new(check_menu), value)
+            })
+            .collect();
+
+        RadioRange {
+            group,
+            items,
+            default_id: None,
+        }
+    }
+
+    /// Sets the default value, which is selected initially and when all radios are unchecked.
+    pub fn with_default(mut self, value: i64) -> Self {
+        self.default_id = self.id_of(value).cloned();
+        self.select(value);
+        self
+    }
+
+    /// Registers the radio buttons in the menu manager.
+    pub fn register(&self, manager: &mut MenuManager<G>) {
+        self.menu_controls().for_each(|menu_control| {
+            manager.insert(menu_control);
+        });
+        self.register_default(manager)
+            .expect("the default radio was just registered");
+    }
+
+    /// Registers the radio buttons in the menu manager, and appends them to a registered parent menu.
+    pub fn insert_into(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+    ) -> Result<(), Error> {
+        for menu_control in self.menu_controls() {
+            manager.insert_into(parent_id, menu_control, None)?;
+        }
+        self.register_default(manager)
+    }
+
+    /// Gets the radio buttons, e.g. for `Menu::with_items` or `Submenu::with_items`.
+    pub fn items(&self) -> Vec<&dyn IsMenuItem> {
+        self.items
+            .iter()
+            .map(|(check_menu, _)| check_menu.as_ref() as &dyn IsMenuItem)
+            .collect()
+    }
+
+    /// Gets the numeric value of a radio button.
+    pub fn value_of(&self, menu_id: &MenuId) -> Option<i64> {
+        self.items
+            .iter()
+            .find(|(check_menu, _)| check_menu.id() == menu_id)
+            .map(|(_, value)| *value)
+    }
+
+    /// Gets the menu ID of the radio button of a value.
+    pub fn id_of(&self, value: i64) -> Option<&MenuId> {
+        self.items
+            .iter()
+            .find(|(_, v)| *v == value)
+            .map(|(check_menu, _)| check_menu.id())
+    }
+
+    /// Gets the value of the checked radio button.
+    pub fn selected_value(&self) -> Option<i64> {
+        self.items
+            .iter()
+            .find(|(check_menu, _)| check_menu.is_checked())
+            .map(|(_, value)| *value)
+    }
+
+    /// Checks the radio button of a value and unchecks the others, returns `false` if there is none.
+    pub fn select(&self, value: i64) -> bool {
+        if self.id_of(value).is_none() {
+            return false;
+        }
+
+        self.items
+            .iter()
+            .for_each(|(check_menu, v)| check_menu.set_checked(*v == value));
+        true
+    }
+
+    fn register_default(&self, manager: &mut MenuManager<G>) -> Result<(), Error> {
+        match &self.default_id {
+            Some(default_id) => manager.set_group_default(self.group.clone(), default_id.clone()),
+            None => Ok(()),
+        }
+    }
+
+    fn menu_controls(&self) -> impl Iterator<Item = MenuControl<G>> + '_ {
+        self.items.iter().map(|(check_menu, _)| {
+            MenuControl::CheckMenu(CheckMenuKind::Radio(
+                check_menu.clone(),
+                None,
+                self.group.clone(),
+            ))
+        })
+    }
+}
@@ -0,0 +1,191 @@
+use std::hash::Hash;
+
+use muda::{MenuId, MenuItem, PredefinedMenuItem, Submenu};
+
+use crate::{Error, MenuControl, MenuManager, MenuParent, SubmenuBinding};
+
+/// Action produced by [`RecentItems::handle`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecentAction {
+    /// A recent entry was clicked
+    Selected(String),
+    /// The "Clear Recent" entry was clicked and the entries were removed
+    Cleared,
+}
+
+type OnChange = Box<dyn Fn(&[String])>;
+
+struct RecentEntry {
+    menu_id: MenuId,
+    label: String,
+}
+
+/// Recent-items (MRU) submenu, e.g. "Recent files" or "Recent connections"
+///
+/// The most recent value is shown first, duplicates are moved to the top and the
+/// list is truncated to `max_len`. A separator and a "Clear Recent" entry are kept
+/// at the end of the submenu. All entries are registered in the [`MenuManager`].
+///
+/// Menu IDs are built from the `id_prefix`: entries use `{id_prefix}{value}` and
+/// the "Clear Recent" entry uses `{id_prefix}clear`.
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, RecentAction, RecentItems};
+/// use tray_icon::menu::Submenu;
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let submenu = Submenu::new("Recent Files", true);
+///
+/// let mut recent = RecentItems::new(&mut manager, submenu, "recent:", 5).unwrap();
+/// recent.set_on_change(|values| println!("persist: {values:?}"));
+/// recent.push(&mut manager, "notes.txt").unwrap();
+///
+/// let click_menu_id = recent.clear_menu_id().clone();
+/// assert_eq!(recent.handle(&mut manager, &click_menu_id), Some(RecentAction::Cleared));
+/// ```
+pub struct RecentItems<G> {
+    binding: SubmenuBinding<RecentEntry, G>,
+    id_prefix: String,
+    max_len: usize,
+    values: Vec<String>,
+    clear_item: MenuItem,
+    on_change: Option<OnChange>,
+}
+
+impl<G> RecentItems<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Creates the component and appends the separator and "Clear Recent" entry to the submenu.
+    pub fn new(
+        manager: &mut MenuManager<G>,
+        submenu: Submenu,
+        id_prefix: &str,
+        max_len: usize,
+    ) -> Result<Self, Error> {
+        let clear_item = MenuItem::with_id(
+            MenuId::new(format!("{id_prefix}clear")),
+            "Clear Recent",
+            false,
+            None,
+        );
+
+        submenu.append(&PredefinedMenuItem::separator())?;
+        manager.insert_parent(MenuParent::Submenu(submenu.clone()));
+        manager.insert_into(
+            submenu.id(),
+            MenuControl::MenuItem(clear_item.clone()),
+            None,
+        )?;
+
+        Ok(RecentItems {
+            binding: SubmenuBinding::new(submenu, |entry: &RecentEntry| {
+                (entry.menu_id.clone(), entry.label.clone())
+            }),
+            id_prefix: id_prefix.to_owned(),
+            max_len,
+            values: Vec::new(),
+            clear_item,
+            on_change: None,
+        })
+    }
+
+    /// Sets the persistence hook, called with the values whenever they change.
+    pub fn set_on_change(&mut self, on_change: impl Fn(&[String]) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Replaces the values (e.g. restored from persistence) without calling the persistence hook.
+    pub fn load<I, S>(&mut self, manager: &mut MenuManager<G>, values: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.values.clear();
+        for value in values {
+            let value = value.into();
+            if !self.values.contains(&value) {
+                self.values.push(value);
+            }
+        }
+        self.values.truncate(self.max_len);
+
+        self.sync(manager)
+    }
+
+    /// Adds a value as the most recent entry.
+    pub fn push(&mut self, manager: &mut MenuManager<G>, value: &str) -> Result<(), Error> {
+        self.values.retain(|v| v != value);
+        self.values.insert(0, value.to_owned());
+        self.values.truncate(self.max_len);
+
+        self.sync(manager)?;
+        self.notify();
+        Ok(())
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self, manager: &mut MenuManager<G>) -> Result<(), Error> {
+        self.values.clear();
+
+        self.sync(manager)?;
+        self.notify();
+        Ok(())
+    }
+
+    /// Handles a menu click, returns `None` if the menu ID doesn't belong to this component.
+    ///
+    /// Clicking "Clear Recent" removes all entries.
+    pub fn handle(
+        &mut self,
+        manager: &mut MenuManager<G>,
+        menu_id: &MenuId,
+    ) -> Option<RecentAction> {
+        if menu_id == self.clear_item.id() {
+            return self.clear(manager).ok().map(|_| RecentAction::Cleared);
+        }
+
+        self.value_of(menu_id)
+            .map(|value| RecentAction::Selected(value.to_owned()))
+    }
+
+    /// Gets the value of a recent entry from its menu ID.
+    pub fn value_of(&self, menu_id: &MenuId) -> Option<&str> {
+        let value = menu_id.0.strip_prefix(&self.id_prefix)?;
+        self.values
+            .iter()
+            .find(|v| v.as_str() == value)
+            .map(String::as_str)
+    }
+
+    /// Gets the values, the most recent first.
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    pub fn clear_menu_id(&self) -> &MenuId {
+        self.clear_item.id()
+    }
+
+    fn sync(&mut self, manager: &mut MenuManager<G>) -> Result<(), Error> {
+        let entries: Vec<RecentEntry> = self
+            .values
+            .iter()
+            .map(|value| RecentEntry {
+                menu_id: MenuId::new(format!("{}{value}", self.id_prefix)),
+                label: value.clone(),
+            })
+            .collect();
+
+        self.binding.sync(manager, &entries)?;
+        self.clear_item.set_enabled(!self.values.is_empty());
+        Ok(())
+    }
+
+    fn notify(&self) {
+        if let Some(on_change) = &self.on_change {
+            on_change(&self.values);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::hash::Hash;
+
+use tray_icon::{TrayIconEvent, menu::MenuEvent};
+
+use crate::{FxIndexMap, MenuControl, MenuManager, TrayAction, TrayController};
+
+/// Several tray icons, e.g. one per monitored account, with their events routed by ID
+///
+/// Each [`TrayController`] keeps its own [`MenuManager`]. Menu controls shown by several
+/// tray icons (the same `Menu` can be set on all of them) are registered once in the
+/// shared menu manager instead.
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{MenuManager, TrayController, TrayRegistry};
+/// use tray_icon::TrayIconBuilder;
+///
+/// let mut registry = TrayRegistry::<&str, ()>::new();
+///
+/// for account in ["work", "home"] {
+///     let tray = TrayIconBuilder::new().with_id(account).build().unwrap();
+///     registry.insert(account, TrayController::new(tray, MenuManager::new()));
+/// }
+///
+/// // In the tray icon event handler
+/// # let event: tray_icon::TrayIconEvent = unimplemented!();
+/// if let Some((account, action)) = registry.handle_tray_event(&event) {
+///     println!("{account}: {action:?}");
+/// }
+/// ```
+pub struct TrayRegistry<K, G>
+where
+    K: Clone + Eq + Hash,
+    G: Clone + Eq + Hash + PartialEq,
+{
+    controllers: FxIndexMap<K, TrayController<G>>,
+    shared_manager: MenuManager<G>,
+}
+
+impl<K, G> Default for TrayRegistry<K, G>
+where
+    K: Clone + Eq + Hash,
+    G: Clone + Eq + Hash + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, G> TrayRegistry<K, G>
+where
+    K: Clone + Eq + Hash,
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new() -> Self {
+        TrayRegistry {
+            controllers: FxIndexMap::default(),
+            shared_manager: MenuManager::new(),
+        }
+    }
+
+    /// Uses a menu manager for the menu controls shared by the tray icons.
+    pub fn with_shared_manager(mut self, manager: MenuManager<G>) -> Self {
+        self.shared_manager = manager;
+        self
+    }
+
+    /// Adds a tray controller, returns the previous one of the key.
+    pub fn insert(&mut self, key: K, controller: TrayController<G>) -> Option<TrayController<G>> {
+        self.controllers.insert(key, controller)
+    }
+
+    /// Removes a tray controller, which removes its tray icon once dropped.
+    pub fn remove(&mut self, key: &K) -> Option<TrayController<G>> {
+        self.controllers.shift_remove(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&TrayController<G>> {
+        self.controllers.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut TrayController<G>> {
+        self.controllers.get_mut(key)
+    }
+
+    /// Iterates over the tray controllers in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &TrayController<G>)> {
+        self.controllers.iter()
+    }
+
+    pub fn shared_manager(&self) -> &MenuManager<G> {
+        &self.shared_manager
+    }
+
+    pub fn shared_manager_mut(&mut self) -> &mut MenuManager<G> {
+        &mut self.shared_manager
+    }
+
+    /// Gets the key of the tray icon that emitted a tray icon event.
+    pub fn key_of(&self, event: &TrayIconEvent) -> Option<&K> {
+        self.controllers
+            .iter()
+            .find(|(_, controller)| controller.tray().id() == event.id())
+            .map(|(key, _)| key)
+    }
+
+    /// Routes a tray icon event to the controller of its tray icon.
+    ///
+    /// See [`TrayController::handle_tray_event`].
+    pub fn handle_tray_event(&mut self, event: &TrayIconEvent) -> Option<(K, TrayAction)> {
+        let (key, controller) = self
+            .controllers
+            .iter_mut()
+            .find(|(_, controller)| controller.tray().id() == event.id())?;
+
+        controller
+            .handle_tray_event(event)
+            .map(|action| (key.clone(), action))
+    }
+
+    /// Routes a menu event to the controller whose menu manager registers the clicked menu,
+    /// or else to the shared menu manager, and callback the key of the controller
+    /// (`None` for the shared menu manager) and the menu control.
+    ///
+    /// Returns `false` if no menu manager registers the clicked menu.
+    pub fn handle_menu_event(
+        &mut self,
+        event: &MenuEvent,
+        callback: impl Fn(Option<&K>, Option<&MenuControl<G>>),
+    ) -> bool {
+        if let Some((key, controller)) = self.controllers.iter_mut().find(|(_, controller)| {
+            controller
+                .menu_manager()
+                .get_menu_item_from_id(event.id())
+                .is_some()
+        }) {
+            controller.handle_menu_event(event, |menu_control| callback(Some(key), menu_control));
+            return true;
+        }
+
+        if self
+            .shared_manager
+            .get_menu_item_from_id(event.id())
+            .is_none()
+        {
+            return false;
+        }
+
+        self.shared_manager
+            .update(event.id(), |menu_control| callback(None, menu_control));
+        true
+    }
+}
@@ -0,0 +1,215 @@
+use std::hash::Hash;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use muda::MenuId;
+
+use crate::{Error, MenuAction, TrayAction, TrayController, TrayEvent};
+
+type MenuHandler<G> = Box<dyn FnMut(&mut TrayController<G>, &MenuId, Option<&MenuAction>)>;
+type TrayHandler<G> = Box<dyn FnMut(&mut TrayController<G>, TrayAction)>;
+type PeriodicHandler<G> = Box<dyn FnMut(&mut TrayController<G>)>;
+
+struct Periodic<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    interval: Duration,
+    next_due: Instant,
+    handler: PeriodicHandler<G>,
+}
+
+/// Event pump and periodic heartbeat of a tray controller (**requires the `runtime` feature**)
+///
+/// It receives the menu and tray icon events, updates the menu state, runs the
+/// declarative actions, and dispatches to the handlers. Each [`TrayRuntime::pump`] also
+/// ticks the scheduled actions, the timers, the attention icon and the periodic
+/// handlers, e.g. a [`StatusItem`](crate::StatusItem) refresh.
+///
+/// It is not tied to an event loop: the `wake` callback is called from the platform
+/// event handler, e.g. to send a user event through a winit or tao `EventLoopProxy`.
+/// The loop then calls [`TrayRuntime::pump`], and waits until the instant it returns.
+///
+/// NOTE: It installs [`TrayController::set_event_handler`], which can only be set once.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use tray_controls::{MenuAction, MenuManager, StatusItem, TrayController, TrayRuntime};
+/// use tray_icon::TrayIconBuilder;
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let status = StatusItem::new("cpu_status", "CPU: -");
+/// manager.insert(status.menu_control());
+///
+/// let tray = TrayIconBuilder::new().build().unwrap();
+/// let controller = TrayController::new(tray, manager);
+///
+/// // e.g. `move || { let _ = proxy.send_event(UserEvent::Wake); }` with winit
+/// let mut runtime = TrayRuntime::new(controller, || {});
+/// runtime.add_menu_handler(|_, menu_id, action| {
+///     if let Some(MenuAction::Custom(name)) = action {
+///         println!("{menu_id:?} runs {name}");
+///     }
+/// });
+/// runtime.add_periodic(Duration::from_secs(2), move |_| {
+///     status.set_value(format!("CPU: {}%", 42));
+/// });
+///
+/// // In your event loop, when woken or when the returned instant is reached
+/// let next_wake = runtime.pump();
+/// ```
+pub struct TrayRuntime<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    controller: TrayController<G>,
+    events: Receiver<TrayEvent>,
+    menu_handlers: Vec<MenuHandler<G>>,
+    tray_handlers: Vec<TrayHandler<G>>,
+    periodic: Vec<Periodic<G>>,
+}
+
+impl<G> TrayRuntime<G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    /// Installs the tray event handler, `wake` is called after each event is received.
+    pub fn new(controller: TrayController<G>, wake: impl Fn() + Send + Sync + 'static) -> Self {
+        let (sender, events) = mpsc::channel();
+        TrayController::<G>::set_event_handler(move |event| {
+            if sender.send(event).is_ok() {
+                wake();
+            }
+        });
+
+        TrayRuntime {
+            controller,
+            events,
+            menu_handlers: Vec::new(),
+            tray_handlers: Vec::new(),
+            periodic: Vec::new(),
+        }
+    }
+
+    /// Adds a handler of menu clicks and expired timers, called after the menu state is updated.
+    ///
+    /// The action is the outcome of the declarative action of the menu control, `None`
+    /// if it has none. Failed actions are reported to the error handler of the menu manager.
+    pub fn add_menu_handler(
+        &mut self,
+        handler: impl FnMut(&mut TrayController<G>, &MenuId, Option<&MenuAction>) + 'static,
+    ) {
+        self.menu_handlers.push(Box::new(handler));
+    }
+
+    /// Adds a handler of the tray icon actions, see [`TrayController::bind`].
+    pub fn add_tray_handler(
+        &mut self,
+        handler: impl FnMut(&mut TrayController<G>, TrayAction) + 'static,
+    ) {
+        self.tray_handlers.push(Box::new(handler));
+    }
+
+    /// Adds a handler run every `interval`, e.g. to refresh a status or animate the icon.
+    ///
+    /// It first runs on the next [`TrayRuntime::pump`].
+    pub fn add_periodic(
+        &mut self,
+        interval: Duration,
+        handler: impl FnMut(&mut TrayController<G>) + 'static,
+    ) {
+        self.periodic.push(Periodic {
+            interval,
+            next_due: Instant::now(),
+            handler: Box::new(handler),
+        });
+    }
+
+    /// Dispatches the received events and runs what is due.
+    ///
+    /// Returns when to pump next, `None` if only an event can change anything.
+    pub fn pump(&mut self) -> Option<Instant> {
+        while let Ok(event) = self.events.try_recv() {
+            self.dispatch(event);
+        }
+
+        self.tick()
+    }
+
+    fn dispatch(&mut self, event: TrayEvent) {
+        match event {
+            TrayEvent::Menu(event) => {
+                self.controller.handle_menu_event(&event, |_| {});
+                let action = self.controller.menu_manager_mut().run_action(event.id());
+                self.dispatch_menu(event.id(), action);
+            }
+            TrayEvent::Tray(event) => {
+                if let Some(action) = self.controller.handle_tray_event(&event) {
+                    for handler in &mut self.tray_handlers {
+                        handler(&mut self.controller, action);
+                    }
+                }
+            }
+        }
+    }
+
+    fn dispatch_menu(&mut self, menu_id: &MenuId, action: Option<Result<MenuAction, Error>>) {
+        let action = match action {
+            Some(Ok(action)) => Some(action),
+            Some(Err(e)) => {
+                self.controller.menu_manager().report(Err(e));
+                None
+            }
+            None => None,
+        };
+
+        for handler in &mut self.menu_handlers {
+            handler(&mut self.controller, menu_id, action.as_ref());
+        }
+    }
+
+    fn tick(&mut self) -> Option<Instant> {
+        let manager = self.controller.menu_manager_mut();
+        manager.tick();
+        for (menu_id, action) in manager.tick_timers() {
+            self.dispatch_menu(&menu_id, Some(action));
+        }
+
+        let next_flash = match self.controller.tick_attention() {
+            Ok(next_flash) => next_flash,
+            Err(e) => {
+                self.controller.menu_manager().report(Err(e));
+                None
+            }
+        };
+
+        let now = Instant::now();
+        for periodic in &mut self.periodic {
+            if periodic.next_due <= now {
+                (periodic.handler)(&mut self.controller);
+                periodic.next_due = now + periodic.interval;
+            }
+        }
+
+        self.periodic
+            .iter()
+            .map(|periodic| periodic.next_due)
+            .chain(self.controller.menu_manager().next_schedule())
+            .chain(next_flash)
+            .min()
+    }
+
+    pub fn controller(&self) -> &TrayController<G> {
+        &self.controller
+    }
+
+    pub fn controller_mut(&mut self) -> &mut TrayController<G> {
+        &mut self.controller
+    }
+
+    /// Gets the controller back, the tray events received from then on are dropped.
+    pub fn into_controller(self) -> TrayController<G> {
+        self.controller
+    }
+}
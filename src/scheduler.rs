@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use muda::MenuId;
+
+/// When a scheduled action runs, see [`MenuManager::schedule`](crate::MenuManager::schedule)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Once, at an instant
+    At(Instant),
+    /// Once, after a delay from now
+    After(Duration),
+    /// Repeatedly, every interval from now
+    Every(Duration),
+}
+
+/// Change made by a scheduled action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledAction {
+    Check,
+    Uncheck,
+    Toggle,
+    Enable,
+    Disable,
+}
+
+/// Key of a scheduled action, see [`MenuManager::cancel_schedule`](crate::MenuManager::cancel_schedule)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScheduleId(u64);
+
+#[derive(Clone)]
+pub(crate) struct Task {
+    pub(crate) id: ScheduleId,
+    pub(crate) menu_id: MenuId,
+    pub(crate) action: ScheduledAction,
+    pub(crate) due: Instant,
+    pub(crate) interval: Option<Duration>,
+}
+
+/// Scheduled actions of a menu manager
+#[derive(Clone, Default)]
+pub(crate) struct Scheduler {
+    tasks: Vec<Task>,
+    next_id: u64,
+}
+
+impl Scheduler {
+    pub(crate) fn schedule(
+        &mut self,
+        menu_id: MenuId,
+        schedule: Schedule,
+        action: ScheduledAction,
+    ) -> ScheduleId {
+        let now = Instant::now();
+        let (due, interval) = match schedule {
+            Schedule::At(at) => (at, None),
+            Schedule::After(delay) => (now + delay, None),
+            Schedule::Every(interval) => (now + interval, Some(interval)),
+        };
+
+        let id = ScheduleId(self.next_id);
+        self.next_id += 1;
+        self.tasks.push(Task {
+            id,
+            menu_id,
+            action,
+            due,
+            interval,
+        });
+        id
+    }
+
+    pub(crate) fn cancel(&mut self, id: ScheduleId) -> bool {
+        let len = self.tasks.len();
+        self.tasks.retain(|task| task.id != id);
+        self.tasks.len() != len
+    }
+
+    pub(crate) fn cancel_menu(&mut self, menu_id: &MenuId) {
+        self.tasks.retain(|task| task.menu_id != *menu_id);
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.tasks.shrink_to_fit();
+    }
+
+    pub(crate) fn next_due(&self) -> Option<Instant> {
+        self.tasks.iter().map(|task| task.due).min()
+    }
+
+    /// Takes the tasks due at `now` in due order, repeating tasks are rescheduled.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<Task> {
+        let mut due: Vec<Task> = self
+            .tasks
+            .iter()
+            .filter(|task| task.due <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|task| task.due);
+
+        self.tasks.retain_mut(|task| {
+            if task.due > now {
+                return true;
+            }
+
+            match task.interval {
+                // A late tick runs a repeating action once, not once per missed interval
+                Some(interval) if !interval.is_zero() => {
+                    while task.due <= now {
+                        task.due += interval;
+                    }
+                    true
+                }
+                _ => false,
+            }
+        });
+
+        due
+    }
+}
@@ -0,0 +1,74 @@
+use muda::MenuId;
+
+/// Menu IDs generated under a scope, e.g. `devices/usb0/eject`
+///
+/// Scopes and actions are joined with [`ScopedIds::SEPARATOR`]. A clicked menu ID is parsed
+/// back with [`ScopedIds::action`] for a known scope, or with [`ScopedIds::split`].
+///
+/// # Example
+/// ```
+/// use tray_controls::ScopedIds;
+/// use tray_icon::menu::MenuId;
+///
+/// let usb0 = ScopedIds::new("devices").child("usb0");
+/// let eject = usb0.id("eject");
+/// assert_eq!(eject, MenuId::new("devices/usb0/eject"));
+///
+/// // Handle menu clicks
+/// let click_menu_id = eject;
+///
+/// assert_eq!(usb0.action(&click_menu_id), Some("eject"));
+/// assert_eq!(ScopedIds::split(&click_menu_id), Some(("devices/usb0", "eject")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScopedIds {
+    scope: String,
+}
+
+impl ScopedIds {
+    pub const SEPARATOR: char = '/';
+
+    pub fn new(scope: impl Into<String>) -> Self {
+        ScopedIds {
+            scope: scope.into(),
+        }
+    }
+
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    /// Creates a nested scope, e.g. `devices/usb0` under `devices`.
+    pub fn child(&self, segment: &str) -> ScopedIds {
+        ScopedIds::new(self.join(segment))
+    }
+
+    /// Gets the menu ID of an action in the scope.
+    pub fn id(&self, action: &str) -> MenuId {
+        MenuId::new(self.join(action))
+    }
+
+    /// Checks if a menu ID is in the scope, including nested scopes.
+    pub fn contains(&self, menu_id: &MenuId) -> bool {
+        self.action(menu_id).is_some()
+    }
+
+    /// Gets the action of a menu ID in the scope, `None` if it belongs to another scope.
+    ///
+    /// For a nested scope, the action keeps the nested segments, e.g. `usb0/eject` in `devices`.
+    pub fn action<'a>(&self, menu_id: &'a MenuId) -> Option<&'a str> {
+        menu_id
+            .as_ref()
+            .strip_prefix(self.scope.as_str())
+            .and_then(|rest| rest.strip_prefix(Self::SEPARATOR))
+    }
+
+    /// Splits a menu ID into its scope and its action, at the last separator.
+    pub fn split(menu_id: &MenuId) -> Option<(&str, &str)> {
+        menu_id.as_ref().rsplit_once(Self::SEPARATOR)
+    }
+
+    fn join(&self, segment: &str) -> String {
+        format!("{}{}{segment}", self.scope, Self::SEPARATOR)
+    }
+}
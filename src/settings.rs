@@ -0,0 +1,212 @@
+use std::hash::Hash;
+
+use muda::MenuId;
+
+use crate::{ChangeOrigin, MenuManager};
+
+/// Gets the index of the radio standing for the value of a field.
+type GetRadio<S> = Box<dyn Fn(&S) -> Option<usize>>;
+/// Sets a field to the value of a radio.
+type SetRadio<S> = Box<dyn Fn(&mut S, usize)>;
+
+enum FieldBinding<S> {
+    CheckBox {
+        menu_id: MenuId,
+        get: fn(&S) -> bool,
+        set: fn(&mut S, bool),
+    },
+    Radio {
+        menu_ids: Vec<MenuId>,
+        get: GetRadio<S>,
+        set: SetRadio<S>,
+    },
+}
+
+/// Two-way binding between the fields of a settings struct and check menus
+///
+/// A `bool` field maps to a checkbox, and a field with a few possible values (e.g. an enum)
+/// maps to the radios of a radio group. [`SettingsBinding::sync_to_menu`] checks the menu
+/// controls from the settings, and [`SettingsBinding::apply_action`] writes a click back.
+///
+/// # Example
+/// ```
+/// use std::rc::Rc;
+/// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, SettingsBinding};
+/// use tray_icon::menu::{CheckMenuItem, MenuId};
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Theme {
+///     Light,
+///     Dark,
+/// }
+///
+/// struct Settings {
+///     autostart: bool,
+///     theme: Theme,
+/// }
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let autostart = CheckMenuItem::with_id("autostart", "Autostart", true, false, None);
+/// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(Rc::new(autostart))));
+/// for id in ["light", "dark"] {
+///     let radio = CheckMenuItem::with_id(id, id, true, false, None);
+///     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(Rc::new(radio), None, "theme")));
+/// }
+///
+/// let binding = SettingsBinding::new()
+///     .checkbox(
+///         MenuId::new("autostart"),
+///         |settings: &Settings| settings.autostart,
+///         |settings, checked| settings.autostart = checked,
+///     )
+///     .radio(
+///         [(MenuId::new("light"), Theme::Light), (MenuId::new("dark"), Theme::Dark)],
+///         |settings| settings.theme,
+///         |settings, theme| settings.theme = theme,
+///     );
+///
+/// let mut settings = Settings { autostart: true, theme: Theme::Dark };
+/// binding.sync_to_menu(&mut manager, &settings);
+/// let state = manager.state();
+/// assert_eq!(state.get(&MenuId::new("dark")).unwrap().is_checked(), Some(true));
+///
+/// // In the menu event handler, after `MenuManager::update`
+/// manager.get_menu_item_from_id(&MenuId::new("light")).unwrap().set_checked(true);
+/// manager.update(&MenuId::new("light"), |_| {});
+/// assert!(binding.apply_action(&manager, &mut settings, &MenuId::new("light")));
+/// assert!(settings.theme == Theme::Light);
+/// ```
+pub struct SettingsBinding<S> {
+    fields: Vec<FieldBinding<S>>,
+}
+
+impl<S> Default for SettingsBinding<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> SettingsBinding<S> {
+    pub fn new() -> Self {
+        SettingsBinding { fields: Vec::new() }
+    }
+
+    /// Binds a `bool` field to a checkbox.
+    pub fn checkbox(mut self, menu_id: MenuId, get: fn(&S) -> bool, set: fn(&mut S, bool)) -> Self {
+        self.fields
+            .push(FieldBinding::CheckBox { menu_id, get, set });
+        self
+    }
+
+    /// Binds a field to radios, each radio standing for one value of the field.
+    ///
+    /// A value without a radio leaves the radio group untouched on sync.
+    pub fn radio<T>(
+        mut self,
+        options: impl IntoIterator<Item = (MenuId, T)>,
+        get: fn(&S) -> T,
+        set: fn(&mut S, T),
+    ) -> Self
+    where
+        T: Clone + PartialEq + 'static,
+        S: 'static,
+    {
+        let (menu_ids, values): (Vec<MenuId>, Vec<T>) = options.into_iter().unzip();
+        let get_values = values.clone();
+
+        self.fields.push(FieldBinding::Radio {
+            menu_ids,
+            get: Box::new(move |settings| {
+                let value = get(settings);
+                get_values.iter().position(|option| *option == value)
+            }),
+            set: Box::new(move |settings, index| set(settings, values[index].clone())),
+        });
+        self
+    }
+
+    /// Checks the bound menu controls from the settings, and returns the menu IDs whose
+    /// state changed.
+    ///
+    /// The changes are applied as one [`MenuManager::batch`].
+    pub fn sync_to_menu<G>(&self, manager: &mut MenuManager<G>, settings: &S) -> Vec<MenuId>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        manager.with_origin(ChangeOrigin::Restore, |manager| {
+            manager.batch(|batch| {
+                for field in &self.fields {
+                    match field {
+                        FieldBinding::CheckBox { menu_id, get, .. } => {
+                            batch.set_checked(menu_id, get(settings));
+                        }
+                        FieldBinding::Radio { menu_ids, get, .. } => {
+                            if let Some(index) = get(settings) {
+                                batch.set_checked(&menu_ids[index], true);
+                            }
+                        }
+                    }
+                }
+            })
+        })
+    }
+
+    /// Writes the state of a clicked menu control back to the settings, returns `false`
+    /// if the menu control isn't bound or the settings are unchanged.
+    ///
+    /// Call it after [`MenuManager::update`], so radio groups are settled.
+    pub fn apply_action<G>(
+        &self,
+        manager: &MenuManager<G>,
+        settings: &mut S,
+        menu_id: &MenuId,
+    ) -> bool
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let is_checked = |menu_id: &MenuId| {
+            manager
+                .get_menu_item_from_id(menu_id)
+                .and_then(|menu| menu.as_check_menu())
+                .map(|check_menu| check_menu.is_checked())
+        };
+
+        for field in &self.fields {
+            match field {
+                FieldBinding::CheckBox {
+                    menu_id: bound_id,
+                    get,
+                    set,
+                } if bound_id == menu_id => {
+                    let Some(checked) = is_checked(menu_id) else {
+                        return false;
+                    };
+                    if get(settings) == checked {
+                        return false;
+                    }
+
+                    set(settings, checked);
+                    return true;
+                }
+                FieldBinding::Radio { menu_ids, get, set } if menu_ids.contains(menu_id) => {
+                    // The clicked radio may have been unchecked in favour of the default radio
+                    let Some(index) = menu_ids
+                        .iter()
+                        .position(|radio_id| is_checked(radio_id) == Some(true))
+                    else {
+                        return false;
+                    };
+                    if get(settings) == Some(index) {
+                        return false;
+                    }
+
+                    set(settings, index);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,178 @@
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::Error;
+
+/// Separates the fields of a message, command line arguments can't contain it
+const SEPARATOR: char = '\0';
+const ACK: u8 = 1;
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Guard of the first running instance of an app, see [`SingleInstance::acquire`]
+///
+/// The first instance listens on a localhost port, which it writes to a per-user file.
+/// A second instance finds the port, signals the first one with its command line
+/// arguments, and exits. Dropping the guard stops listening.
+///
+/// NOTE: Two instances started at the very same moment may both become the first one.
+///
+/// # Example
+/// ```no_run
+/// use std::sync::mpsc;
+/// use tray_controls::SingleInstance;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let Some(_instance) = SingleInstance::acquire("com.example.myapp", move |args| {
+///     let _ = sender.send(args);
+/// })
+/// .unwrap() else {
+///     // Another instance is running and was signaled
+///     return;
+/// };
+///
+/// // In the event loop, e.g. call `TrayController::attention` or `TrayController::show_menu`
+/// if let Ok(args) = receiver.try_recv() {
+///     println!("started again with {args:?}");
+/// }
+/// ```
+pub struct SingleInstance {
+    lock_path: PathBuf,
+    port: u16,
+    stop: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl SingleInstance {
+    /// Becomes the first instance of the app, or signals the running one.
+    ///
+    /// Returns `None` if another instance is running, the caller should exit. Otherwise
+    /// `on_activate` is called from a background thread with the command line arguments
+    /// (without the program) of each instance started later.
+    pub fn acquire(
+        app_name: &str,
+        on_activate: impl Fn(Vec<String>) + Send + 'static,
+    ) -> Result<Option<SingleInstance>, Error> {
+        let lock_path = lock_path(app_name);
+
+        if let Ok(port) = std::fs::read_to_string(&lock_path)
+            && let Ok(port) = port.trim().parse()
+            && signal(port, app_name).is_ok()
+        {
+            return Ok(None);
+        }
+
+        // No instance answered, the lock file is missing or stale
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let port = listener.local_addr()?.port();
+        std::fs::write(&lock_path, port.to_string())?;
+
+        trace_event!(debug, app_name, port, "single instance acquired");
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let app_name = app_name.to_owned();
+        let listener = std::thread::spawn({
+            let stop = stop.clone();
+            move || listen(listener, &app_name, &stop, on_activate)
+        });
+
+        Ok(Some(SingleInstance {
+            lock_path,
+            port,
+            stop,
+            listener: Some(listener),
+        }))
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake up the blocking accept
+        let _ = TcpStream::connect_timeout(
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, self.port)),
+            TIMEOUT,
+        );
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+
+        // A later instance may own the lock file by now
+        if std::fs::read_to_string(&self.lock_path)
+            .is_ok_and(|port| port.trim() == self.port.to_string())
+        {
+            let _ = std::fs::remove_file(&self.lock_path);
+        }
+    }
+}
+
+/// Gets the per-user lock file of an app.
+fn lock_path(app_name: &str) -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    dir.join(format!("{app_name}-{user}.instance"))
+}
+
+/// Sends the app name and the arguments to the running instance, and waits for its answer.
+fn signal(port: u16, app_name: &str) -> std::io::Result<()> {
+    let mut stream =
+        TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, port)), TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let message: Vec<String> = std::iter::once(app_name.to_owned())
+        .chain(
+            std::env::args_os()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned()),
+        )
+        .collect();
+    stream.write_all(message.join(&SEPARATOR.to_string()).as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut ack = [0];
+    stream.read_exact(&mut ack)?;
+    match ack == [ACK] {
+        true => Ok(()),
+        false => Err(std::io::Error::other("unexpected answer")),
+    }
+}
+
+fn listen(
+    listener: TcpListener,
+    app_name: &str,
+    stop: &AtomicBool,
+    on_activate: impl Fn(Vec<String>),
+) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        let _ = stream.set_read_timeout(Some(TIMEOUT));
+
+        let mut message = String::new();
+        if stream.read_to_string(&mut message).is_err() {
+            continue;
+        }
+
+        // Ignore connections that are not from another instance of the app
+        let mut fields = message.split(SEPARATOR);
+        if fields.next() != Some(app_name) {
+            continue;
+        }
+
+        let _ = stream.write_all(&[ACK]);
+        on_activate(fields.map(ToOwned::to_owned).collect());
+    }
+}
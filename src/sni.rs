@@ -0,0 +1,343 @@
+use std::hash::Hash;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use ksni::blocking::{Handle, TrayMethods};
+use ksni::menu::{CheckmarkItem, StandardItem, SubMenu};
+use muda::{Menu, MenuId, MenuItemKind};
+
+use crate::{Error, MenuControl, MenuManager};
+
+/// Tray icon exported with the StatusNotifierItem protocol, without `tray-icon`
+///
+/// An alternative Linux backend for sessions where the libappindicator path of `tray-icon`
+/// is unavailable, e.g. a Flatpak without the indicator extension. The menu is the same
+/// `muda` menu, with its controls registered in a [`MenuManager`] as usual.
+///
+/// The service runs on its own thread, so it exports a copy of the menu: call
+/// [`SniTray::refresh`] after changing the menu outside of [`SniTray::handle_pending`].
+/// Clicks wait until [`SniTray::handle_pending`] runs them with [`MenuManager::activate`].
+///
+/// # Example
+/// ```no_run
+/// use tray_controls::{MenuControl, MenuManager, SniTray};
+/// use tray_icon::menu::{Menu, MenuItem};
+///
+/// let tray_menu = Menu::new();
+/// let mut manager = MenuManager::<&str>::new();
+/// let quit = MenuItem::with_id("quit", "&Quit", true, None);
+/// tray_menu.append(&quit).unwrap();
+/// manager.insert(MenuControl::MenuItem(quit));
+///
+/// let mut tray = SniTray::spawn("com.example.myapp").unwrap();
+/// tray.set_icon_name("applications-utilities");
+/// tray.set_menu(Some(tray_menu));
+/// tray.refresh(&manager);
+///
+/// // In the event loop of the tray app
+/// tray.handle_pending(&mut manager, |menu_control| {
+///     if menu_control.is_some_and(|menu| menu.id() == "quit") {
+///         std::process::exit(0);
+///     }
+/// });
+/// ```
+pub struct SniTray {
+    handle: Handle<SniService>,
+    clicks: Receiver<MenuId>,
+    menu: Option<Menu>,
+}
+
+impl SniTray {
+    /// Registers the tray icon with the StatusNotifierWatcher of the session.
+    ///
+    /// `id` should be unique for the app and stay the same between sessions, e.g. its name.
+    /// In a sandbox the D-Bus name of the service is not owned, as Flatpak doesn't allow it.
+    pub fn spawn(id: &str) -> Result<SniTray, Error> {
+        let (sender, clicks) = mpsc::channel();
+        let service = SniService {
+            id: id.to_owned(),
+            title: id.to_owned(),
+            icon_name: String::new(),
+            icon_pixmap: Vec::new(),
+            tooltip: String::new(),
+            items: Vec::new(),
+            clicks: sender,
+        };
+
+        let sandboxed = std::path::Path::new("/.flatpak-info").exists();
+        let handle = service.disable_dbus_name(sandboxed).spawn()?;
+        trace_event!(debug, id, sandboxed, "status notifier item spawned");
+
+        Ok(SniTray {
+            handle,
+            clicks,
+            menu: None,
+        })
+    }
+
+    /// Sets the exported menu, shown once [`SniTray::refresh`] copies it.
+    pub fn set_menu(&mut self, menu: Option<Menu>) {
+        self.menu = menu;
+    }
+
+    /// Exports the current text, enabled and checked state of the menu.
+    ///
+    /// The checked state of registered menu controls is read from the menu manager, so
+    /// checkable icon menus are shown as check menus.
+    pub fn refresh<G>(&self, manager: &MenuManager<G>)
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let items = self
+            .menu
+            .as_ref()
+            .map(|menu| copy_items(menu.items(), manager))
+            .unwrap_or_default();
+        self.handle.update(|service| service.items = items);
+    }
+
+    /// Runs the clicks received since the last call, returns how many ran.
+    ///
+    /// The menu is refreshed afterwards, see [`SniTray::refresh`].
+    pub fn handle_pending<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        callback: impl Fn(Option<&MenuControl<G>>),
+    ) -> usize
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        let mut count = 0;
+        while let Ok(menu_id) = self.clicks.try_recv() {
+            trace_event!(debug, menu_id = ?menu_id, "status notifier item click");
+
+            manager.activate(&menu_id, &callback);
+            count += 1;
+        }
+
+        if count > 0 {
+            self.refresh(manager);
+        }
+        count
+    }
+
+    /// Sets the title, shown by hosts without an icon.
+    pub fn set_title(&self, title: &str) {
+        let title = title.to_owned();
+        self.handle.update(|service| service.title = title);
+    }
+
+    /// Sets the tooltip.
+    pub fn set_tooltip(&self, tooltip: &str) {
+        let tooltip = tooltip.to_owned();
+        self.handle.update(|service| service.tooltip = tooltip);
+    }
+
+    /// Sets the icon by its name in the icon theme, e.g. `"mail-unread"`.
+    pub fn set_icon_name(&self, icon_name: &str) {
+        let icon_name = icon_name.to_owned();
+        self.handle.update(|service| service.icon_name = icon_name);
+    }
+
+    /// Sets the icon from RGBA pixels, shown if no icon name is set.
+    pub fn set_icon_rgba(&self, mut rgba: Vec<u8>, width: u32, height: u32) {
+        debug_assert_eq!(rgba.len(), width as usize * height as usize * 4);
+
+        // The protocol wants ARGB
+        rgba.chunks_exact_mut(4)
+            .for_each(|pixel| pixel.rotate_right(1));
+        let icon = ksni::Icon {
+            width: width as i32,
+            height: height as i32,
+            data: rgba,
+        };
+        self.handle
+            .update(|service| service.icon_pixmap = vec![icon]);
+    }
+
+    /// Checks if the service stopped, e.g. because the D-Bus connection was lost.
+    pub fn is_closed(&self) -> bool {
+        self.handle.is_closed()
+    }
+}
+
+impl Drop for SniTray {
+    fn drop(&mut self) {
+        self.handle.shutdown().wait();
+    }
+}
+
+/// Menu item copied from the `muda` menu for the service thread
+enum SniItem {
+    Item {
+        id: MenuId,
+        text: String,
+        enabled: bool,
+        checked: Option<bool>,
+    },
+    Submenu {
+        text: String,
+        enabled: bool,
+        items: Vec<SniItem>,
+    },
+    Separator,
+}
+
+struct SniService {
+    id: String,
+    title: String,
+    icon_name: String,
+    icon_pixmap: Vec<ksni::Icon>,
+    tooltip: String,
+    items: Vec<SniItem>,
+    clicks: Sender<MenuId>,
+}
+
+impl ksni::Tray for SniService {
+    // Like the AppIndicator of `tray-icon`, a click opens the menu
+    const MENU_ON_ACTIVATE: bool = true;
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn icon_name(&self) -> String {
+        self.icon_name.clone()
+    }
+
+    fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+        self.icon_pixmap.clone()
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: self.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        self.items.iter().map(to_ksni_item).collect()
+    }
+}
+
+fn copy_items<G>(items: Vec<MenuItemKind>, manager: &MenuManager<G>) -> Vec<SniItem>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    items
+        .into_iter()
+        .map(|item| {
+            let (text, enabled, checked) = match &item {
+                MenuItemKind::Submenu(submenu) => {
+                    return SniItem::Submenu {
+                        text: submenu.text(),
+                        enabled: submenu.is_enabled(),
+                        items: copy_items(submenu.items(), manager),
+                    };
+                }
+                MenuItemKind::Predefined(predefined) if predefined.text().is_empty() => {
+                    return SniItem::Separator;
+                }
+                MenuItemKind::Predefined(predefined) => (predefined.text(), true, None),
+                MenuItemKind::MenuItem(menu_item) => {
+                    (menu_item.text(), menu_item.is_enabled(), None)
+                }
+                MenuItemKind::Icon(icon_menu) => (icon_menu.text(), icon_menu.is_enabled(), None),
+                MenuItemKind::Check(check_menu) => (
+                    check_menu.text(),
+                    check_menu.is_enabled(),
+                    Some(check_menu.is_checked()),
+                ),
+            };
+            let checked = manager
+                .get_menu_item_from_id(item.id())
+                .and_then(|menu| menu.as_check_menu())
+                .map(|check_menu| check_menu.is_checked())
+                .or(checked);
+
+            SniItem::Item {
+                id: item.id().clone(),
+                text,
+                enabled,
+                checked,
+            }
+        })
+        .collect()
+}
+
+fn to_ksni_item(item: &SniItem) -> ksni::MenuItem<SniService> {
+    match item {
+        SniItem::Item {
+            id,
+            text,
+            enabled,
+            checked: None,
+        } => StandardItem {
+            label: to_dbus_label(text),
+            enabled: *enabled,
+            activate: send_click(id),
+            ..Default::default()
+        }
+        .into(),
+        SniItem::Item {
+            id,
+            text,
+            enabled,
+            checked: Some(checked),
+        } => CheckmarkItem {
+            label: to_dbus_label(text),
+            enabled: *enabled,
+            checked: *checked,
+            activate: send_click(id),
+            ..Default::default()
+        }
+        .into(),
+        SniItem::Submenu {
+            text,
+            enabled,
+            items,
+        } => SubMenu {
+            label: to_dbus_label(text),
+            enabled: *enabled,
+            submenu: items.iter().map(to_ksni_item).collect(),
+            ..Default::default()
+        }
+        .into(),
+        SniItem::Separator => ksni::MenuItem::Separator,
+    }
+}
+
+fn send_click(menu_id: &MenuId) -> Box<dyn Fn(&mut SniService) + Send> {
+    let menu_id = menu_id.clone();
+    Box::new(move |service| {
+        let _ = service.clicks.send(menu_id.clone());
+    })
+}
+
+/// Converts the `&` mnemonic markers of `muda` into the `_` markers of D-Bus menus.
+fn to_dbus_label(text: &str) -> String {
+    let mut label = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '_' => label.push_str("__"),
+            '&' => match chars.next() {
+                Some('&') => label.push('&'),
+                Some('_') => label.push_str("__"),
+                Some(next) => {
+                    label.push('_');
+                    label.push(next);
+                }
+                None => {}
+            },
+            c => label.push(c),
+        }
+    }
+
+    label
+}
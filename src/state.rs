@@ -0,0 +1,195 @@
+use std::rc::Rc;
+
+use muda::MenuId;
+
+use crate::FxIndexMap;
+use crate::menu_key::MenuKey;
+
+/// State of a menu control in a [`MenuState`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItemState {
+    text: String,
+    enabled: bool,
+    checked: Option<bool>,
+    description: Option<String>,
+}
+
+impl MenuItemState {
+    pub(crate) fn new(text: String, enabled: bool, checked: Option<bool>) -> Self {
+        MenuItemState {
+            text,
+            enabled,
+            checked,
+            description: None,
+        }
+    }
+
+    pub(crate) fn with_description(mut self, description: Option<&str>) -> Self {
+        self.description = description.map(str::to_owned);
+        self
+    }
+
+    /// Overrides the checked state, e.g. to undo a platform toggle.
+    pub(crate) fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Gets the checked state, `None` if the menu control can't be checked.
+    pub fn is_checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    /// Gets the accessible description, see [`MenuManager::set_accessible_description`].
+    ///
+    /// [`MenuManager::set_accessible_description`]: crate::MenuManager::set_accessible_description
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Change between two [`MenuState`]s, see [`MenuState::diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateChange {
+    /// The menu control was inserted
+    Added(MenuId),
+    /// The menu control was removed
+    Removed(MenuId),
+    /// The check menu was checked or unchecked
+    CheckedChanged { menu_id: MenuId, checked: bool },
+    /// The menu control was enabled or disabled
+    EnabledChanged { menu_id: MenuId, enabled: bool },
+    /// The text of the menu control changed
+    TextChanged {
+        menu_id: MenuId,
+        before: String,
+        after: String,
+    },
+}
+
+/// Snapshot of the menu controls, taken with [`MenuManager::state`]
+///
+/// # Example
+/// ```
+/// use std::rc::Rc;
+/// use tray_controls::{CheckMenuKind, MenuControl, MenuManager, MenuState, StateChange};
+/// use tray_icon::menu::{CheckMenuItem, MenuId};
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let autostart = Rc::new(CheckMenuItem::with_id("autostart", "Autostart", true, false, None));
+/// manager.insert(MenuControl::CheckMenu(CheckMenuKind::Separate(autostart.clone())));
+///
+/// let before = manager.state();
+/// autostart.set_checked(true);
+/// let after = manager.state();
+///
+/// assert_eq!(
+///     MenuState::diff(&before, &after),
+///     [StateChange::CheckedChanged { menu_id: MenuId::new("autostart"), checked: true }]
+/// );
+/// ```
+///
+/// [`MenuManager::state`]: crate::MenuManager::state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuState {
+    /// The menu IDs are shared with the menu manager, a snapshot doesn't copy them
+    items: FxIndexMap<Rc<MenuId>, MenuItemState>,
+}
+
+impl MenuState {
+    pub(crate) fn new(items: FxIndexMap<Rc<MenuId>, MenuItemState>) -> Self {
+        MenuState { items }
+    }
+
+    pub fn get(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&MenuItemState> {
+        self.items.get(&MenuKey::new(menu_id))
+    }
+
+    /// Iterates over the menu control states in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = (&MenuId, &MenuItemState)> {
+        self.items
+            .iter()
+            .map(|(menu_id, item)| (menu_id.as_ref(), item))
+    }
+
+    /// Lists what changed from `before` to `after`.
+    ///
+    /// Removed menu controls come first, then the changes in the order of `after`.
+    pub fn diff(before: &MenuState, after: &MenuState) -> Vec<StateChange> {
+        let mut changes: Vec<StateChange> = before
+            .items
+            .keys()
+            .filter(|menu_id| !after.items.contains_key(menu_id.as_ref()))
+            .map(|menu_id| StateChange::Removed(menu_id.as_ref().clone()))
+            .collect();
+
+        for (menu_id, after_item) in &after.items {
+            let Some(before_item) = before.items.get(menu_id.as_ref()) else {
+                changes.push(StateChange::Added(menu_id.as_ref().clone()));
+                continue;
+            };
+
+            if before_item.text != after_item.text {
+                changes.push(StateChange::TextChanged {
+                    menu_id: menu_id.as_ref().clone(),
+                    before: before_item.text.clone(),
+                    after: after_item.text.clone(),
+                });
+            }
+
+            if before_item.enabled != after_item.enabled {
+                changes.push(StateChange::EnabledChanged {
+                    menu_id: menu_id.as_ref().clone(),
+                    enabled: after_item.enabled,
+                });
+            }
+
+            if let Some(checked) = after_item.checked
+                && before_item.checked != after_item.checked
+            {
+                changes.push(StateChange::CheckedChanged {
+                    menu_id: menu_id.as_ref().clone(),
+                    checked,
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Snapshot of the check menus of a group, taken with [`MenuManager::snapshot_group`]
+///
+/// [`MenuManager::snapshot_group`]: crate::MenuManager::snapshot_group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupSnapshot<G> {
+    group: G,
+    items: FxIndexMap<MenuId, MenuItemState>,
+}
+
+impl<G> GroupSnapshot<G> {
+    pub(crate) fn new(group: G, items: FxIndexMap<MenuId, MenuItemState>) -> Self {
+        GroupSnapshot { group, items }
+    }
+
+    pub fn group(&self) -> &G {
+        &self.group
+    }
+
+    pub fn get(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&MenuItemState> {
+        self.items.get(&MenuKey::new(menu_id))
+    }
+
+    /// Iterates over the check menu states in group order.
+    pub fn iter(&self) -> impl Iterator<Item = (&MenuId, &MenuItemState)> {
+        self.items.iter()
+    }
+}
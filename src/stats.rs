@@ -0,0 +1,112 @@
+use std::time::SystemTime;
+
+use muda::MenuId;
+
+use crate::FxIndexMap;
+use crate::menu_key::MenuKey;
+
+/// Click count and last click time of a menu control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemStats {
+    clicks: u64,
+    last_clicked: SystemTime,
+}
+
+impl ItemStats {
+    pub fn clicks(&self) -> u64 {
+        self.clicks
+    }
+
+    pub fn last_clicked(&self) -> SystemTime {
+        self.last_clicked
+    }
+}
+
+/// Usage statistics of the menu controls, see [`MenuManager::set_stats_enabled`]
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuControl, MenuManager};
+/// use tray_icon::menu::{MenuId, MenuItem};
+///
+/// let mut manager = MenuManager::<()>::new();
+/// manager.insert(MenuControl::MenuItem(MenuItem::with_id("open", "Open", true, None)));
+/// manager.set_stats_enabled(true);
+///
+/// manager.update(&MenuId::new("open"), |_| {});
+/// manager.update(&MenuId::new("open"), |_| {});
+///
+/// let stats = manager.stats().unwrap();
+/// assert_eq!(stats.get(&MenuId::new("open")).unwrap().clicks(), 2);
+/// assert_eq!(stats.total_clicks(), 2);
+/// ```
+///
+/// [`MenuManager::set_stats_enabled`]: crate::MenuManager::set_stats_enabled
+#[derive(Debug, Clone, Default)]
+pub struct ClickStats {
+    items: FxIndexMap<MenuId, ItemStats>,
+}
+
+impl ClickStats {
+    /// Gets the statistics of a menu control, `None` if it was never clicked.
+    pub fn get(&self, menu_id: &(impl AsRef<str> + ?Sized)) -> Option<&ItemStats> {
+        self.items.get(&MenuKey::new(menu_id))
+    }
+
+    /// Iterates over the clicked menu controls, in order of their first click.
+    pub fn iter(&self) -> impl Iterator<Item = (&MenuId, &ItemStats)> {
+        self.items.iter()
+    }
+
+    pub fn total_clicks(&self) -> u64 {
+        self.items.values().map(|item| item.clicks).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub(crate) fn record(&mut self, menu_id: &MenuId) {
+        let now = SystemTime::now();
+        // The menu ID is only copied for the first click
+        if let Some(item) = self.items.get_mut(menu_id) {
+            item.clicks += 1;
+            item.last_clicked = now;
+        } else {
+            self.items.insert(
+                menu_id.clone(),
+                ItemStats {
+                    clicks: 1,
+                    last_clicked: now,
+                },
+            );
+        }
+    }
+
+    /// Serializes the statistics, with the last click times as Unix milliseconds (**requires the `serde` feature**).
+    ///
+    /// ```json
+    /// { "open": { "clicks": 2, "last_clicked": 1760000000000 } }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Value {
+        self.items
+            .iter()
+            .map(|(menu_id, item)| {
+                let last_clicked = item
+                    .last_clicked
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+                (
+                    menu_id.0.clone(),
+                    serde_json::json!({
+                        "clicks": item.clicks,
+                        "last_clicked": last_clicked,
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
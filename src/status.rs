@@ -0,0 +1,104 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use muda::{MenuId, MenuItem};
+
+use crate::MenuControl;
+
+type Refresh = Box<dyn Fn() -> String>;
+
+struct PeriodicRefresh {
+    interval: Duration,
+    refresh: Refresh,
+    last_refresh: Option<Instant>,
+}
+
+/// Read-only status line in the tray menu, e.g. "CPU: 42% | RAM: 63%"
+///
+/// The underlying `MenuItem` is disabled, so it can't be clicked. Update it with
+/// [`StatusItem::set_value`], or register a refresh callback that is run by
+/// [`StatusItem::tick`] whenever its interval has elapsed.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use tray_controls::{MenuManager, StatusItem};
+///
+/// let mut manager = MenuManager::<&str>::new();
+///
+/// let mut status = StatusItem::new("cpu_status", "CPU: -")
+///     .with_refresh(Duration::from_secs(2), || format!("CPU: {}%", 42));
+/// manager.insert(status.menu_control());
+///
+/// // Call periodically, e.g. from your event loop
+/// status.tick();
+/// assert_eq!(status.menu_item().text(), "CPU: 42%");
+/// ```
+pub struct StatusItem {
+    menu_item: MenuItem,
+    refresh: Option<PeriodicRefresh>,
+}
+
+impl StatusItem {
+    pub fn new<I: Into<MenuId>>(id: I, text: &str) -> Self {
+        StatusItem {
+            menu_item: MenuItem::with_id(id, text, false, None),
+            refresh: None,
+        }
+    }
+
+    /// Sets a callback producing the text, run by [`StatusItem::tick`] every `interval`.
+    pub fn with_refresh(
+        mut self,
+        interval: Duration,
+        refresh: impl Fn() -> String + 'static,
+    ) -> Self {
+        self.refresh = Some(PeriodicRefresh {
+            interval,
+            refresh: Box::new(refresh),
+            last_refresh: None,
+        });
+        self
+    }
+
+    /// Sets the displayed value.
+    pub fn set_value(&self, value: impl Display) {
+        let text = value.to_string();
+        if self.menu_item.text() != text {
+            self.menu_item.set_text(text);
+        }
+    }
+
+    /// Runs the refresh callback if its interval has elapsed, returns `true` if it was run.
+    pub fn tick(&mut self) -> bool {
+        let Some(periodic) = &mut self.refresh else {
+            return false;
+        };
+
+        let now = Instant::now();
+        if periodic
+            .last_refresh
+            .is_some_and(|last_refresh| now.duration_since(last_refresh) < periodic.interval)
+        {
+            return false;
+        }
+
+        periodic.last_refresh = Some(now);
+        let text = (periodic.refresh)();
+        self.set_value(text);
+        true
+    }
+
+    pub fn id(&self) -> &MenuId {
+        self.menu_item.id()
+    }
+
+    pub fn menu_item(&self) -> &MenuItem {
+        &self.menu_item
+    }
+
+    /// Gets the menu control to insert into the menu manager.
+    pub fn menu_control<G>(&self) -> MenuControl<G> {
+        MenuControl::MenuItem(self.menu_item.clone())
+    }
+}
@@ -0,0 +1,171 @@
+use std::hash::Hash;
+
+use muda::{MenuId, MenuItem};
+
+use crate::{Error, MenuControl, MenuManager};
+
+type OnChange = Box<dyn Fn(i64)>;
+
+/// Numeric stepper: a display item plus "Increase / Decrease / Reset" items
+///
+/// The four menu items behave as one logical control for settings like volume,
+/// opacity or intervals. The value is clamped to the range, and the increase and
+/// decrease items are disabled at the bounds.
+///
+/// Menu IDs are built from the `id_prefix`: `{id_prefix}value`, `{id_prefix}increase`,
+/// `{id_prefix}decrease` and `{id_prefix}reset`.
+///
+/// # Example
+/// ```
+/// use tray_controls::{MenuManager, MenuParent, Stepper};
+/// use tray_icon::menu::{MenuId, Submenu};
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let submenu = Submenu::new("Volume", true);
+/// manager.insert_parent(MenuParent::Submenu(submenu.clone()));
+///
+/// let mut volume = Stepper::new("volume:", "Volume", 50)
+///     .with_range(0, 100)
+///     .with_step(10);
+/// volume.on_change(|value| println!("volume: {value}"));
+/// volume.insert_into(&mut manager, submenu.id()).unwrap();
+///
+/// assert_eq!(volume.handle(&MenuId::new("volume:increase")), Some(60));
+/// ```
+pub struct Stepper {
+    label: String,
+    value_item: MenuItem,
+    increase_item: MenuItem,
+    decrease_item: MenuItem,
+    reset_item: MenuItem,
+    value: i64,
+    default: i64,
+    step: i64,
+    min: i64,
+    max: i64,
+    on_change: Vec<OnChange>,
+}
+
+impl Stepper {
+    pub fn new(id_prefix: &str, label: &str, default: i64) -> Self {
+        let stepper = Stepper {
+            label: label.to_owned(),
+            value_item: MenuItem::with_id(format!("{id_prefix}value"), "", false, None),
+            increase_item: MenuItem::with_id(
+                format!("{id_prefix}increase"),
+                "Increase",
+                true,
+                None,
+            ),
+            decrease_item: MenuItem::with_id(
+                format!("{id_prefix}decrease"),
+                "Decrease",
+                true,
+                None,
+            ),
+            reset_item: MenuItem::with_id(format!("{id_prefix}reset"), "Reset", true, None),
+            value: default,
+            default,
+            step: 1,
+            min: i64::MIN,
+            max: i64::MAX,
+            on_change: Vec::new(),
+        };
+        stepper.refresh();
+        stepper
+    }
+
+    /// Sets the inclusive range of the value.
+    pub fn with_range(mut self, min: i64, max: i64) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.default = self.default.clamp(self.min, self.max);
+        self.value = self.value.clamp(self.min, self.max);
+        self.refresh();
+        self
+    }
+
+    /// Sets how much the value changes per increase or decrease.
+    pub fn with_step(mut self, step: i64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Adds a callback called with the new value whenever it changes.
+    pub fn on_change(&mut self, on_change: impl Fn(i64) + 'static) {
+        self.on_change.push(Box::new(on_change));
+    }
+
+    /// Inserts the display, increase, decrease and reset items into a registered parent menu.
+    pub fn insert_into<G>(
+        &self,
+        manager: &mut MenuManager<G>,
+        parent_id: &MenuId,
+    ) -> Result<(), Error>
+    where
+        G: Clone + Eq + Hash + PartialEq,
+    {
+        for menu_item in [
+            &self.value_item,
+            &self.increase_item,
+            &self.decrease_item,
+            &self.reset_item,
+        ] {
+            manager.insert_into(parent_id, MenuControl::MenuItem(menu_item.clone()), None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles a menu click, returns the new value if the menu ID belongs to this stepper.
+    pub fn handle(&mut self, menu_id: &MenuId) -> Option<i64> {
+        let value = if menu_id == self.increase_item.id() {
+            self.value.saturating_add(self.step)
+        } else if menu_id == self.decrease_item.id() {
+            self.value.saturating_sub(self.step)
+        } else if menu_id == self.reset_item.id() {
+            self.default
+        } else {
+            return None;
+        };
+
+        self.set_value(value);
+        Some(self.value)
+    }
+
+    /// Sets the value, clamped to the range.
+    pub fn set_value(&mut self, value: i64) {
+        let value = value.clamp(self.min, self.max);
+        if value == self.value {
+            return;
+        }
+
+        self.value = value;
+        self.refresh();
+        self.on_change.iter().for_each(|on_change| on_change(value));
+    }
+
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    /// Checks if the menu ID belongs to this stepper.
+    pub fn contains(&self, menu_id: &MenuId) -> bool {
+        [
+            &self.value_item,
+            &self.increase_item,
+            &self.decrease_item,
+            &self.reset_item,
+        ]
+        .iter()
+        .any(|menu_item| menu_item.id() == menu_id)
+    }
+
+    fn refresh(&self) {
+        self.value_item
+            .set_text(format!("{}: {}", self.label, self.value));
+        self.increase_item.set_enabled(self.value < self.max);
+        self.decrease_item.set_enabled(self.value > self.min);
+        self.reset_item.set_enabled(self.value != self.default);
+    }
+}
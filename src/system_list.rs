@@ -0,0 +1,263 @@
+use std::hash::Hash;
+use std::io;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use muda::{MenuId, Submenu};
+
+use crate::{Error, FxIndexMap, MenuManager, ScopedIds, SubmenuBinding};
+
+/// List of the system, e.g. audio output devices or displays (**requires the `system-lists` feature**)
+///
+/// Implement it for other OS-sourced lists, [`AudioOutputs`] and [`Displays`] are built in.
+pub trait SystemList {
+    type Item: Clone;
+
+    /// Lists the current items.
+    fn list(&self) -> Result<Vec<Self::Item>, Error>;
+
+    /// Gets the stable key of an item, used in its menu ID, and its label.
+    fn entry(item: &Self::Item) -> (String, String);
+}
+
+/// Item of the built-in system lists
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SystemDevice {
+    /// Stable name of the device, e.g. `alsa_output.pci-0000_00_1f.3.analog-stereo` or `HDMI-1`
+    pub id: String,
+    /// Human-readable name of the device
+    pub name: String,
+}
+
+/// Radio group of a submenu, populated from a [`SystemList`] and kept refreshed
+///
+/// The radios are synchronized like a [`SubmenuBinding`], so the checked radio survives
+/// a refresh as long as its item is still listed. Their menu IDs are `{submenu}/{key}`.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use tray_controls::{AudioOutputs, MenuManager, SystemListMenu};
+/// use tray_icon::menu::Submenu;
+///
+/// let mut manager = MenuManager::<&str>::new();
+/// let submenu = Submenu::with_id("outputs", "Output Device", true);
+///
+/// let mut outputs = SystemListMenu::new(AudioOutputs, submenu, "output")
+///     .with_interval(Duration::from_secs(5));
+/// outputs.refresh(&mut manager).unwrap();
+///
+/// // Call periodically, e.g. from your event loop
+/// outputs.tick(&mut manager).unwrap();
+///
+/// // In the menu event handler, after `MenuManager::update`
+/// # let clicked_id = tray_icon::menu::MenuId::new("outputs/hdmi");
+/// if let Some(device) = outputs.handle(&clicked_id) {
+///     println!("switch to {}", device.name);
+/// }
+/// ```
+pub struct SystemListMenu<L: SystemList, G> {
+    list: L,
+    binding: SubmenuBinding<(MenuId, String), G>,
+    items: FxIndexMap<MenuId, L::Item>,
+    interval: Option<Duration>,
+    last_refresh: Option<Instant>,
+}
+
+impl<L, G> SystemListMenu<L, G>
+where
+    L: SystemList,
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new(list: L, submenu: Submenu, group: G) -> Self {
+        SystemListMenu {
+            list,
+            binding: SubmenuBinding::new(submenu, |entry: &(MenuId, String)| entry.clone())
+                .with_radio_group(group),
+            items: FxIndexMap::default(),
+            interval: None,
+            last_refresh: None,
+        }
+    }
+
+    /// Sets how often [`SystemListMenu::tick`] lists the items again.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn submenu(&self) -> &Submenu {
+        self.binding.submenu()
+    }
+
+    /// Lists the items, and synchronizes the radios with them.
+    pub fn refresh(&mut self, manager: &mut MenuManager<G>) -> Result<(), Error> {
+        self.last_refresh = Some(Instant::now());
+        let items = self.list.list()?;
+
+        let scope = ScopedIds::new(self.binding.submenu().id().as_ref());
+        self.items = items
+            .into_iter()
+            .map(|item| (scope.id(&L::entry(&item).0), item))
+            .collect();
+
+        let entries: Vec<(MenuId, String)> = self
+            .items
+            .iter()
+            .map(|(menu_id, item)| (menu_id.clone(), L::entry(item).1))
+            .collect();
+        self.binding.sync(manager, &entries)
+    }
+
+    /// Refreshes if the interval has elapsed, returns `true` if it did.
+    pub fn tick(&mut self, manager: &mut MenuManager<G>) -> Result<bool, Error> {
+        let due = match (self.interval, self.last_refresh) {
+            (Some(interval), Some(last_refresh)) => last_refresh.elapsed() >= interval,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if due {
+            self.refresh(manager)?;
+        }
+        Ok(due)
+    }
+
+    /// Gets the item of a clicked radio, `None` if the menu ID is not one of the radios.
+    pub fn handle(&self, menu_id: &MenuId) -> Option<&L::Item> {
+        self.items.get(menu_id)
+    }
+
+    /// Gets the item of the checked radio.
+    pub fn selected(&self, manager: &MenuManager<G>) -> Option<&L::Item> {
+        self.items.iter().find_map(|(menu_id, item)| {
+            manager
+                .get_menu_item_from_id(menu_id)
+                .and_then(|menu| menu.as_check_menu())
+                .is_some_and(|check_menu| check_menu.is_checked())
+                .then_some(item)
+        })
+    }
+
+    /// Checks the radio of an item, returns `false` if it is not listed.
+    pub fn select(&self, manager: &mut MenuManager<G>, key: &str) -> bool {
+        let menu_id = ScopedIds::new(self.binding.submenu().id().as_ref()).id(key);
+        if !self.items.contains_key(&menu_id) {
+            return false;
+        }
+
+        manager.batch(|batch| {
+            batch.set_checked(&menu_id, true);
+        });
+        true
+    }
+}
+
+/// Audio output devices
+///
+/// ## Platform-specific:
+///
+/// - **Linux:** The sinks of PulseAudio or PipeWire, listed by `pactl`.
+/// - **Windows / macOS:** Unsupported, listing fails.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioOutputs;
+
+impl SystemList for AudioOutputs {
+    type Item = SystemDevice;
+
+    fn list(&self) -> Result<Vec<SystemDevice>, Error> {
+        if cfg!(any(target_os = "windows", target_os = "macos")) {
+            return Err(unsupported());
+        }
+
+        Ok(parse_pactl_sinks(&run("pactl", &["list", "sinks"])?))
+    }
+
+    fn entry(device: &SystemDevice) -> (String, String) {
+        (device.id.clone(), device.name.clone())
+    }
+}
+
+/// Connected displays
+///
+/// ## Platform-specific:
+///
+/// - **Linux:** The monitors of X11, listed by `xrandr`.
+/// - **Windows / macOS:** Unsupported, listing fails.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Displays;
+
+impl SystemList for Displays {
+    type Item = SystemDevice;
+
+    fn list(&self) -> Result<Vec<SystemDevice>, Error> {
+        if cfg!(any(target_os = "windows", target_os = "macos")) {
+            return Err(unsupported());
+        }
+
+        Ok(parse_xrandr_monitors(&run("xrandr", &["--listmonitors"])?))
+    }
+
+    fn entry(device: &SystemDevice) -> (String, String) {
+        (device.id.clone(), device.name.clone())
+    }
+}
+
+fn unsupported() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the system list is not supported on this platform",
+    ))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, Error> {
+    // pactl translates its field names, only the messages go untranslated so the
+    // character set of the labels is kept
+    let mut command = Command::new(program);
+    command.args(args).env("LC_MESSAGES", "C");
+    if let Some(lc_all) = std::env::var_os("LC_ALL") {
+        command.env_remove("LC_ALL").env("LC_CTYPE", lc_all);
+    }
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(Error::Io(io::Error::other(format!("`{program}` failed"))));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses the `Name:` and `Description:` of each sink of `pactl list sinks`.
+fn parse_pactl_sinks(output: &str) -> Vec<SystemDevice> {
+    let mut devices = Vec::new();
+    let mut id = None;
+
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Name:") {
+            id = Some(name.trim().to_owned());
+        } else if let Some(description) = line.strip_prefix("Description:")
+            && let Some(id) = id.take()
+        {
+            devices.push(SystemDevice {
+                id,
+                name: description.trim().to_owned(),
+            });
+        }
+    }
+
+    devices
+}
+
+/// Parses the monitor lines of `xrandr --listmonitors`, e.g. ` 0: +*eDP-1 1920/344x1080/194+0+0  eDP-1`.
+fn parse_xrandr_monitors(output: &str) -> Vec<SystemDevice> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let name = line.split_whitespace().last()?;
+            Some(SystemDevice {
+                id: name.to_owned(),
+                name: name.to_owned(),
+            })
+        })
+        .collect()
+}
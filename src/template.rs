@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+/// Renders a template like `"Download: {speed}"`.
+///
+/// Placeholders without a variable are kept as they are.
+pub(crate) fn render_template(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        match vars.get(&rest[1..end]) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Checks if a template references a variable.
+pub(crate) fn references_var(template: &str, name: &str) -> bool {
+    template.contains(&format!("{{{name}}}"))
+}
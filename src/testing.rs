@@ -0,0 +1,120 @@
+//! Drives a [`MenuManager`] like real menu events, without spawning a tray icon
+//!
+//! # Example
+//! ```
+//! use std::rc::Rc;
+//! use tray_controls::testing::TestDriver;
+//! use tray_controls::{CheckMenuKind, MenuControl, MenuManager};
+//! use tray_icon::menu::{CheckMenuItem, MenuId};
+//!
+//! let mut manager = MenuManager::new();
+//! let default_id = Some(Rc::new(MenuId::new("system")));
+//! for (id, checked) in [("system", true), ("dark", false)] {
+//!     let radio = CheckMenuItem::with_id(id, id, true, checked, None);
+//!     manager.insert(MenuControl::CheckMenu(CheckMenuKind::Radio(
+//!         Rc::new(radio),
+//!         default_id.clone(),
+//!         "theme",
+//!     )));
+//! }
+//!
+//! let mut driver = TestDriver::new(&mut manager);
+//!
+//! driver.click(&MenuId::new("dark"));
+//! driver.assert_selected(&"theme", &MenuId::new("dark"));
+//!
+//! // Clicking the checked radio unchecks it, and the default radio is checked back
+//! driver.click(&MenuId::new("dark"));
+//! driver.assert_selected(&"theme", &MenuId::new("system"));
+//! driver.assert_unchecked(&MenuId::new("dark"));
+//! ```
+
+use std::cell::RefCell;
+use std::hash::Hash;
+
+use muda::MenuId;
+
+use crate::MenuManager;
+
+/// Simulated menu clicks on a [`MenuManager`]
+pub struct TestDriver<'a, G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    manager: &'a mut MenuManager<G>,
+}
+
+impl<'a, G> TestDriver<'a, G>
+where
+    G: Clone + Eq + Hash + PartialEq,
+{
+    pub fn new(manager: &'a mut MenuManager<G>) -> Self {
+        TestDriver { manager }
+    }
+
+    /// Clicks a menu control, returns the menu ID the update callback received.
+    ///
+    /// See [`MenuManager::activate`], a disabled or unknown menu control produces no event
+    /// at all.
+    pub fn click(&mut self, menu_id: &MenuId) -> Option<MenuId> {
+        let callback_id = RefCell::new(None);
+        self.manager.activate(menu_id, |menu| {
+            *callback_id.borrow_mut() = menu.map(|menu| menu.id().clone());
+        });
+
+        callback_id.into_inner()
+    }
+
+    /// Gets the driven menu manager.
+    pub fn manager(&self) -> &MenuManager<G> {
+        self.manager
+    }
+
+    /// Asserts that a check menu is checked.
+    #[track_caller]
+    pub fn assert_checked(&self, menu_id: &(impl AsRef<str> + ?Sized)) {
+        let menu_id = menu_id.as_ref();
+        assert_eq!(
+            self.is_checked(menu_id),
+            Some(true),
+            "expected {menu_id:?} to be checked"
+        );
+    }
+
+    /// Asserts that a check menu is unchecked.
+    #[track_caller]
+    pub fn assert_unchecked(&self, menu_id: &(impl AsRef<str> + ?Sized)) {
+        let menu_id = menu_id.as_ref();
+        assert_eq!(
+            self.is_checked(menu_id),
+            Some(false),
+            "expected {menu_id:?} to be unchecked"
+        );
+    }
+
+    /// Asserts that a radio is the only checked radio of its group.
+    #[track_caller]
+    pub fn assert_selected(&self, group_id: &G, menu_id: &MenuId) {
+        let checked: Vec<&MenuId> = self
+            .manager
+            .get_check_items_from_grouped(group_id)
+            .into_iter()
+            .flatten()
+            .filter(|(_, check_menu)| check_menu.is_checked())
+            .map(|(checked_id, _)| checked_id.as_ref())
+            .collect();
+
+        assert_eq!(
+            checked,
+            [menu_id],
+            "expected {menu_id:?} to be the only checked radio of its group"
+        );
+    }
+
+    fn is_checked(&self, menu_id: &str) -> Option<bool> {
+        self.manager
+            .get_menu_item_from_id(menu_id)
+            .and_then(|menu| menu.as_check_menu())
+            .map(|check_menu| check_menu.is_checked())
+    }
+}
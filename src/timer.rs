@@ -0,0 +1,163 @@
+use std::time::{Duration, Instant};
+
+use muda::{MenuId, MenuItem};
+
+use crate::{Action, MenuControl};
+
+/// Countdown in the tray menu, e.g. a pomodoro timer showing "Focus 24:59"
+///
+/// The label is followed by the remaining time. Insert it with
+/// [`MenuManager::insert_timer`](crate::MenuManager::insert_timer), then control it with
+/// [`MenuManager::start_timer`](crate::MenuManager::start_timer),
+/// [`MenuManager::pause_timer`](crate::MenuManager::pause_timer) and
+/// [`MenuManager::reset_timer`](crate::MenuManager::reset_timer).
+/// [`MenuManager::tick_timers`](crate::MenuManager::tick_timers) updates the labels,
+/// and runs the action of the expired timers.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use tray_controls::{Action, MenuAction, MenuManager, TimerItem};
+/// use tray_icon::menu::MenuId;
+///
+/// let mut manager = MenuManager::<()>::new();
+/// let timer = TimerItem::new("focus", "Focus", Duration::from_secs(25 * 60))
+///     .with_action(Action::Custom("focus_done".into()));
+/// manager.insert_timer(timer);
+///
+/// let focus = MenuId::new("focus");
+/// assert_eq!(manager.get_menu_item_from_id(&focus).unwrap().text(), "Focus 25:00");
+/// manager.start_timer(&focus);
+///
+/// // Call periodically, e.g. from your event loop until `MenuManager::next_schedule`
+/// for (menu_id, action) in manager.tick_timers() {
+///     if let Ok(MenuAction::Custom(name)) = action {
+///         println!("{name}, take a break");
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TimerItem {
+    menu_item: MenuItem,
+    label: String,
+    duration: Duration,
+    action: Option<Action>,
+    /// Remaining time when the countdown was last started or paused
+    remaining: Duration,
+    /// When the countdown was started, `None` while it is stopped
+    started: Option<Instant>,
+}
+
+impl TimerItem {
+    pub fn new<I: Into<MenuId>>(id: I, label: &str, duration: Duration) -> Self {
+        TimerItem {
+            menu_item: MenuItem::with_id(id, format_text(label, duration), true, None),
+            label: label.to_owned(),
+            duration,
+            action: None,
+            remaining: duration,
+            started: None,
+        }
+    }
+
+    /// Sets the action run when the countdown expires, e.g. [`Action::Custom`].
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn id(&self) -> &MenuId {
+        self.menu_item.id()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Gets the menu control to insert into the menu manager.
+    pub fn menu_control<G>(&self) -> MenuControl<G> {
+        MenuControl::MenuItem(self.menu_item.clone())
+    }
+
+    pub(crate) fn action(&self) -> Option<&Action> {
+        self.action.as_ref()
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub(crate) fn remaining(&self, now: Instant) -> Duration {
+        match self.started {
+            Some(started) => self
+                .remaining
+                .saturating_sub(now.saturating_duration_since(started)),
+            None => self.remaining,
+        }
+    }
+
+    pub(crate) fn text(&self, now: Instant) -> String {
+        format_text(&self.label, self.remaining(now))
+    }
+
+    /// Starts or resumes the countdown, an expired countdown starts over.
+    pub(crate) fn start(&mut self, now: Instant) {
+        if self.started.is_some() {
+            return;
+        }
+
+        if self.remaining.is_zero() {
+            self.remaining = self.duration;
+        }
+        self.started = Some(now);
+    }
+
+    pub(crate) fn pause(&mut self, now: Instant) {
+        self.remaining = self.remaining(now);
+        self.started = None;
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.remaining = self.duration;
+        self.started = None;
+    }
+
+    /// Stops the countdown if it expired at `now`, returns `true` if it did.
+    pub(crate) fn expire(&mut self, now: Instant) -> bool {
+        if self.started.is_none() || !self.remaining(now).is_zero() {
+            return false;
+        }
+
+        self.remaining = Duration::ZERO;
+        self.started = None;
+        true
+    }
+
+    /// Gets when the shown remaining time changes next, `None` while it is stopped.
+    pub(crate) fn next_update(&self, now: Instant) -> Option<Instant> {
+        self.started?;
+
+        // The shown seconds are rounded up, so they change at each whole second left
+        let remaining = self.remaining(now);
+        let wait = match remaining.subsec_nanos() {
+            0 => remaining.min(Duration::from_secs(1)),
+            nanos => Duration::from_nanos(u64::from(nanos)),
+        };
+        Some(now + wait)
+    }
+}
+
+/// Formats the label and the remaining time, e.g. `Focus 24:59` or `Backup 1:00:00`.
+fn format_text(label: &str, remaining: Duration) -> String {
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    let time = match hours {
+        0 => format!("{minutes:02}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    };
+    match label.is_empty() {
+        true => time,
+        false => format!("{label} {time}"),
+    }
+}
@@ -0,0 +1,100 @@
+use muda::{MenuEvent, MenuId};
+
+/// Strongly typed menu ID, converted to and from the `MenuId` of tray-icon
+///
+/// Implemented for every type that converts into a `MenuId` and back, e.g. an enum
+/// declared with [`menu_ids!`](crate::menu_ids).
+///
+/// # Example
+/// ```
+/// use tray_controls::{TypedId, menu_ids};
+/// use tray_icon::menu::{MenuId, MenuItem};
+///
+/// menu_ids! {
+///     #[derive(Debug)]
+///     pub enum AppId {
+///         Open = "open",
+///         Quit = "quit",
+///     }
+/// }
+///
+/// let quit = MenuItem::with_id(AppId::Quit, "Quit", true, None);
+///
+/// // Handle menu clicks
+/// let click_menu_id = quit.id().clone();
+///
+/// match AppId::from_menu_id(&click_menu_id) {
+///     Some(AppId::Open) => println!("Open"),
+///     Some(AppId::Quit) => println!("Quit"),
+///     None => {}
+/// }
+/// ```
+pub trait TypedId: Clone + Into<MenuId> + TryFrom<MenuId> {
+    fn menu_id(&self) -> MenuId {
+        self.clone().into()
+    }
+
+    /// Converts a menu ID, `None` if it is not one of the typed IDs.
+    fn from_menu_id(menu_id: &MenuId) -> Option<Self> {
+        Self::try_from(menu_id.clone()).ok()
+    }
+
+    /// Converts the menu ID of a menu event.
+    fn from_event(event: &MenuEvent) -> Option<Self> {
+        Self::from_menu_id(event.id())
+    }
+}
+
+impl<T> TypedId for T where T: Clone + Into<MenuId> + TryFrom<MenuId> {}
+
+/// Declares an enum of menu IDs implementing [`TypedId`]
+///
+/// Each unit variant is mapped to a string menu ID. The enum derives `Clone`, `Copy`,
+/// `PartialEq`, `Eq` and `Hash`, and converts into a `MenuId` through `Display`. The
+/// conversion back fails with the unknown `MenuId`.
+///
+/// See [`TypedId`] for an example.
+#[macro_export]
+macro_rules! menu_ids {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $id:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant),+
+        }
+
+        impl $name {
+            /// All the menu IDs, in declaration order.
+            pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $id),+
+                }
+            }
+        }
+
+        // tray-icon converts every `ToString` into a `MenuId`
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::core::convert::TryFrom<$crate::__MenuId> for $name {
+            type Error = $crate::__MenuId;
+
+            fn try_from(menu_id: $crate::__MenuId) -> ::core::result::Result<Self, Self::Error> {
+                match menu_id.as_ref() {
+                    $($id => ::core::result::Result::Ok($name::$variant),)+
+                    _ => ::core::result::Result::Err(menu_id),
+                }
+            }
+        }
+    };
+}